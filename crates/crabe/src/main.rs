@@ -7,7 +7,7 @@ use crabe_framework::component::{
 };
 use crabe_framework::config::CommonConfig;
 use crabe_framework::data::output::FeedbackMap;
-use crabe_framework::data::tool::ToolCommands;
+use crabe_framework::data::tool::GuardData;
 use crabe_framework::data::world::World;
 use crabe_guard::pipeline::{GuardConfig, GuardPipeline};
 use crabe_io::pipeline::input::{InputConfig, InputPipeline};
@@ -135,17 +135,24 @@ pub struct System {
 impl System {
     pub fn run(&mut self, _refresh_rate: Duration) {
         let mut feedback: FeedbackMap = Default::default();
+        let mut guard_data: GuardData = Default::default();
 
         while self.running.load(Ordering::SeqCst) {
             let timer = Instant::now();
             let receive_data = self.input_component.step(&mut feedback);
             self.filter_component.step(receive_data, &mut self.world);
             let (mut command_map, mut tool_data) = self.decision_component.step(&self.world);
-            self.tool_component
+            // the guards run after the tools, what they reported is sent on the next step
+            tool_data.guards = std::mem::take(&mut guard_data);
+            let mut tool_commands = self
+                .tool_component
                 .step(&self.world, &mut tool_data, &mut command_map);
+            self.decision_component
+                .handle_tool_commands(&mut tool_commands);
             self.guard_component
-                .step(&self.world, &mut command_map, &mut ToolCommands);
-            feedback = self.output_component.step(command_map, ToolCommands);
+                .step(&self.world, &mut command_map, &mut guard_data, &mut tool_commands);
+            self.decision_component.handle_guard_data(&guard_data);
+            feedback = self.output_component.step(command_map, tool_commands);
             // info!("Execution time : {} μs", &timer.elapsed().as_micros());
             let elapsed = timer.elapsed();
             if elapsed < _refresh_rate {
@@ -16,19 +16,49 @@ pub mod sequencer;
 /// The state module contains the State enum which represents the current state of an action.
 pub mod state;
 
+/// The `sequence` module contains the `Sequence` action which runs a list of actions one after the other.
+pub mod sequence;
 
+/// The `parallel` module contains the `Parallel` action which runs a list of actions at the same time.
+pub mod parallel;
+
+/// The `race` module contains the `Race` action which runs a list of actions until the first one finishes.
+pub mod race;
+
+/// The `timeout` module contains the `Timeout` action which fails if an action takes too long.
+pub mod timeout;
+
+/// The `retry` module contains the `Retry` action which restarts an action when it fails.
+pub mod retry;
+
+/// The `guarded` module contains the `Guarded` action which runs an action only while a condition holds.
+pub mod guarded;
+
+/// The `until` module contains the `Until` action which runs an action until a condition is met.
+pub mod until;
 
 use crate::action::move_to::MoveTo;
 use crate::action::orient_to::OrientTo;
 use crate::action::go_to::GoTo;
 use crate::action::order_raw::RawOrder;
 use crate::action::sequencer::Sequencer;
+use crate::action::sequence::Sequence;
+use crate::action::parallel::Parallel;
+use crate::action::race::Race;
+use crate::action::timeout::Timeout;
+use crate::action::retry::Retry;
+use crate::action::guarded::Guarded;
+use crate::action::until::Until;
 use crabe_framework::data::output::{Command, CommandMap};
 use crabe_framework::data::tool::ToolData;
 use crabe_framework::data::world::World;
 use enum_dispatch::enum_dispatch;
 use state::State;
 use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A condition on the state of the world, used by the actions that depend on the game situation.
+pub type Predicate = Arc<dyn Fn(&World) -> bool + Send + Sync>;
 
 /// The Action trait represents an action that can be performed by a robot, such as moving to a certain point.
 #[enum_dispatch(Actions)]
@@ -45,11 +75,19 @@ pub trait Action {
 
 /// The Actions enum is used to define the various actions that can be taken by a robot and implement the Action
 #[enum_dispatch]
+#[derive(Clone)]
 pub enum Actions {
     MoveTo(MoveTo),
     OrientTo(OrientTo),
     GoTo(GoTo),
     RawOrder(RawOrder),
+    Sequence(Sequence),
+    Parallel(Parallel),
+    Race(Race),
+    Timeout(Timeout),
+    Retry(Retry),
+    Guarded(Guarded),
+    Until(Until),
 }
 
 /// The `ActionWrapper` struct represents a wrapper for a sequence of actions to be executed for each robot.
@@ -87,6 +125,40 @@ impl ActionWrapper {
         }
     }
 
+    /// Returns the state of the sequence of actions of a given robot, so that a strategy
+    /// can react when one of its actions has failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot whose state is requested.
+    ///
+    /// # Returns
+    ///
+    /// The state of the sequence, or `None` if no action was ever given to this robot.
+    pub fn state(&self, id: u8) -> Option<State> {
+        self.actions.get(&id).map(|sequencer| sequencer.state())
+    }
+
+    /// Takes the robots whose sequence of actions failed, clearing their sequences,
+    /// so that the failures are given to their strategies once.
+    ///
+    /// # Returns
+    ///
+    /// The ids of the robots whose sequence failed since the previous call.
+    pub fn take_failed(&mut self) -> Vec<u8> {
+        let mut failed: Vec<u8> = self
+            .actions
+            .iter_mut()
+            .filter(|(_, sequencer)| sequencer.state() == State::Failed)
+            .map(|(id, sequencer)| {
+                sequencer.clear();
+                *id
+            })
+            .collect();
+        failed.sort();
+        failed
+    }
+
     /// Clears the sequence of actions to be executed of all robot.
     pub fn clear_all(&mut self) {
         self.actions.iter_mut().for_each(|(_, sequencer)| {
@@ -0,0 +1,84 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions, Predicate};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+use std::sync::Arc;
+
+/// The `Guarded` struct represents an action that only runs while a condition on the world holds.
+/// As soon as the condition is false, the child is cancelled and the action fails.
+#[derive(Clone)]
+pub struct Guarded {
+    /// The current state of the action.
+    state: State,
+    /// The condition that must hold for the action to keep running.
+    predicate: Predicate,
+    /// The action to run.
+    action: Box<Actions>,
+}
+
+impl From<&mut Guarded> for Guarded {
+    fn from(other: &mut Guarded) -> Guarded {
+        other.clone()
+    }
+}
+
+impl Guarded {
+    /// Creates a new `Guarded` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate`: The condition on the world that must hold while the action runs.
+    /// * `action`: The action to run.
+    pub fn new<P, T>(predicate: P, action: T) -> Self
+    where
+        P: Fn(&World) -> bool + Send + Sync + 'static,
+        T: Into<Actions>,
+    {
+        Self {
+            state: State::Running,
+            predicate: Arc::new(predicate),
+            action: Box::new(action.into()),
+        }
+    }
+}
+
+impl Action for Guarded {
+    /// Returns the name of the action.
+    fn name(&self) -> String {
+        format!("Guarded({})", self.action.name())
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Checks the condition then computes the orders of the child.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        if !(self.predicate)(world) {
+            self.action.cancel();
+            self.state = State::Failed;
+            return Command::default();
+        }
+
+        let command = self.action.compute_order(id, world, tools);
+        self.state = self.action.state();
+        command
+    }
+
+    /// Cancels the child action.
+    fn cancel(&mut self) {
+        self.action.cancel();
+    }
+}
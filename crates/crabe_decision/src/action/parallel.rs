@@ -0,0 +1,108 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+
+/// The `Parallel` struct represents an action that runs all its children at the same time,
+/// for example moving to a point while charging the kicker.
+/// It is done when every child is done, and fails as soon as one of them fails.
+///
+/// The commands of the children are merged: the velocities come from the first child
+/// still running, the charge order is kept if any child asks for it, the first kick found
+/// is used and the dribbler takes the highest requested speed.
+#[derive(Clone)]
+pub struct Parallel {
+    /// The current state of the action.
+    state: State,
+    /// The actions executed together.
+    actions: Vec<Actions>,
+}
+
+impl From<&mut Parallel> for Parallel {
+    fn from(other: &mut Parallel) -> Parallel {
+        other.clone()
+    }
+}
+
+impl Parallel {
+    /// Creates a new `Parallel` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions`: The actions to execute together, the first one driving the robot.
+    pub fn new(actions: Vec<Actions>) -> Self {
+        Self {
+            state: State::Running,
+            actions,
+        }
+    }
+}
+
+/// Merges the command of a secondary action into the command of the action driving the robot.
+pub(crate) fn merge_commands(primary: Option<Command>, secondary: Command) -> Command {
+    match primary {
+        None => secondary,
+        Some(primary) => Command {
+            charge: primary.charge || secondary.charge,
+            kick: primary.kick.or(secondary.kick),
+            dribbler: primary.dribbler.max(secondary.dribbler),
+            ..primary
+        },
+    }
+}
+
+impl Action for Parallel {
+    /// Returns the name of the action, which is a concatenation of the names of its children.
+    fn name(&self) -> String {
+        let names: Vec<String> = self.actions.iter().map(|action| action.name()).collect();
+        format!("Parallel({})", names.join(", "))
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Computes the orders of every running child and returns their merged `Command`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        let mut command = None;
+        let mut failed = false;
+        let mut running = false;
+        for action in self.actions.iter_mut() {
+            if action.state() != State::Running {
+                failed |= action.state() == State::Failed;
+                continue;
+            }
+            command = Some(merge_commands(command, action.compute_order(id, world, tools)));
+            match action.state() {
+                State::Failed => failed = true,
+                State::Running => running = true,
+                State::Done => {}
+            }
+        }
+
+        if failed {
+            self.cancel();
+            self.state = State::Failed;
+        } else if !running {
+            self.state = State::Done;
+        }
+        command.unwrap_or_default()
+    }
+
+    /// Cancels every child of the action.
+    fn cancel(&mut self) {
+        self.actions.iter_mut().for_each(|a| a.cancel());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::action::parallel::merge_commands;
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+
+/// The `Race` struct represents an action that runs all its children at the same time
+/// and stops as soon as one of them finishes. The race takes the state of the first child
+/// that finished (done or failed) and cancels the others.
+///
+/// The commands of the children are merged the same way as in the `Parallel` action.
+#[derive(Clone)]
+pub struct Race {
+    /// The current state of the action.
+    state: State,
+    /// The actions racing against each other.
+    actions: Vec<Actions>,
+}
+
+impl From<&mut Race> for Race {
+    fn from(other: &mut Race) -> Race {
+        other.clone()
+    }
+}
+
+impl Race {
+    /// Creates a new `Race` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions`: The actions to execute together, the first one driving the robot.
+    pub fn new(actions: Vec<Actions>) -> Self {
+        Self {
+            state: State::Running,
+            actions,
+        }
+    }
+}
+
+impl Action for Race {
+    /// Returns the name of the action, which is a concatenation of the names of its children.
+    fn name(&self) -> String {
+        let names: Vec<String> = self.actions.iter().map(|action| action.name()).collect();
+        format!("Race({})", names.join(", "))
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Computes the orders of every child and returns their merged `Command`.
+    /// The race ends with the state of the first child that is no longer running.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+        if self.actions.is_empty() {
+            self.state = State::Done;
+            return Command::default();
+        }
+
+        let mut command = None;
+        for action in self.actions.iter_mut() {
+            if action.state() == State::Running {
+                command = Some(merge_commands(command, action.compute_order(id, world, tools)));
+            }
+            if action.state() != State::Running {
+                self.state = action.state();
+                break;
+            }
+        }
+
+        if self.state != State::Running {
+            self.cancel();
+        }
+        command.unwrap_or_default()
+    }
+
+    /// Cancels every child of the action.
+    fn cancel(&mut self) {
+        self.actions.iter_mut().for_each(|a| a.cancel());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+
+/// The `Retry` struct represents an action that restarts its child from scratch when it fails,
+/// up to a given number of attempts. The action fails once every attempt has failed.
+#[derive(Clone)]
+pub struct Retry {
+    /// The current state of the action.
+    state: State,
+    /// A copy of the action as it was given, used to restart it.
+    template: Box<Actions>,
+    /// The attempt currently running.
+    action: Box<Actions>,
+    /// The number of attempts left after the current one.
+    attempts_left: usize,
+}
+
+impl From<&mut Retry> for Retry {
+    fn from(other: &mut Retry) -> Retry {
+        other.clone()
+    }
+}
+
+impl Retry {
+    /// Creates a new `Retry` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `action`: The action to run.
+    /// * `attempts`: The maximum number of times the action is run (at least once).
+    pub fn new<T: Into<Actions>>(action: T, attempts: usize) -> Self {
+        let action: Actions = action.into();
+        Self {
+            state: State::Running,
+            template: Box::new(action.clone()),
+            action: Box::new(action),
+            attempts_left: attempts.saturating_sub(1),
+        }
+    }
+}
+
+impl Action for Retry {
+    /// Returns the name of the action.
+    fn name(&self) -> String {
+        format!("Retry({})", self.action.name())
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Computes the orders of the current attempt and returns a `Command` instance.
+    /// When the attempt fails, a new one is started on the next call if any is left.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        if self.action.state() == State::Failed {
+            if self.attempts_left == 0 {
+                self.state = State::Failed;
+                return Command::default();
+            }
+            self.attempts_left -= 1;
+            self.action = self.template.clone();
+        }
+
+        let command = self.action.compute_order(id, world, tools);
+        match self.action.state() {
+            State::Done => self.state = State::Done,
+            State::Failed if self.attempts_left == 0 => self.state = State::Failed,
+            _ => {}
+        }
+        command
+    }
+
+    /// Cancels the current attempt.
+    fn cancel(&mut self) {
+        self.action.cancel();
+    }
+}
@@ -0,0 +1,98 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+
+/// The `Sequence` struct represents an action that runs its children one after the other.
+/// It is done when every child is done, and fails as soon as one of them fails.
+#[derive(Clone)]
+pub struct Sequence {
+    /// The current state of the action.
+    state: State,
+    /// The remaining actions to execute, the head being the running one.
+    actions: Vec<Actions>,
+}
+
+impl From<&mut Sequence> for Sequence {
+    fn from(other: &mut Sequence) -> Sequence {
+        other.clone()
+    }
+}
+
+impl Sequence {
+    /// Creates a new `Sequence` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions`: The actions to execute, in order.
+    pub fn new(actions: Vec<Actions>) -> Self {
+        Self {
+            state: State::Running,
+            actions,
+        }
+    }
+}
+
+impl Action for Sequence {
+    /// Returns the name of the action, which is a concatenation of the names of its children.
+    fn name(&self) -> String {
+        let names: Vec<String> = self.actions.iter().map(|action| action.name()).collect();
+        format!("Sequence({})", names.join(", "))
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Computes the orders of the running child and returns a `Command` instance.
+    /// Finished children are removed from the sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        while let Some(action) = self.actions.first_mut() {
+            match action.state() {
+                State::Done => {
+                    self.actions.remove(0);
+                }
+                State::Failed => {
+                    self.cancel();
+                    self.state = State::Failed;
+                    return Command::default();
+                }
+                State::Running => {
+                    let command = action.compute_order(id, world, tools);
+                    match action.state() {
+                        State::Failed => {
+                            self.cancel();
+                            self.state = State::Failed;
+                        }
+                        State::Done if self.actions.len() == 1 => {
+                            self.actions.clear();
+                            self.state = State::Done;
+                        }
+                        _ => {}
+                    }
+                    return command;
+                }
+            }
+        }
+
+        self.state = State::Done;
+        Command::default()
+    }
+
+    /// Cancels every remaining child of the sequence.
+    fn cancel(&mut self) {
+        self.actions.iter_mut().for_each(|a| a.cancel());
+    }
+}
@@ -31,18 +31,27 @@ impl Sequencer {
     }
 
     /// Adds an action to the end of the sequence.
+    /// A failed sequence is cleared first, so that the new action is executed.
     pub fn push(&mut self, action: Actions) {
+        if self.state == State::Failed {
+            self.clear();
+        }
         self.actions.push(action);
-        self.state = match self.state {
-            State::Running | State::Done => State::Running,
-            State::Failed => State::Failed,
-        };
+        self.state = State::Running;
     }
 
     /// Removes all the actions from the sequence and cancels their execution.
+    /// The sequence is running again afterwards, even if it had failed.
     pub fn clear(&mut self) {
         self.actions.iter_mut().for_each(|a| a.cancel());
         self.actions.clear();
+        self.state = State::Running;
+    }
+
+    /// Returns the current state of the sequence.
+    /// The sequence fails as soon as one of its actions fails.
+    pub fn state(&self) -> State {
+        self.state
     }
 
     /// Returns the name of the sequence, which is a concatenation of the names of its actions.
@@ -0,0 +1,85 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+use std::time::{Duration, Instant};
+
+/// The `Timeout` struct represents an action that fails if its child is not finished
+/// within a given duration. The timer starts on the first computed order.
+#[derive(Clone)]
+pub struct Timeout {
+    /// The current state of the action.
+    state: State,
+    /// The action to run under the time limit.
+    action: Box<Actions>,
+    /// The time given to the action to finish.
+    duration: Duration,
+    /// The instant at which the action started.
+    start: Option<Instant>,
+}
+
+impl From<&mut Timeout> for Timeout {
+    fn from(other: &mut Timeout) -> Timeout {
+        other.clone()
+    }
+}
+
+impl Timeout {
+    /// Creates a new `Timeout` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `action`: The action to run.
+    /// * `duration`: The time given to the action to finish before failing.
+    pub fn new<T: Into<Actions>>(action: T, duration: Duration) -> Self {
+        Self {
+            state: State::Running,
+            action: Box::new(action.into()),
+            duration,
+            start: None,
+        }
+    }
+}
+
+impl Action for Timeout {
+    /// Returns the name of the action.
+    fn name(&self) -> String {
+        format!("Timeout({})", self.action.name())
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Computes the orders of the child and returns a `Command` instance.
+    /// If the time is elapsed before the child is done, the action fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        let start = *self.start.get_or_insert_with(Instant::now);
+        if start.elapsed() > self.duration {
+            self.action.cancel();
+            self.state = State::Failed;
+            return Command::default();
+        }
+
+        let command = self.action.compute_order(id, world, tools);
+        self.state = self.action.state();
+        command
+    }
+
+    /// Cancels the child action.
+    fn cancel(&mut self) {
+        self.action.cancel();
+    }
+}
@@ -0,0 +1,85 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions, Predicate};
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+use std::sync::Arc;
+
+/// The `Until` struct represents an action that runs its child until a condition on the world
+/// becomes true, for example "follow the ball until it stops".
+/// The action is done when the condition is met or when the child is done, and fails if the child fails.
+#[derive(Clone)]
+pub struct Until {
+    /// The current state of the action.
+    state: State,
+    /// The condition that stops the action.
+    predicate: Predicate,
+    /// The action to run.
+    action: Box<Actions>,
+}
+
+impl From<&mut Until> for Until {
+    fn from(other: &mut Until) -> Until {
+        other.clone()
+    }
+}
+
+impl Until {
+    /// Creates a new `Until` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate`: The condition on the world that ends the action.
+    /// * `action`: The action to run.
+    pub fn new<P, T>(predicate: P, action: T) -> Self
+    where
+        P: Fn(&World) -> bool + Send + Sync + 'static,
+        T: Into<Actions>,
+    {
+        Self {
+            state: State::Running,
+            predicate: Arc::new(predicate),
+            action: Box::new(action.into()),
+        }
+    }
+}
+
+impl Action for Until {
+    /// Returns the name of the action.
+    fn name(&self) -> String {
+        format!("Until({})", self.action.name())
+    }
+
+    /// Returns the state of the action.
+    fn state(&mut self) -> State {
+        self.state
+    }
+
+    /// Checks the condition then computes the orders of the child.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the robot for which the orders are computed.
+    /// * `world`: The current state of the world.
+    /// * `tools`: A collection of external tools used by the action, such as a viewer.
+    fn compute_order(&mut self, id: u8, world: &World, tools: &mut ToolData) -> Command {
+        if self.state != State::Running {
+            return Command::default();
+        }
+
+        if (self.predicate)(world) {
+            self.action.cancel();
+            self.state = State::Done;
+            return Command::default();
+        }
+
+        let command = self.action.compute_order(id, world, tools);
+        self.state = self.action.state();
+        command
+    }
+
+    /// Cancels the child action.
+    fn cancel(&mut self) {
+        self.action.cancel();
+    }
+}
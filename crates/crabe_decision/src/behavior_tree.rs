@@ -0,0 +1,262 @@
+//! A small behavior tree runtime used to assemble strategies from reusable nodes.
+//!
+//! A tree is made of composite nodes (`Selector`, `Sequence`, `Parallel`), decorators
+//! (`Inverter`, `Repeat`, `AlwaysSucceed`) and leaves (`Condition`, `Task`, `ActionLeaf`).
+//! The leaves driving the robot wrap regular `Actions`, so every action can be reused in a tree.
+//! Each robot runs its own instance of the tree with its own `Blackboard`, and the branches
+//! currently running are sent to the tool in `ToolData::behavior_trees`.
+//!
+//! # Example
+//! ```
+//! use crabe_decision::action::move_to::MoveTo;
+//! use crabe_decision::behavior_tree::{ActionLeaf, BehaviorTree, Condition, Node, Selector, Sequence};
+//! use crabe_decision::strategy::Strategy;
+//! use nalgebra::Point2;
+//!
+//! fn build() -> Box<dyn Node> {
+//!     Box::new(Selector::new("Root", vec![
+//!         Box::new(Sequence::new("GoToBall", vec![
+//!             Box::new(Condition::new("BallVisible", |_id, world, _bb| world.ball.is_some())),
+//!             Box::new(ActionLeaf::reactive("MoveToBall", |_id, world, _bb| {
+//!                 world.ball.as_ref().map(|ball| {
+//!                     MoveTo::new(ball.position_2d(), 0., 0., false, None, false, true).into()
+//!                 })
+//!             })),
+//!         ])),
+//!         Box::new(ActionLeaf::new("GoHome", |_id, _world, _bb| {
+//!             Some(MoveTo::new(Point2::new(-1., 0.), 0., 0., false, None, false, true).into())
+//!         })),
+//!     ]))
+//! }
+//!
+//! let strategy = BehaviorTree::new("BallChaser", vec![1, 2], build);
+//! assert_eq!(strategy.name(), "BallChaser");
+//! assert_eq!(strategy.get_ids(), vec![1, 2]);
+//! ```
+
+mod blackboard;
+pub use self::blackboard::{Blackboard, BlackboardValue};
+mod node;
+pub use self::node::{Node, TickContext};
+mod selector;
+pub use self::selector::Selector;
+mod sequence;
+pub use self::sequence::Sequence;
+mod parallel;
+pub use self::parallel::Parallel;
+mod decorator;
+pub use self::decorator::{AlwaysSucceed, Inverter, Repeat};
+mod leaf;
+pub use self::leaf::{ActionLeaf, Condition, Task};
+mod tree;
+pub use self::tree::BehaviorTree;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::state::State;
+    use crabe_framework::config::CommonConfig;
+    use crabe_framework::data::tool::ToolData;
+    use crabe_framework::data::world::World;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// What happened to the scripted nodes, like `tick A` or `reset B`.
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    /// A node returning the states it is given, one per tick, and then its last state.
+    struct Scripted {
+        name: &'static str,
+        states: Vec<State>,
+        log: Log,
+    }
+
+    impl Node for Scripted {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn tick(&mut self, _ctx: &mut TickContext) -> State {
+            self.log.borrow_mut().push(format!("tick {}", self.name));
+            if self.states.len() > 1 {
+                self.states.remove(0)
+            } else {
+                self.states[0]
+            }
+        }
+
+        fn reset(&mut self) {
+            self.log.borrow_mut().push(format!("reset {}", self.name));
+        }
+    }
+
+    fn scripted(name: &'static str, states: &[State], log: &Log) -> Box<dyn Node> {
+        Box::new(Scripted {
+            name,
+            states: states.to_vec(),
+            log: log.clone(),
+        })
+    }
+
+    /// Ticks the root once, returning its state and the running branches.
+    fn tick(root: &mut dyn Node, world: &World) -> (State, Vec<Vec<String>>) {
+        let mut tools_data = ToolData::default();
+        let mut blackboard = Blackboard::default();
+        let mut ctx = TickContext::new(0, world, &mut tools_data, &mut blackboard);
+        let state = ctx.tick(root);
+        (state, ctx.branches)
+    }
+
+    fn world() -> World {
+        World::with_config(&CommonConfig {
+            yellow: false,
+            real: false,
+            gc: false,
+        })
+    }
+
+    fn branch(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn selector_runs_the_first_child_not_failing() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Selector::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Failed, State::Running], &log),
+                scripted("B", &[State::Running], &log),
+            ],
+        );
+
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(branches, vec![branch(&["Root", "B"])]);
+
+        // A can run again, it has the priority and interrupts B
+        log.borrow_mut().clear();
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(branches, vec![branch(&["Root", "A"])]);
+        assert_eq!(*log.borrow(), vec!["tick A", "reset B"]);
+    }
+
+    #[test]
+    fn selector_fails_when_every_child_fails() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Selector::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Failed], &log),
+                scripted("B", &[State::Failed], &log),
+            ],
+        );
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Failed);
+        assert!(branches.is_empty());
+    }
+
+    #[test]
+    fn sequence_runs_its_children_one_after_the_other() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Sequence::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Done], &log),
+                scripted("B", &[State::Running, State::Done], &log),
+                scripted("C", &[State::Running], &log),
+            ],
+        );
+
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(branches, vec![branch(&["Root", "B"])]);
+
+        // A already succeeded, it isn't ticked again
+        log.borrow_mut().clear();
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(branches, vec![branch(&["Root", "C"])]);
+        assert_eq!(*log.borrow(), vec!["tick B", "tick C"]);
+    }
+
+    #[test]
+    fn sequence_fails_as_soon_as_a_child_fails() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Sequence::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Done], &log),
+                scripted("B", &[State::Failed], &log),
+                scripted("C", &[State::Done], &log),
+            ],
+        );
+        let (state, _) = tick(&mut root, &world);
+        assert_eq!(state, State::Failed);
+        assert!(!log.borrow().contains(&"tick C".to_string()));
+        // the sequence starts again from its first child
+        log.borrow_mut().clear();
+        tick(&mut root, &world);
+        assert_eq!(log.borrow()[0], "tick A");
+    }
+
+    #[test]
+    fn parallel_reports_every_running_child() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Parallel::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Running, State::Done], &log),
+                Box::new(Sequence::new(
+                    "B",
+                    vec![scripted(
+                        "C",
+                        &[State::Running, State::Running, State::Done],
+                        &log,
+                    )],
+                )),
+            ],
+        );
+
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(
+            branches,
+            vec![branch(&["Root", "A"]), branch(&["Root", "B", "C"])]
+        );
+
+        // A succeeded, only the other branch is still running
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Running);
+        assert_eq!(branches, vec![branch(&["Root", "B", "C"])]);
+
+        // every child succeeded
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Done);
+        assert!(branches.is_empty());
+    }
+
+    #[test]
+    fn parallel_fails_as_soon_as_a_child_fails() {
+        let world = world();
+        let log = Log::default();
+        let mut root = Parallel::new(
+            "Root",
+            vec![
+                scripted("A", &[State::Running], &log),
+                scripted("B", &[State::Failed], &log),
+            ],
+        );
+        let (state, branches) = tick(&mut root, &world);
+        assert_eq!(state, State::Failed);
+        // the branch of A stopped with the parallel node
+        assert!(branches.is_empty());
+        assert!(log.borrow().contains(&"reset A".to_string()));
+    }
+}
@@ -0,0 +1,73 @@
+use nalgebra::Point2;
+use std::collections::HashMap;
+
+/// A value stored in a `Blackboard`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Float(f64),
+    Id(u8),
+    Point(Point2<f64>),
+}
+
+/// The `Blackboard` struct is the memory shared by the nodes of the behavior tree of one robot.
+/// Nodes use it to pass information to each other, such as a target computed by a task
+/// and used later by an action.
+#[derive(Clone, Debug, Default)]
+pub struct Blackboard {
+    values: HashMap<String, BlackboardValue>,
+}
+
+impl Blackboard {
+    /// Stores a value, replacing the previous one with the same key.
+    pub fn set(&mut self, key: &str, value: BlackboardValue) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    /// Returns the value stored with the given key.
+    pub fn get(&self, key: &str) -> Option<&BlackboardValue> {
+        self.values.get(key)
+    }
+
+    /// Removes the value stored with the given key.
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    /// Removes every value from the blackboard.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Returns the boolean stored with the given key, if any.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.values.get(key) {
+            Some(BlackboardValue::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the float stored with the given key, if any.
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        match self.values.get(key) {
+            Some(BlackboardValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the robot id stored with the given key, if any.
+    pub fn get_id(&self, key: &str) -> Option<u8> {
+        match self.values.get(key) {
+            Some(BlackboardValue::Id(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the point stored with the given key, if any.
+    pub fn get_point(&self, key: &str) -> Option<Point2<f64>> {
+        match self.values.get(key) {
+            Some(BlackboardValue::Point(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::action::state::State;
+use crate::behavior_tree::{Node, TickContext};
+
+/// The `Inverter` node turns the success of its child into a failure and the other way around.
+pub struct Inverter {
+    child: Box<dyn Node>,
+}
+
+impl Inverter {
+    /// Creates a new `Inverter` node.
+    pub fn new(child: Box<dyn Node>) -> Self {
+        Self { child }
+    }
+}
+
+impl Node for Inverter {
+    fn name(&self) -> String {
+        String::from("Inverter")
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        match ctx.tick(self.child.as_mut()) {
+            State::Done => State::Failed,
+            State::Failed => State::Done,
+            State::Running => State::Running,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// The `AlwaysSucceed` node succeeds when its child finishes, even if the child failed.
+pub struct AlwaysSucceed {
+    child: Box<dyn Node>,
+}
+
+impl AlwaysSucceed {
+    /// Creates a new `AlwaysSucceed` node.
+    pub fn new(child: Box<dyn Node>) -> Self {
+        Self { child }
+    }
+}
+
+impl Node for AlwaysSucceed {
+    fn name(&self) -> String {
+        String::from("AlwaysSucceed")
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        match ctx.tick(self.child.as_mut()) {
+            State::Running => State::Running,
+            _ => State::Done,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// The `Repeat` node restarts its child each time it succeeds, a given number of times or forever.
+/// It fails as soon as its child fails.
+pub struct Repeat {
+    child: Box<dyn Node>,
+    times: Option<usize>,
+    count: usize,
+}
+
+impl Repeat {
+    /// Creates a new `Repeat` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `child`: The node to repeat.
+    /// * `times`: The number of successes needed for the node to succeed, `None` to repeat forever.
+    pub fn new(child: Box<dyn Node>, times: Option<usize>) -> Self {
+        Self {
+            child,
+            times,
+            count: 0,
+        }
+    }
+}
+
+impl Node for Repeat {
+    fn name(&self) -> String {
+        String::from("Repeat")
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        match ctx.tick(self.child.as_mut()) {
+            State::Running => State::Running,
+            State::Failed => {
+                self.reset();
+                State::Failed
+            }
+            State::Done => {
+                self.child.reset();
+                self.count += 1;
+                match self.times {
+                    Some(times) if self.count >= times => {
+                        self.count = 0;
+                        State::Done
+                    }
+                    _ => State::Running,
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+        self.count = 0;
+    }
+}
@@ -0,0 +1,151 @@
+use crate::action::state::State;
+use crate::action::{Action, Actions};
+use crate::behavior_tree::{Blackboard, Node, TickContext};
+use crabe_framework::data::world::World;
+
+/// A predicate evaluated with the id of the robot, the world and its blackboard.
+type NodePredicate = Box<dyn Fn(u8, &World, &Blackboard) -> bool>;
+/// Builds an action from the id of the robot, the world and its blackboard.
+type ActionFactory = Box<dyn Fn(u8, &World, &Blackboard) -> Option<Actions>>;
+
+/// The `Condition` node succeeds when its predicate is true and fails otherwise.
+pub struct Condition {
+    name: String,
+    predicate: NodePredicate,
+}
+
+impl Condition {
+    /// Creates a new `Condition` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `predicate`: The predicate evaluated with the id of the robot, the world and its blackboard.
+    pub fn new(name: &str, predicate: impl Fn(u8, &World, &Blackboard) -> bool + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+impl Node for Condition {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        if (self.predicate)(ctx.id, ctx.world, ctx.blackboard) {
+            State::Done
+        } else {
+            State::Failed
+        }
+    }
+}
+
+/// The `Task` node runs arbitrary code, typically to compute a value and store it in the blackboard.
+pub struct Task {
+    name: String,
+    task: Box<dyn FnMut(&mut TickContext) -> State>,
+}
+
+impl Task {
+    /// Creates a new `Task` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `task`: The code executed at each tick, returning the state of the node.
+    pub fn new(name: &str, task: impl FnMut(&mut TickContext) -> State + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            task: Box::new(task),
+        }
+    }
+}
+
+impl Node for Task {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        (self.task)(ctx)
+    }
+}
+
+/// The `ActionLeaf` node drives the robot with an action, built from the world and the blackboard.
+/// The node has the state of its action, and fails when no action can be built.
+pub struct ActionLeaf {
+    name: String,
+    factory: ActionFactory,
+    reactive: bool,
+    action: Option<Actions>,
+}
+
+impl ActionLeaf {
+    /// Creates a new `ActionLeaf` node building its action once, when the node starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `factory`: Builds the action from the id of the robot, the world and its blackboard.
+    pub fn new(
+        name: &str,
+        factory: impl Fn(u8, &World, &Blackboard) -> Option<Actions> + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            factory: Box::new(factory),
+            reactive: false,
+            action: None,
+        }
+    }
+
+    /// Creates a new `ActionLeaf` node building its action again at every tick,
+    /// for actions whose target moves, such as the ball.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `factory`: Builds the action from the id of the robot, the world and its blackboard.
+    pub fn reactive(
+        name: &str,
+        factory: impl Fn(u8, &World, &Blackboard) -> Option<Actions> + 'static,
+    ) -> Self {
+        Self {
+            reactive: true,
+            ..Self::new(name, factory)
+        }
+    }
+}
+
+impl Node for ActionLeaf {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        if self.reactive || self.action.is_none() {
+            self.action = (self.factory)(ctx.id, ctx.world, ctx.blackboard);
+        }
+        let Some(action) = self.action.as_mut() else {
+            return State::Failed;
+        };
+        let command = action.compute_order(ctx.id, ctx.world, ctx.tools_data);
+        let state = action.state();
+        if state == State::Running {
+            ctx.add_command(command);
+        } else {
+            self.action = None;
+        }
+        state
+    }
+
+    fn reset(&mut self) {
+        if let Some(action) = self.action.as_mut() {
+            action.cancel();
+        }
+        self.action = None;
+    }
+}
@@ -0,0 +1,85 @@
+use crate::action::parallel::merge_commands;
+use crate::action::state::State;
+use crate::behavior_tree::Blackboard;
+use crabe_framework::data::output::Command;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+
+/// The `Node` trait represents a node of a behavior tree.
+/// Ticking a node returns `State::Running` while it is still working, `State::Done` when it
+/// succeeded and `State::Failed` when it failed.
+pub trait Node {
+    /// Returns the name of the node, displayed in the tool.
+    fn name(&self) -> String;
+    /// Executes one step of the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: The context of the robot running the tree.
+    fn tick(&mut self, ctx: &mut TickContext) -> State;
+    /// Puts the node back in its initial state, called when a running node is interrupted.
+    fn reset(&mut self) {}
+}
+
+/// The `TickContext` struct gives the nodes access to the robot running the tree,
+/// the world, its blackboard and the command being built for this step.
+pub struct TickContext<'a> {
+    /// The id of the robot running the tree.
+    pub id: u8,
+    /// The current state of the world.
+    pub world: &'a World,
+    /// A collection of external tools used by the nodes, such as a viewer.
+    pub tools_data: &'a mut ToolData,
+    /// The memory of the robot running the tree.
+    pub blackboard: &'a mut Blackboard,
+    /// The command built by the action leaves ticked during this step.
+    pub command: Option<Command>,
+    /// The running branches, each one being the names of the nodes from the root to a deepest running node.
+    /// There is one branch per running child of a `Parallel` node.
+    pub branches: Vec<Vec<String>>,
+    /// The names of the nodes from the root to the node being ticked.
+    path: Vec<String>,
+}
+
+impl<'a> TickContext<'a> {
+    /// Creates a new context for a robot.
+    pub fn new(
+        id: u8,
+        world: &'a World,
+        tools_data: &'a mut ToolData,
+        blackboard: &'a mut Blackboard,
+    ) -> Self {
+        Self {
+            id,
+            world,
+            tools_data,
+            blackboard,
+            command: None,
+            branches: vec![],
+            path: vec![],
+        }
+    }
+
+    /// Ticks a child node, keeping track of the running branches.
+    /// Composite nodes must tick their children through this method.
+    pub fn tick(&mut self, node: &mut dyn Node) -> State {
+        let branches = self.branches.len();
+        self.path.push(node.name());
+        let state = node.tick(self);
+        if state != State::Running {
+            // the branches of its children stopped with the node
+            self.branches.truncate(branches);
+        } else if self.branches.len() == branches {
+            // none of its children is running, the node is the deepest running node of its branch
+            self.branches.push(self.path.clone());
+        }
+        self.path.pop();
+        state
+    }
+
+    /// Adds the command of an action leaf to the command of the step.
+    /// The first leaf drives the robot, the following ones can only add kicker and dribbler orders.
+    pub fn add_command(&mut self, command: Command) {
+        self.command = Some(merge_commands(self.command, command));
+    }
+}
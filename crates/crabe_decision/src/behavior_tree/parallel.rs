@@ -0,0 +1,59 @@
+use crate::action::state::State;
+use crate::behavior_tree::{Node, TickContext};
+
+/// The `Parallel` node ticks all its children at every step, for example to move while charging.
+/// It succeeds when every child succeeded and fails as soon as one of them fails.
+pub struct Parallel {
+    name: String,
+    children: Vec<Box<dyn Node>>,
+    done: Vec<bool>,
+}
+
+impl Parallel {
+    /// Creates a new `Parallel` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `children`: The children, the first action leaf driving the robot.
+    pub fn new(name: &str, children: Vec<Box<dyn Node>>) -> Self {
+        let done = vec![false; children.len()];
+        Self {
+            name: name.to_string(),
+            children,
+            done,
+        }
+    }
+}
+
+impl Node for Parallel {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        for (child, done) in self.children.iter_mut().zip(self.done.iter_mut()) {
+            if *done {
+                continue;
+            }
+            match ctx.tick(child.as_mut()) {
+                State::Done => *done = true,
+                State::Running => {}
+                State::Failed => {
+                    self.reset();
+                    return State::Failed;
+                }
+            }
+        }
+        if self.done.iter().all(|done| *done) {
+            self.reset();
+            return State::Done;
+        }
+        State::Running
+    }
+
+    fn reset(&mut self) {
+        self.children.iter_mut().for_each(|child| child.reset());
+        self.done.iter_mut().for_each(|done| *done = false);
+    }
+}
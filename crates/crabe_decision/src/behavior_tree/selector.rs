@@ -0,0 +1,60 @@
+use crate::action::state::State;
+use crate::behavior_tree::{Node, TickContext};
+
+/// The `Selector` node ticks its children in order until one of them does not fail.
+/// Children are re-evaluated from the first one at every tick, so a higher priority branch
+/// interrupts a lower priority one as soon as it can run.
+pub struct Selector {
+    name: String,
+    children: Vec<Box<dyn Node>>,
+    running: Option<usize>,
+}
+
+impl Selector {
+    /// Creates a new `Selector` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `children`: The children, by decreasing priority.
+    pub fn new(name: &str, children: Vec<Box<dyn Node>>) -> Self {
+        Self {
+            name: name.to_string(),
+            children,
+            running: None,
+        }
+    }
+}
+
+impl Node for Selector {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let state = ctx.tick(child.as_mut());
+            if state == State::Failed {
+                continue;
+            }
+            if let Some(previous) = self.running {
+                if previous != i {
+                    self.children[previous].reset();
+                }
+            }
+            self.running = if state == State::Running {
+                Some(i)
+            } else {
+                None
+            };
+            return state;
+        }
+        self.running = None;
+        State::Failed
+    }
+
+    fn reset(&mut self) {
+        self.children.iter_mut().for_each(|child| child.reset());
+        self.running = None;
+    }
+}
@@ -0,0 +1,53 @@
+use crate::action::state::State;
+use crate::behavior_tree::{Node, TickContext};
+
+/// The `Sequence` node ticks its children one after the other.
+/// It succeeds when every child succeeded and fails as soon as one of them fails.
+/// The children that already succeeded are not ticked again until the sequence is reset.
+pub struct Sequence {
+    name: String,
+    children: Vec<Box<dyn Node>>,
+    current: usize,
+}
+
+impl Sequence {
+    /// Creates a new `Sequence` node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the node, displayed in the tool.
+    /// * `children`: The children, in execution order.
+    pub fn new(name: &str, children: Vec<Box<dyn Node>>) -> Self {
+        Self {
+            name: name.to_string(),
+            children,
+            current: 0,
+        }
+    }
+}
+
+impl Node for Sequence {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn tick(&mut self, ctx: &mut TickContext) -> State {
+        while let Some(child) = self.children.get_mut(self.current) {
+            match ctx.tick(child.as_mut()) {
+                State::Done => self.current += 1,
+                State::Running => return State::Running,
+                State::Failed => {
+                    self.reset();
+                    return State::Failed;
+                }
+            }
+        }
+        self.reset();
+        State::Done
+    }
+
+    fn reset(&mut self) {
+        self.children.iter_mut().for_each(|child| child.reset());
+        self.current = 0;
+    }
+}
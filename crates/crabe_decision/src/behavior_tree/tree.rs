@@ -0,0 +1,105 @@
+use crate::action::order_raw::RawOrder;
+use crate::action::state::State;
+use crate::action::ActionWrapper;
+use crate::behavior_tree::{Blackboard, Node, TickContext};
+use crate::message::MessageData;
+use crate::strategy::Strategy;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::World;
+use std::collections::HashMap;
+
+/// The tree and the memory of one robot.
+struct RobotTree {
+    root: Box<dyn Node>,
+    blackboard: Blackboard,
+}
+
+/// The `BehaviorTree` struct is a strategy running a behavior tree for each of its robots.
+/// Every robot gets its own instance of the tree, built when the robot joins the strategy.
+/// The tree is restarted from the root when it succeeds or fails.
+pub struct BehaviorTree {
+    name: &'static str,
+    ids: Vec<u8>,
+    messages: Vec<MessageData>,
+    builder: fn() -> Box<dyn Node>,
+    trees: HashMap<u8, RobotTree>,
+}
+
+impl BehaviorTree {
+    /// Creates a new `BehaviorTree` strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the strategy.
+    /// * `ids`: The ids of the robots running the tree.
+    /// * `builder`: Builds the tree of one robot.
+    pub fn new(name: &'static str, ids: Vec<u8>, builder: fn() -> Box<dyn Node>) -> Self {
+        Self {
+            name,
+            ids,
+            messages: vec![],
+            builder,
+            trees: HashMap::new(),
+        }
+    }
+}
+
+impl Strategy for BehaviorTree {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
+    }
+
+    fn get_ids(&self) -> Vec<u8> {
+        self.ids.clone()
+    }
+
+    fn put_ids(&mut self, ids: Vec<u8>) {
+        self.trees.retain(|id, _| ids.contains(id));
+        self.ids = ids;
+    }
+
+    /// Ticks the tree of each robot and sends the resulting command to the robot.
+    ///
+    /// # Arguments
+    ///
+    /// * `world`: The current state of the world.
+    /// * `tools_data`: A collection of external tools used by the strategy, such as a viewer.
+    /// * `action_wrapper`: An `ActionWrapper` instance used to issue actions to the robot.
+    ///
+    /// # Returns
+    ///
+    /// `false`, the trees run until the strategy is replaced.
+    fn step(
+        &mut self,
+        world: &World,
+        tools_data: &mut ToolData,
+        action_wrapper: &mut ActionWrapper,
+    ) -> bool {
+        self.messages.clear();
+        for id in self.ids.iter() {
+            let builder = self.builder;
+            let tree = self.trees.entry(*id).or_insert_with(|| RobotTree {
+                root: builder(),
+                blackboard: Blackboard::default(),
+            });
+            let mut ctx = TickContext::new(*id, world, tools_data, &mut tree.blackboard);
+            let state = ctx.tick(tree.root.as_mut());
+            let command = ctx.command;
+            let branches = ctx.branches;
+            if state != State::Running {
+                tree.root.reset();
+            }
+
+            action_wrapper.clear(*id);
+            if let Some(command) = command {
+                action_wrapper.push(*id, RawOrder::new(command));
+            }
+            tools_data.behavior_trees.insert(*id, branches);
+        }
+        false
+    }
+}
@@ -8,6 +8,9 @@
 /// The `action` module contains the definitions of various actions that can be
 /// performed by a robot, such as moving to a certain point.
 pub mod action;
+/// The `behavior_tree` module contains a behavior tree runtime, used to assemble
+/// strategies from reusable nodes wrapping the actions.
+pub mod behavior_tree;
 /// The `manager` module is responsible for coordinating and executing the `Strategies`.
 /// It contains multiple manager implementation, which is in charge of managing
 /// the execution of the strategies.
@@ -18,6 +21,9 @@ pub mod manager;
 /// responsible for coordinating and executing the Strategies,
 /// which in turn use `Action` to issue actions for each robots.
 pub mod pipeline;
+/// The `play_file` module describes the plays, formations and strategy parameters
+/// loaded at runtime from a TOML file, to tune the team without rebuilding.
+pub mod play_file;
 /// The strategy module contains the Strategy trait and various implementations of strategies.
 /// Strategies are behaviors that one or multiple robots can adopt in order to achieve a certain goal.
 pub mod strategy;
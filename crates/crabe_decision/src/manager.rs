@@ -1,10 +1,15 @@
 use crate::action::ActionWrapper;
+use crate::strategy::Strategy;
 use crabe_framework::data::tool::ToolData;
 use crabe_framework::data::world::World;
 
 pub mod manual;
 pub mod bigbro;
 pub mod test_manager;
+/// The `roles` module contains the typed roles given by `BigBro` to the robots.
+pub mod roles;
+/// The `lifecycle` module calls the lifecycle hooks of the strategies, like when the game state changes.
+pub mod lifecycle;
 
 /// The `Manager` trait defines a coach that handles the SSL game and gives each robot at least one strategy.
 /// A strategy is a behavior for one or multiple robots that gives one `Action` per robot. The `Manager`'s
@@ -19,4 +24,13 @@ pub trait Manager {
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     );
+
+    /// Starts a strategy requested at runtime, like from a tool,
+    /// taking its robots from their current strategies.
+    fn spawn_strategy(&mut self, strategy: Box<dyn Strategy>);
+
+    /// Leaves robots out of the strategies, like the ones pulled off the field by the guards.
+    /// It is called before each step with all the robots to leave out, they stay in the world
+    /// so that the other robots still avoid them.
+    fn exclude_robots(&mut self, _ids: &[u8]) {}
 }
@@ -1,12 +1,16 @@
 use std::vec;
 use crate::action::ActionWrapper;
+use crate::manager::roles::{Assignment, Role, RoleTable};
 use crate::manager::Manager;
 use crate::message::AttackerMessage;
 use crate::message::Message;
-use crate::message::MessageData;
-use crate::strategy::offensive::Attacker;
+use crate::message::{MessageBus, MessageData};
+use crate::play_file::{PlayFile, PlayFileWatcher};
+use crate::strategy::offensive::{Attacker, SupportAttacker};
 use crate::strategy::offensive::Receiver;
 use crate::strategy::testing::{Aligned, GoLeft, GoRight};
+use crate::strategy::formations::Halt;
+use crate::strategy::registry::create_strategy;
 use crate::strategy::Strategy;
 use crate::utils::everyone_halt;
 use crate::utils::everyone_stop;
@@ -15,217 +19,104 @@ use crate::utils::penalty_state;
 use crate::utils::prepare_kick_off;
 use crate::utils::prepare_start;
 use crate::utils::KEEPER_ID;
-use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::tool::{ParameterValue, ToolData};
 use crabe_framework::data::world::game_state::*;
 use crabe_framework::data::world::TeamColor;
 use crabe_framework::data::world::World;
 use crate::utils::bigbro_decisions::run_state;
+use crate::utils::bigbro_decisions::set_piece_state;
+use log::{error, info};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// The `BigBro` struct represents a decision manager that executes strategies BigBroly
 /// added to its list.
 /// It's used for testing individual strategies only and not meant to be used during an actual game.
 ///
-/// To add a strategy, simply create a new instance of the desired strategy and add it to the
-/// `strategies` field in the `new()` method of the `BigBro` struct.
+/// The robots are given typed roles, each role being played by one strategy of the `roles` table.
 pub struct BigBro {
-    pub strategies: Vec<Box<dyn Strategy>>,
+    /// The role of each robot and the strategy playing each role.
+    pub roles: RoleTable,
     pub team_penalty: TeamColor,
+    /// The plays, formations and strategy parameters used by the decisions.
+    pub play_file: PlayFile,
+    play_file_watcher: Option<PlayFileWatcher>,
+    /// The messages between the strategies and the manager.
+    bus: MessageBus,
 }
 
 impl BigBro {
     /// Creates a new `BigBro` instance with the desired strategies to test.
     pub fn new() -> Self {
         Self {
-            strategies: vec![],
+            roles: RoleTable::default(),
             team_penalty: TeamColor::Blue,
+            play_file: PlayFile::default(),
+            play_file_watcher: None,
+            bus: MessageBus::default(),
         }
     }
 
-    /// Removes a bot from all strategies.
-    ///     
-    /// # Arguments
-    /// - `bot_id`: The id of the bot to remove.
-    /// 
-    /// # Example
-    /// ```
-    /// use crabe_decision::manager::bigbro::BigBro;
-    /// let mut bigbro = BigBro::new();
-    /// bigbro.strategies.clear();
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::formations::Stop::new(vec![1, 2, 3])));
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::Aligned::new(vec![4])));
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::GoLeft::new(5)));
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::GoRight::new(0)));
-    /// assert_eq!(bigbro.strategies.len(), 4);
-    /// bigbro.remove_bot_from_strategies(0);
-    /// assert_eq!(bigbro.strategies.len(), 3);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1, 2, 3]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![4]);
-    /// assert_eq!(bigbro.strategies[2].as_ref().get_ids(), vec![5]);
-    /// bigbro.remove_bot_from_strategies(5);
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1, 2, 3]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![4]);
-    /// bigbro.remove_bot_from_strategies(2);
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1, 3]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![4]);
-    /// ```
-    pub fn remove_bot_from_strategies(&mut self, bot_id: u8) {
-        for strategy in self.strategies.iter_mut() {
-            let mut ids = strategy.get_ids();
-            ids.retain(|&id| id != bot_id);
-            strategy.put_ids(ids);
+    /// Creates a new `BigBro` instance using a play file, reloaded when modified.
+    pub fn with_play_file(path: PathBuf) -> Self {
+        let (watcher, play_file) = PlayFileWatcher::new(path);
+        Self {
+            play_file,
+            play_file_watcher: Some(watcher),
+            ..Self::new()
         }
-        self.strategies.retain(|s| {
-            let ids = s.get_ids();
-            !(ids.len() == 1 && ids[0] == bot_id) && !ids.is_empty()
-        });
     }
 
-    /// Moves a bot from its current strategy to an existing strategy.
-    ///
-    /// # Arguments
-    /// - `bot_id`: The id of the bot to move.
-    /// - `strategy_index`: The index of the strategy (in the strategies list) to move the bot to.
-    pub fn move_bot_to_existing_strategy(&mut self, bot_id: u8, strategy_index: usize) {
-        self.move_bots_to_existing_strategy(vec![bot_id], strategy_index);
+    /// Reloads the play file if it was modified.
+    /// The strategies are dropped, to be created again from the new play file.
+    fn reload_play_file(&mut self) {
+        if let Some(play_file) = self.play_file_watcher.as_mut().and_then(|watcher| watcher.poll()) {
+            self.play_file = play_file;
+            self.roles.clear();
+            info!("strategies reset to apply the new play file");
+        }
     }
 
-    /// Moves a list of bots from their current strategy to an existing strategy.
-    /// 
-    /// # Arguments
-    /// - `bot_ids`: The list of bot ids to move.
-    /// - `strategy_index`: The index of the strategy (in the strategies list) to move the bots to.
-    /// 
-    /// # Example
-    /// ```
-    /// use crabe_decision::manager::bigbro::BigBro;
-    /// let mut bigbro = BigBro::new();
-    /// bigbro.strategies.clear();
-    /// bigbro.move_bots_to_existing_strategy(vec![1, 2, 3], 0);
-    /// assert_eq!(bigbro.strategies.len(), 0);
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::formations::Stop::new(vec![])));
-    /// bigbro.move_bots_to_existing_strategy(vec![1, 2, 3], 0);
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1, 2, 3]);
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::Aligned::new(vec![4])));
-    /// bigbro.move_bots_to_existing_strategy(vec![1, 2, 3], 1);
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![4, 1, 2, 3]);
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::GoLeft::new(5)));
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::testing::GoRight::new(0)));
-    /// bigbro.strategies.push(Box::new(crabe_decision::strategy::formations::Stop::new(vec![2])));
-    /// assert_eq!(bigbro.strategies.len(), 4);
-    /// bigbro.move_bots_to_existing_strategy(vec![5, 0], 3);
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![2, 5, 0]);
-    /// ```
-    pub fn move_bots_to_existing_strategy(&mut self, bot_ids: Vec<u8>, strategy_index: usize) {
-        if strategy_index >= self.strategies.len(){
-            return;
-        }
-        let mut new_strategy_index = strategy_index;
-        for bot_id in bot_ids {
-            let mut new_strategy_ids = self.strategies[new_strategy_index].as_ref().get_ids();
-            if new_strategy_ids.contains(&bot_id) { // if already in the strategy, don't do anything
-                continue;
-            };
-            // if the bot is already in a strategy, remove it from there
-            if let Some(bot_current_strategy_index) = self
-                .strategies
-                .iter()
-                .position(|s| s.get_ids().contains(&bot_id)){
-                    let mut current_strategy_ids = self.strategies[bot_current_strategy_index]
-                        .as_ref()
-                        .get_ids();
-                    if current_strategy_ids.len() == 1 {
-                        self.strategies.remove(bot_current_strategy_index);
-                        if new_strategy_index > bot_current_strategy_index {
-                            new_strategy_index = new_strategy_index - 1;
-                        }
-                    } else {
-                        current_strategy_ids.retain(|&id| id != bot_id);
-                        self.strategies[bot_current_strategy_index].put_ids(current_strategy_ids);
-                    }
-            }
-            new_strategy_ids.push(bot_id);
-            self.strategies[new_strategy_index].put_ids(new_strategy_ids);
+    /// Gives a role to exactly the given robots, see `RoleTable::assign`.
+    /// The robots not fitting the role are reported and the roles are left unchanged.
+    pub fn assign(&mut self, role: Role, ids: Vec<u8>, create: impl FnOnce() -> Box<dyn Strategy>) {
+        if let Err(e) = self.roles.assign(role, ids, create) {
+            error!("failed to assign the role {:?}: {}", role, e);
         }
     }
 
-    /// Moves a bot from its current strategy to a new strategy.
-    /// If the bot is the only one in its current strategy, the strategy is replaced with the new one.
-    /// Otherwise, the bot is removed from the current strategy and added to the new one.
-    /// 
+    /// Gives the role played by a strategy to exactly the given robots, see `RoleTable::assign`.
+    /// The strategy is created through the strategy registry, with its parameters from the play file
+    /// completed by the given ones, and the robots are halted if it can't be created.
+    ///
     /// # Arguments
-    /// - `bot_id`: The id of the bot to move.
-    /// - `strategy`: The new strategy to move the bot to.
-    /// 
-    /// # Example
-    /// ```
-    /// use crabe_decision::manager::bigbro::BigBro;
-    /// let mut bigbro = BigBro::new();
-    /// bigbro.strategies.clear();
-    /// bigbro.move_bot_to_new_strategy(1, Box::new(crabe_decision::strategy::formations::Stop::new(vec![])));
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1]);
-    /// bigbro.move_bot_to_new_strategy(2, Box::new(crabe_decision::strategy::testing::Aligned::new(vec![2])));
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![2]);
-    /// bigbro.move_bot_to_new_strategy(1, Box::new(crabe_decision::strategy::testing::GoLeft::new(1)));
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![2]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![1]);
-    /// ```
-    pub fn move_bot_to_new_strategy(&mut self, bot_id: u8, strategy: Box<dyn Strategy>) {
-        self.move_bots_to_new_strategy(vec![bot_id], strategy);
+    /// - `name`: The name of the strategy playing the role, like `"DefenseWall"`.
+    /// - `ids`: The robots playing the role.
+    /// - `parameters`: The parameters which don't come from the play file, like the team taking a kick off.
+    pub fn assign_strategy(&mut self, name: &'static str, ids: Vec<u8>, parameters: HashMap<String, ParameterValue>) {
+        let role = Role::of_strategy(name);
+        let play_file = &self.play_file;
+        let strategy_ids = if role.single_robot() { ids.clone() } else { vec![] };
+        let create = move || {
+            let mut request = play_file.strategy_request(name, strategy_ids.clone());
+            request.parameters.extend(parameters);
+            create_strategy(&request).unwrap_or_else(|e| {
+                error!("failed to create the strategy {}, the robots are halted: {}", name, e);
+                Box::new(Halt::new(strategy_ids))
+            })
+        };
+        if let Err(e) = self.roles.assign(role, ids, create) {
+            error!("failed to assign the role {:?}: {}", role, e);
+        }
     }
 
-    /// Moves a list of bots from their current strategy to a new strategy.
-    /// 
-    /// # Arguments
-    /// - `bot_ids`: The list of bot ids to move.
-    /// - `strategy`: The new strategy to move the bots to.
-    /// 
-    /// # Example
-    /// ```
-    /// use crabe_decision::manager::bigbro::BigBro;
-    /// let mut bigbro = BigBro::new();
-    /// bigbro.strategies.clear();
-    /// bigbro.move_bots_to_new_strategy(vec![1, 2, 3], Box::new(crabe_decision::strategy::formations::Stop::new(vec![])));
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![1, 2, 3]);
-    /// bigbro.move_bots_to_new_strategy(vec![1, 2, 3], Box::new(crabe_decision::strategy::testing::Aligned::new(vec![4])));
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![4, 1, 2, 3]);
-    /// bigbro.move_bots_to_new_strategy(vec![1, 2, 3], Box::new(crabe_decision::strategy::testing::GoLeft::new(5)));
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![4, 1, 2, 3]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![5]); // strategy can only have one robot
-    /// bigbro.move_bots_to_new_strategy(vec![5, 0], Box::new(crabe_decision::strategy::formations::Stop::new(vec![2])));
-    /// assert_eq!(bigbro.strategies.len(), 2);
-    /// assert_eq!(bigbro.strategies[0].as_ref().get_ids(), vec![4, 1, 3]);
-    /// assert_eq!(bigbro.strategies[1].as_ref().get_ids(), vec![2, 5, 0]);
-    /// bigbro.move_bots_to_new_strategy(vec![0, 1, 2, 3, 4, 5],Box::new(crabe_decision::strategy::formations::Stop::new(vec![])));
-    /// assert_eq!(bigbro.strategies.len(), 1);
-    /// ```
-    pub fn move_bots_to_new_strategy(&mut self, bot_ids: Vec<u8>, mut strategy: Box<dyn Strategy>) {
-        for bot_id in bot_ids {
-            // if the bot is not in the new strategy, add it
-            if !strategy.get_ids().contains(&bot_id) {
-                let mut new_strategy_ids = strategy.get_ids();
-                new_strategy_ids.push(bot_id);
-                strategy.put_ids(new_strategy_ids);
-            }
-        }
-        for bot_id in strategy.get_ids() {
-            self.remove_bot_from_strategies(bot_id);
+    /// Applies several changes of roles at once, see `RoleTable::apply`.
+    pub fn apply(&mut self, assignments: Vec<Assignment>) {
+        if let Err(e) = self.roles.apply(assignments) {
+            error!("failed to change the roles: {}", e);
         }
-        self.strategies.push(strategy);
     }
 
-    
     /// Processes the messages received from the strategies and updates the strategies accordingly.
     ///
     /// # Arguments
@@ -234,82 +125,53 @@ impl BigBro {
         messages.iter().for_each(|m| {
             match &m.message {
                 Message::WantToGoRight => {
-                    let strategy = Box::new(GoRight::new(m.id));
-                    self.move_bot_to_new_strategy(m.id, strategy);
+                    self.apply(vec![Assignment::new(Role::GoRight, vec![m.id]).with_strategy(Box::new(GoRight::new(m.id)))]);
                 }
                 Message::WantToGoLeft => {
-                    let strategy = Box::new(GoLeft::new(m.id));
-                    self.move_bot_to_new_strategy(m.id, strategy);
+                    self.apply(vec![Assignment::new(Role::GoLeft, vec![m.id]).with_strategy(Box::new(GoLeft::new(m.id)))]);
                 }
                 Message::WantToBeAligned => {
-                    //find strategy index with name "Aligned"
-                    if let Some(strategy_index) = self.get_index_strategy_with_name("Aligned") {
-                        self.move_bot_to_existing_strategy(m.id, strategy_index);
-                    } else {
-                        let strategy = Box::new(Aligned::new(vec![m.id]));
-                        self.move_bot_to_new_strategy(m.id, strategy);
+                    if let Err(e) = self.roles.add(Role::Aligned, m.id, || Box::new(Aligned::new(vec![]))) {
+                        error!("failed to align the robot {}: {}", m.id, e);
                     }
                 }
                 Message::AttackerMessage(attacker_message) => {
                     match &attacker_message {
                         AttackerMessage::WantToPassBallTo(receiver_id, passing_trajectory) => {
-                            if let Some(receiver_current_strategy) = self.get_bot_current_strategy(*receiver_id) {
-                                if receiver_current_strategy.name() == "Receiver" {
-                                    return;
-                                }
-                            } 
+                            if self.roles.role_of(*receiver_id) == Some(Role::Receiver) {
+                                return;
+                            }
                             let receiver_strategy = Box::new(Receiver::new(*receiver_id, m.id, *passing_trajectory));
-                            self.move_bot_to_new_strategy(*receiver_id, receiver_strategy);
+                            self.apply(vec![Assignment::new(Role::Receiver, vec![*receiver_id]).with_strategy(receiver_strategy)]);
                         }
                         AttackerMessage::NoNeedReceiver => {
-                            if let Some(receiver_current_strategy_index) = self.get_index_strategy_with_name("Receiver"){
-                                self.strategies.remove(receiver_current_strategy_index);
-                            }
+                            self.roles.remove_role(Role::Receiver);
                         }
                         AttackerMessage::BallPassed(receiver_id) => {
-                            if let Some(receiver_current_strategy_index) = self.get_index_strategy_with_name("Receiver"){
-                                self.strategies.remove(receiver_current_strategy_index);
-                            }
-                            if let Some(attacker_current_strategy_index) = self.get_index_strategy_with_name("Attacker"){
-                                self.strategies.remove(attacker_current_strategy_index);
+                            // the receiver becomes the attacker and the passer supports it, in a single change
+                            let supports: Vec<u8> = self
+                                .roles
+                                .stable_order(Role::SupportAttacker, &[self.roles.ids(Role::SupportAttacker), vec![m.id]].concat())
+                                .into_iter()
+                                .filter(|id| id != receiver_id)
+                                .collect();
+                            let mut support = Assignment::new(Role::SupportAttacker, supports);
+                            if !self.roles.contains(Role::SupportAttacker) {
+                                support = support.with_strategy(Box::new(SupportAttacker::new(vec![])));
                             }
-                            let strategy = Box::new(Attacker::new(*receiver_id));
-                            self.move_bot_to_new_strategy(*receiver_id, strategy);
+                            self.apply(vec![
+                                Assignment::new(Role::Receiver, vec![]),
+                                Assignment::new(Role::Attacker, vec![*receiver_id]).with_strategy(Box::new(Attacker::new(*receiver_id))),
+                                support,
+                            ]);
                         }
                     }
                 }
+                // the acknowledgments are only exchanged between the strategies
+                Message::Ack(_) | Message::Acknowledged(_) => {}
             }
         });
     }
-
-    /// Get the index of a strategy with a given name.
-    /// 
-    /// # Arguments
-    /// - `name`: The name of the strategy.
-    /// 
-    /// # Returns
-    /// The index of the strategy in the strategies list.
-    pub fn get_index_strategy_with_name(&self, name: &str) -> Option<usize> {
-        self.strategies.iter().position(|s| s.name() == name)
-    }
-
-    /// Get the robot current strategy.
-    /// 
-    /// # Arguments
-    /// - `bot_id`: The id of the robot.
-    ///     
-    /// # Returns
-    /// The strategy of the robot.
-    pub fn get_bot_current_strategy(&self, bot_id: u8) -> Option<&Box<dyn Strategy>> {
-        if let Some(strategy) = self.strategies.iter().find(|s| s.get_ids().contains(&bot_id)){
-            return Some(strategy);
-        }
-        None
-    }
-    
-
-
-    
 }
 
 impl Manager for BigBro {
@@ -320,6 +182,7 @@ impl Manager for BigBro {
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     ) {
+        self.reload_play_file();
         match world.data.ref_orders.state {
             GameState::Halted(halted_state) => match halted_state {
                 HaltedState::GameNotStarted => everyone_halt(self, world),
@@ -341,14 +204,18 @@ impl Manager for BigBro {
                 StoppedState::PrepareForGameStart => prepare_start(self, world),
                 StoppedState::BallLeftFieldTouchLine(_) => everyone_halt(self, world),
                 StoppedState::CornerKick(team) => if team == world.team_color{
-                    run_state(self, world, tools_data);
+                    set_piece_state(self, world);
                 }else{
                     everyone_stop_except_keeper(self, world);
                 },
                 StoppedState::GoalKick(_team) => run_state(self, world, tools_data),
                 StoppedState::AimlessKick(_) => everyone_halt(self, world),
                 StoppedState::NoProgressInGame => run_state(self, world, tools_data),
-                StoppedState::PrepareFreekick(team) => prepare_kick_off(self, world, team),
+                StoppedState::PrepareFreekick(team) => if team == world.team_color{
+                    set_piece_state(self, world);
+                }else{
+                    prepare_kick_off(self, world, team);
+                },
                 StoppedState::FoulStop => run_state(self, world, tools_data),
             },
             GameState::Running(running_state) => match running_state {
@@ -359,7 +226,7 @@ impl Manager for BigBro {
                 },
                 RunningState::Penalty(team) => penalty_state(self, world, team),
                 RunningState::FreeKick(team) => if team == world.team_color{
-                    run_state(self, world, tools_data);
+                    set_piece_state(self, world);
                 }else{
                     prepare_kick_off(self, world,team);
                 },
@@ -367,21 +234,31 @@ impl Manager for BigBro {
             }
         }
         
-        // mailbox to grab the messages
-        // (we can't iter the strategies and modify them at the same time so we need to collect the messages first and then process them)
-        let mut messages: Vec<MessageData> = vec![];
-
-        // grab all the messages from the strategies
-        self.strategies.iter().for_each(|s| {
-            messages.extend(s.get_messages().clone());
-        });
-
-        // process the messages
+        // deliver the messages sent during the previous step and process the ones sent to the manager
+        let messages = self.bus.deliver(self.roles.strategies_mut());
         self.process_messages(messages);
 
+        // tell the strategies about the new game state and clear the orders of the robots which changed role
+        self.roles.update(world, action_wrapper);
+
         // execute the strategies
-        self.strategies.iter_mut().for_each(|s| {
+        self.roles.strategies_mut().iter_mut().for_each(|s| {
             s.step(world, tools_data, action_wrapper);
         });
+
+        // collect the messages before the strategies change, they are delivered on the next step
+        self.bus.collect(self.roles.strategies_mut());
+    }
+
+    /// Gives the robots of the strategy the role it plays.
+    /// The decisions of the current game state can take them back on the next steps.
+    fn spawn_strategy(&mut self, strategy: Box<dyn Strategy>) {
+        let role = Role::of_strategy(strategy.name());
+        self.apply(vec![Assignment::new(role, strategy.get_ids()).with_strategy(strategy)]);
+    }
+
+    /// Takes the robots out of their roles, and doesn't give them roles until they are no longer excluded.
+    fn exclude_robots(&mut self, ids: &[u8]) {
+        self.roles.exclude(ids);
     }
 }
@@ -0,0 +1,98 @@
+use crate::action::ActionWrapper;
+use crate::strategy::Strategy;
+use crabe_framework::data::world::game_state::GameState;
+use crabe_framework::data::world::World;
+
+/// The `StrategyLifecycle` struct calls the lifecycle hooks of the strategies of a manager:
+/// when a strategy starts or stops, when its robots change, when the game state changes
+/// and when the actions of one of its robots failed.
+/// The orders of the robots released by a strategy are cleared before the next step,
+/// so that a robot doesn't keep executing the orders of a strategy it left.
+#[derive(Default)]
+pub struct StrategyLifecycle {
+    /// The game state seen at the previous step.
+    game_state: Option<GameState>,
+    /// The robots released by a strategy since the previous step.
+    released: Vec<u8>,
+}
+
+impl StrategyLifecycle {
+    /// Starts a strategy.
+    pub fn enter(&mut self, strategy: &mut dyn Strategy) {
+        strategy.on_enter();
+    }
+
+    /// Stops a strategy, its robots are released.
+    pub fn exit(&mut self, strategy: &mut dyn Strategy) {
+        strategy.on_exit();
+        self.released.extend(strategy.get_ids());
+    }
+
+    /// Changes the robots of a strategy, telling it which robots were added and removed.
+    /// The removed robots are released.
+    pub fn change_robots(&mut self, strategy: &mut dyn Strategy, ids: Vec<u8>) {
+        let previous = strategy.get_ids();
+        if previous == ids {
+            return;
+        }
+        strategy.put_ids(ids.clone());
+        for id in previous.iter().filter(|id| !ids.contains(id)) {
+            strategy.on_robot_removed(*id);
+            self.released.push(*id);
+        }
+        for id in ids.iter().filter(|id| !previous.contains(id)) {
+            strategy.on_robot_added(*id);
+        }
+    }
+
+    /// Gives a new strategy to its robots, removing them from the other strategies
+    /// and stopping the strategies left without robots.
+    pub fn reassign(
+        &mut self,
+        strategies: &mut Vec<Box<dyn Strategy>>,
+        mut strategy: Box<dyn Strategy>,
+    ) {
+        let ids = strategy.get_ids();
+        strategies.retain_mut(|s| {
+            let remaining: Vec<u8> = s
+                .get_ids()
+                .into_iter()
+                .filter(|id| !ids.contains(id))
+                .collect();
+            if remaining.is_empty() {
+                self.exit(s.as_mut());
+                return false;
+            }
+            self.change_robots(s.as_mut(), remaining);
+            true
+        });
+        self.enter(strategy.as_mut());
+        strategies.push(strategy);
+    }
+
+    /// Tells the strategies that the game state changed and which actions of their robots failed,
+    /// and clears the orders of the released robots.
+    /// It is called by the manager before stepping the strategies.
+    pub fn update(
+        &mut self,
+        world: &World,
+        strategies: &mut [Box<dyn Strategy>],
+        action_wrapper: &mut ActionWrapper,
+    ) {
+        let game_state = world.data.ref_orders.state;
+        if let Some(previous) = self.game_state.filter(|previous| *previous != game_state) {
+            for strategy in strategies.iter_mut() {
+                strategy.on_game_state_change(previous, game_state);
+            }
+        }
+        self.game_state = Some(game_state);
+        for id in action_wrapper.take_failed() {
+            if let Some(strategy) = strategies.iter_mut().find(|s| s.get_ids().contains(&id)) {
+                strategy.on_action_failed(id);
+            }
+        }
+        for id in self.released.drain(..) {
+            action_wrapper.clear(id);
+        }
+    }
+}
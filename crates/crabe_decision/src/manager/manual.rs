@@ -1,5 +1,7 @@
 use crate::action::ActionWrapper;
+use crate::manager::lifecycle::StrategyLifecycle;
 use crate::manager::Manager;
+use crate::message::MessageBus;
 use crate::strategy::testing::{Square, TestVisionMoveTo};
 use crate::strategy::formations::MoveAwayFromBall;
 use crate::strategy::Strategy;
@@ -10,18 +12,33 @@ use crabe_framework::data::world::World;
 /// added to its list.
 /// It's used for testing individual strategies only and not meant to be used during an actual game.
 ///
-/// To add a strategy, simply create a new instance of the desired strategy and add it to the
-/// `strategies` field in the `new()` method of the `Manual` struct.
+/// The strategies can be given with `with_strategies`, like the ones picked from the command line
+/// with `--manager manual --strategy Name=id,id`, otherwise the ones of the `new()` method are used.
 #[derive(Default)]
 pub struct Manual {
     strategies: Vec<Box<dyn Strategy>>,
+    /// The messages between the strategies, the ones sent to the manager are ignored.
+    bus: MessageBus,
+    /// Calls the lifecycle hooks of the strategies.
+    lifecycle: StrategyLifecycle,
 }
 
 impl Manual {
     /// Creates a new `Manual` instance with the desired strategies to test.
     pub fn new() -> Self {
+        Self::with_strategies(vec![Box::new(Square::new(0))])
+    }
+
+    /// Creates a new `Manual` instance executing the given strategies.
+    pub fn with_strategies(mut strategies: Vec<Box<dyn Strategy>>) -> Self {
+        let mut lifecycle = StrategyLifecycle::default();
+        for strategy in strategies.iter_mut() {
+            lifecycle.enter(strategy.as_mut());
+        }
         Self {
-            strategies: vec![Box::new(Square::new(0))],
+            strategies,
+            bus: MessageBus::default(),
+            lifecycle,
         }
     }
 }
@@ -34,7 +51,27 @@ impl Manager for Manual {
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     ) {
-        self.strategies
-            .retain_mut(|s| !s.step(world, tools_data, action_wrapper));
+        self.lifecycle.update(world, &mut self.strategies, action_wrapper);
+        self.bus.deliver(&mut self.strategies);
+        let finished: Vec<bool> = self
+            .strategies
+            .iter_mut()
+            .map(|s| s.step(world, tools_data, action_wrapper))
+            .collect();
+        // collect the messages before removing the finished strategies
+        self.bus.collect(&mut self.strategies);
+        let mut finished = finished.into_iter();
+        self.strategies.retain_mut(|s| {
+            if finished.next().unwrap_or(false) {
+                self.lifecycle.exit(s.as_mut());
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Adds the strategy to the list, taking its robots from the other strategies.
+    fn spawn_strategy(&mut self, strategy: Box<dyn Strategy>) {
+        self.lifecycle.reassign(&mut self.strategies, strategy);
     }
 }
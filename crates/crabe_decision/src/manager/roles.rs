@@ -0,0 +1,566 @@
+use crate::action::ActionWrapper;
+use crate::manager::lifecycle::StrategyLifecycle;
+use crate::strategy::Strategy;
+use crabe_framework::data::world::World;
+
+/// The role given by `BigBro` to a group of robots, each role being played by one strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The robots stay still, played by `Halt`.
+    Halt,
+    /// The robots keep away from the ball, played by `MoveAwayFromBall`.
+    Stop,
+    /// The robots go to their positions before the game starts, played by `PrepareStart`.
+    PrepareStart,
+    /// The robots go to their positions for a kick off, played by `PrepareKickOff`.
+    PrepareKickOff,
+    /// The robot defends our goal, played by `GoalKeeper`.
+    GoalKeeper,
+    /// The robots defend between the ball and our goal, played by `DefenseWall`.
+    DefenseWall,
+    /// The robot takes the ball, played by `Attacker`.
+    Attacker,
+    /// The robot receives a pass of the attacker, played by `Receiver`.
+    Receiver,
+    /// The robots get open for a pass, played by `SupportAttacker`.
+    SupportAttacker,
+    /// The robots mark the threatening enemies, played by `ManMarking`.
+    Marker,
+    /// The robots take our free kicks and corner kicks, played by `SetPiece`.
+    SetPiece,
+    /// The testing roles, played by the strategies of the same name.
+    GoLeft,
+    GoRight,
+    Aligned,
+    /// Any other strategy, like the ones started from the tools, by strategy name.
+    Custom(&'static str),
+}
+
+impl Role {
+    /// Returns true if the role is played by a single robot.
+    pub fn single_robot(&self) -> bool {
+        matches!(
+            self,
+            Role::GoalKeeper | Role::Attacker | Role::Receiver | Role::GoLeft | Role::GoRight
+        )
+    }
+
+    /// Returns the role played by a strategy, from its name.
+    pub fn of_strategy(name: &'static str) -> Self {
+        match name {
+            "Halt" => Role::Halt,
+            "MoveAwayFromBall" => Role::Stop,
+            "PrepareStart" => Role::PrepareStart,
+            "PrepareKickOff" => Role::PrepareKickOff,
+            "GoalKeeper" => Role::GoalKeeper,
+            "DefenseWall" => Role::DefenseWall,
+            "Attacker" => Role::Attacker,
+            "Receiver" => Role::Receiver,
+            "SupportAttacker" => Role::SupportAttacker,
+            "ManMarking" => Role::Marker,
+            "SetPiece" => Role::SetPiece,
+            "GoLeft" => Role::GoLeft,
+            "GoRight" => Role::GoRight,
+            "Aligned" => Role::Aligned,
+            name => Role::Custom(name),
+        }
+    }
+}
+
+/// A change of the robots playing a role, applied by `RoleTable::apply`.
+pub struct Assignment {
+    /// The role to change.
+    pub role: Role,
+    /// The robots playing the role after the change, none to remove the role.
+    pub ids: Vec<u8>,
+    /// The new strategy playing the role, None to keep the current one.
+    pub strategy: Option<Box<dyn Strategy>>,
+}
+
+impl Assignment {
+    /// Gives a role to the robots, keeping the strategy currently playing it.
+    pub fn new(role: Role, ids: Vec<u8>) -> Self {
+        Self {
+            role,
+            ids,
+            strategy: None,
+        }
+    }
+
+    /// Plays the role with a new strategy.
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+}
+
+/// The `RoleTable` struct keeps the role of each robot and the strategy playing each role.
+/// It guarantees that a robot has at most one role, that the robots given a role play exactly that role,
+/// that each role is played by one strategy with at least one robot,
+/// and that the single robot roles, like the goal keeper, are played by exactly one robot.
+/// The lifecycle hooks of the strategies are called when their roles change.
+///
+/// # Example
+/// ```
+/// use crabe_decision::manager::roles::{Assignment, Role, RoleTable};
+/// use crabe_decision::strategy::defensive::{DefenseWall, GoalKeeper};
+/// use crabe_decision::strategy::offensive::Attacker;
+///
+/// let mut roles = RoleTable::default();
+/// roles.assign(Role::DefenseWall, vec![1, 2, 4], || Box::new(DefenseWall::new(vec![]))).unwrap();
+/// roles.assign(Role::GoalKeeper, vec![3], || Box::new(GoalKeeper::new(3, vec![]))).unwrap();
+/// assert_eq!(roles.ids(Role::DefenseWall), vec![1, 2, 4]);
+/// assert_eq!(roles.role_of(3), Some(Role::GoalKeeper));
+///
+/// // A robot taking a new role leaves its previous one
+/// roles.assign(Role::Attacker, vec![2], || Box::new(Attacker::new(2))).unwrap();
+/// assert_eq!(roles.ids(Role::DefenseWall), vec![1, 4]);
+/// assert_eq!(roles.role_of(2), Some(Role::Attacker));
+///
+/// // There is a single keeper, and a role left without robots is removed
+/// assert!(roles.assign(Role::GoalKeeper, vec![3, 5], || Box::new(GoalKeeper::new(3, vec![]))).is_err());
+/// roles.assign(Role::GoalKeeper, vec![1], || Box::new(GoalKeeper::new(1, vec![]))).unwrap();
+/// assert_eq!(roles.ids(Role::GoalKeeper), vec![1]);
+/// assert_eq!(roles.role_of(3), None);
+/// roles.assign(Role::Attacker, vec![4], || Box::new(Attacker::new(4))).unwrap();
+/// assert!(!roles.contains(Role::DefenseWall));
+/// assert!(roles.check().is_ok());
+/// ```
+#[derive(Default)]
+pub struct RoleTable {
+    roles: Vec<Role>,
+    /// The strategy playing each role, at the same index.
+    strategies: Vec<Box<dyn Strategy>>,
+    lifecycle: StrategyLifecycle,
+    /// The robots which can't be given a role, like the ones pulled off the field by the guards.
+    excluded: Vec<u8>,
+}
+
+impl RoleTable {
+    fn index(&self, role: Role) -> Option<usize> {
+        self.roles.iter().position(|r| *r == role)
+    }
+
+    /// Returns true if some robots play the role.
+    pub fn contains(&self, role: Role) -> bool {
+        self.index(role).is_some()
+    }
+
+    /// Returns the robots playing a role, none if nobody plays it.
+    pub fn ids(&self, role: Role) -> Vec<u8> {
+        self.index(role)
+            .map(|index| self.strategies[index].get_ids())
+            .unwrap_or_default()
+    }
+
+    /// Returns the role of a robot, None if it has none.
+    pub fn role_of(&self, id: u8) -> Option<Role> {
+        self.roles
+            .iter()
+            .zip(&self.strategies)
+            .find(|(_, strategy)| strategy.get_ids().contains(&id))
+            .map(|(role, _)| *role)
+    }
+
+    /// Returns the strategies playing the roles, to step them.
+    pub fn strategies_mut(&mut self) -> &mut [Box<dyn Strategy>] {
+        &mut self.strategies
+    }
+
+    /// Tells the strategies that the game state changed and clears the orders of the robots
+    /// that left a role, see `StrategyLifecycle::update`. It is called before stepping the strategies.
+    pub fn update(&mut self, world: &World, action_wrapper: &mut ActionWrapper) {
+        self.lifecycle
+            .update(world, &mut self.strategies, action_wrapper);
+    }
+
+    /// Removes all the roles.
+    pub fn clear(&mut self) {
+        self.roles.clear();
+        for mut strategy in self.strategies.drain(..) {
+            self.lifecycle.exit(strategy.as_mut());
+        }
+    }
+
+    /// Orders robots that will play a role, the ones already playing it first and in the same order,
+    /// so that a strategy keeping state per robot keeps it.
+    pub fn stable_order(&self, role: Role, ids: &[u8]) -> Vec<u8> {
+        let mut ordered: Vec<u8> = self
+            .ids(role)
+            .into_iter()
+            .filter(|id| ids.contains(id))
+            .collect();
+        for id in ids {
+            if !ordered.contains(id) {
+                ordered.push(*id);
+            }
+        }
+        ordered
+    }
+
+    /// Gives a role to exactly the given robots, taking them from their previous roles.
+    /// The strategy currently playing the role is kept, otherwise it is created.
+    ///
+    /// # Arguments
+    /// - `role`: The role to give.
+    /// - `ids`: The robots playing the role, none to remove the role.
+    /// - `create`: Creates the strategy playing the role if nobody plays it yet.
+    ///
+    /// # Returns
+    /// An error if the robots don't fit the role, the table is then unchanged.
+    pub fn assign(
+        &mut self,
+        role: Role,
+        ids: Vec<u8>,
+        create: impl FnOnce() -> Box<dyn Strategy>,
+    ) -> Result<(), String> {
+        let mut assignment = Assignment::new(role, ids);
+        if !self.contains(role) && !assignment.ids.is_empty() {
+            assignment.strategy = Some(create());
+        }
+        self.apply(vec![assignment])
+    }
+
+    /// Adds a robot to a role, taking it from its previous role.
+    pub fn add(
+        &mut self,
+        role: Role,
+        id: u8,
+        create: impl FnOnce() -> Box<dyn Strategy>,
+    ) -> Result<(), String> {
+        let ids = self.stable_order(role, &[self.ids(role), vec![id]].concat());
+        self.assign(role, ids, create)
+    }
+
+    /// Removes a role, its robots are left without role.
+    pub fn remove_role(&mut self, role: Role) {
+        if let Some(index) = self.index(role) {
+            self.roles.remove(index);
+            let mut strategy = self.strategies.remove(index);
+            self.lifecycle.exit(strategy.as_mut());
+        }
+    }
+
+    /// Takes robots out of their roles and leaves them out of the next changes, until they are no longer excluded.
+    ///
+    /// # Arguments
+    /// - `ids`: All the robots which can't be given a role, replacing the previous ones.
+    pub fn exclude(&mut self, ids: &[u8]) {
+        self.excluded = ids.to_vec();
+        self.take_robots(ids, None);
+    }
+
+    /// Removes a robot from its role, the role being removed if it is left without robots.
+    pub fn remove_robot(&mut self, id: u8) {
+        self.take_robots(&[id], None);
+    }
+
+    /// Removes robots from their roles, except from the given one.
+    fn take_robots(&mut self, ids: &[u8], except: Option<Role>) {
+        let mut index = 0;
+        while index < self.roles.len() {
+            let current = self.strategies[index].get_ids();
+            let remaining: Vec<u8> = current
+                .iter()
+                .copied()
+                .filter(|id| !ids.contains(id))
+                .collect();
+            if Some(self.roles[index]) == except || remaining.len() == current.len() {
+                index += 1;
+            } else if remaining.is_empty() {
+                // the single robot strategies ignore an empty list of robots, they are removed instead
+                self.roles.remove(index);
+                let mut strategy = self.strategies.remove(index);
+                self.lifecycle.exit(strategy.as_mut());
+            } else {
+                self.lifecycle
+                    .change_robots(self.strategies[index].as_mut(), remaining);
+                index += 1;
+            }
+        }
+    }
+
+    /// Applies several changes of roles at once, like a pass switching the attacker and the receiver.
+    /// The changes are checked together before changing anything, so that the table goes from one
+    /// consistent state to another. The excluded robots are left out of the changes.
+    ///
+    /// # Returns
+    /// An error if a robot is given two roles, if the robots don't fit a role, if a new role has no strategy
+    /// or if a new strategy ignores the robots given to it. The table is then unchanged.
+    /// An error is also returned if a strategy already in the table ignores its new robots,
+    /// which can only be seen once they are given to it.
+    ///
+    /// # Example
+    /// ```
+    /// use crabe_decision::manager::roles::{Assignment, Role, RoleTable};
+    /// use crabe_decision::strategy::offensive::{Attacker, SupportAttacker};
+    ///
+    /// let mut roles = RoleTable::default();
+    /// roles.assign(Role::Attacker, vec![1], || Box::new(Attacker::new(1))).unwrap();
+    /// roles.assign(Role::SupportAttacker, vec![2, 4], || Box::new(SupportAttacker::new(vec![]))).unwrap();
+    ///
+    /// // The robot 2 received the pass, the passer supports it
+    /// roles.apply(vec![
+    ///     Assignment::new(Role::Attacker, vec![2]).with_strategy(Box::new(Attacker::new(2))),
+    ///     Assignment::new(Role::SupportAttacker, vec![4, 1]),
+    /// ]).unwrap();
+    /// assert_eq!(roles.ids(Role::Attacker), vec![2]);
+    /// assert_eq!(roles.ids(Role::SupportAttacker), vec![4, 1]);
+    ///
+    /// // A robot can't have two roles, and nothing is changed
+    /// assert!(roles.apply(vec![
+    ///     Assignment::new(Role::Attacker, vec![4]),
+    ///     Assignment::new(Role::SupportAttacker, vec![4, 2]),
+    /// ]).is_err());
+    /// assert_eq!(roles.ids(Role::Attacker), vec![2]);
+    /// ```
+    pub fn apply(&mut self, mut assignments: Vec<Assignment>) -> Result<(), String> {
+        for assignment in assignments.iter_mut() {
+            assignment.ids.retain(|id| !self.excluded.contains(id));
+        }
+        for (i, assignment) in assignments.iter().enumerate() {
+            if assignment.role.single_robot() && assignment.ids.len() > 1 {
+                return Err(format!(
+                    "the role {:?} is played by a single robot, got {:?}",
+                    assignment.role, assignment.ids
+                ));
+            }
+            if !assignment.ids.is_empty()
+                && assignment.strategy.is_none()
+                && !self.contains(assignment.role)
+            {
+                return Err(format!(
+                    "no strategy to play the role {:?}",
+                    assignment.role
+                ));
+            }
+            for other in &assignments[i + 1..] {
+                if other.role == assignment.role {
+                    return Err(format!("the role {:?} is assigned twice", assignment.role));
+                }
+                if let Some(id) = other.ids.iter().find(|id| assignment.ids.contains(id)) {
+                    return Err(format!(
+                        "the robot {} can't play both {:?} and {:?}",
+                        id, assignment.role, other.role
+                    ));
+                }
+            }
+        }
+        for assignment in assignments.iter_mut() {
+            if assignment.ids.is_empty() {
+                continue;
+            }
+            if let Some(strategy) = assignment.strategy.as_mut() {
+                strategy.put_ids(assignment.ids.clone());
+                if !same_robots(&strategy.get_ids(), &assignment.ids) {
+                    return Err(format!(
+                        "the strategy {} playing {:?} ignores the robots {:?} given to it",
+                        strategy.name(),
+                        assignment.role,
+                        assignment.ids
+                    ));
+                }
+            }
+        }
+
+        let expected: Vec<(Role, Vec<u8>)> = assignments
+            .iter()
+            .map(|assignment| (assignment.role, assignment.ids.clone()))
+            .collect();
+        for assignment in assignments {
+            self.take_robots(&assignment.ids, Some(assignment.role));
+            if assignment.ids.is_empty() {
+                self.remove_role(assignment.role);
+                continue;
+            }
+            match (assignment.strategy, self.index(assignment.role)) {
+                (Some(mut strategy), index) => {
+                    strategy.put_ids(assignment.ids);
+                    let index = match index {
+                        Some(index) => {
+                            // the previous strategy stops before the new one starts
+                            self.lifecycle.exit(self.strategies[index].as_mut());
+                            self.strategies[index] = strategy;
+                            index
+                        }
+                        None => {
+                            self.roles.push(assignment.role);
+                            self.strategies.push(strategy);
+                            self.strategies.len() - 1
+                        }
+                    };
+                    self.lifecycle.enter(self.strategies[index].as_mut());
+                }
+                (None, Some(index)) => self
+                    .lifecycle
+                    .change_robots(self.strategies[index].as_mut(), assignment.ids),
+                (None, None) => {}
+            }
+        }
+
+        for (role, ids) in expected {
+            if !same_robots(&self.ids(role), &ids) {
+                return Err(format!(
+                    "the role {:?} is played by {:?} instead of {:?}",
+                    role,
+                    self.ids(role),
+                    ids
+                ));
+            }
+        }
+        self.check()
+    }
+
+    /// Checks the invariants of the table: each robot has at most one role,
+    /// each role has at least one robot and the single robot roles have exactly one.
+    /// With `apply` checking that the robots given a role play exactly that role, they can only be broken
+    /// by a strategy ignoring the robots given to it.
+    pub fn check(&self) -> Result<(), String> {
+        let mut seen: Vec<u8> = vec![];
+        for (role, strategy) in self.roles.iter().zip(&self.strategies) {
+            let ids = strategy.get_ids();
+            if ids.is_empty() {
+                return Err(format!("the role {:?} has no robot", role));
+            }
+            if role.single_robot() && ids.len() != 1 {
+                return Err(format!("the role {:?} has several robots: {:?}", role, ids));
+            }
+            if let Some(id) = ids.iter().find(|id| seen.contains(id)) {
+                return Err(format!("the robot {} has several roles", id));
+            }
+            seen.extend(ids);
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if the two lists have the same robots, in any order.
+fn same_robots(a: &[u8], b: &[u8]) -> bool {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort();
+    b.sort();
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::offensive::{Attacker, Receiver, SupportAttacker};
+    use crabe_math::shape::Line;
+    use nalgebra::Point2;
+
+    /// The attacker 1 passes to the receiver 2, the robots 3 and 4 support them.
+    fn passing_roles() -> RoleTable {
+        let mut roles = RoleTable::default();
+        let trajectory = Line::new(Point2::new(0., 0.), Point2::new(1., 0.));
+        roles
+            .assign(Role::Attacker, vec![1], || Box::new(Attacker::new(1)))
+            .unwrap();
+        roles
+            .assign(Role::Receiver, vec![2], || {
+                Box::new(Receiver::new(2, 1, trajectory))
+            })
+            .unwrap();
+        roles
+            .assign(Role::SupportAttacker, vec![3, 4], || {
+                Box::new(SupportAttacker::new(vec![]))
+            })
+            .unwrap();
+        roles
+    }
+
+    #[test]
+    fn new_single_robot_strategy_ignoring_its_robots() {
+        let mut roles = passing_roles();
+        // the marker role has several robots, but the attacker strategy only plays one
+        let result =
+            roles
+                .apply(vec![Assignment::new(Role::Marker, vec![3, 4])
+                    .with_strategy(Box::new(Attacker::new(3)))]);
+        assert!(result.is_err());
+        assert!(!roles.contains(Role::Marker));
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![3, 4]);
+        assert!(roles.check().is_ok());
+    }
+
+    #[test]
+    fn single_robot_strategy_in_the_table_ignoring_its_robots() {
+        let mut roles = passing_roles();
+        roles
+            .apply(vec![
+                Assignment::new(Role::Marker, vec![3]).with_strategy(Box::new(Attacker::new(3)))
+            ])
+            .unwrap();
+        // the robot 4 leaves the supports but the strategy keeps the robot 3 only
+        assert!(roles
+            .apply(vec![Assignment::new(Role::Marker, vec![3, 4])])
+            .is_err());
+    }
+
+    #[test]
+    fn duplicate_ids_across_assignments() {
+        let mut roles = passing_roles();
+        let result = roles.apply(vec![
+            Assignment::new(Role::Attacker, vec![3]).with_strategy(Box::new(Attacker::new(3))),
+            Assignment::new(Role::SupportAttacker, vec![4, 3]),
+        ]);
+        assert!(result.is_err());
+        assert_eq!(roles.ids(Role::Attacker), vec![1]);
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![3, 4]);
+
+        let result = roles.apply(vec![
+            Assignment::new(Role::SupportAttacker, vec![3]),
+            Assignment::new(Role::SupportAttacker, vec![4]),
+        ]);
+        assert!(result.is_err());
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![3, 4]);
+    }
+
+    #[test]
+    fn ball_passed_swap() {
+        let mut roles = passing_roles();
+        // the changes made by BigBro when the ball is passed
+        let supports = roles.stable_order(Role::SupportAttacker, &[3, 4, 1]);
+        roles
+            .apply(vec![
+                Assignment::new(Role::Receiver, vec![]),
+                Assignment::new(Role::Attacker, vec![2]).with_strategy(Box::new(Attacker::new(2))),
+                Assignment::new(Role::SupportAttacker, supports),
+            ])
+            .unwrap();
+        assert!(!roles.contains(Role::Receiver));
+        assert_eq!(roles.role_of(2), Some(Role::Attacker));
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![3, 4, 1]);
+        for id in 1..=4 {
+            assert!(roles.role_of(id).is_some());
+        }
+        assert!(roles.check().is_ok());
+    }
+
+    #[test]
+    fn excluded_robots_get_no_role() {
+        let mut roles = passing_roles();
+        roles.exclude(&[1, 3]);
+        assert_eq!(roles.role_of(1), None);
+        assert!(!roles.contains(Role::Attacker));
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![4]);
+
+        // the decisions can't give them a role back
+        roles
+            .assign(Role::SupportAttacker, vec![3, 4], || {
+                Box::new(SupportAttacker::new(vec![]))
+            })
+            .unwrap();
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![4]);
+
+        // until they are back on the field
+        roles.exclude(&[]);
+        roles
+            .assign(Role::SupportAttacker, vec![3, 4], || {
+                Box::new(SupportAttacker::new(vec![]))
+            })
+            .unwrap();
+        assert_eq!(roles.ids(Role::SupportAttacker), vec![3, 4]);
+    }
+}
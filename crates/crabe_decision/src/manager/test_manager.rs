@@ -1,5 +1,7 @@
 use crate::action::ActionWrapper;
+use crate::manager::lifecycle::StrategyLifecycle;
 use crate::manager::Manager;
+use crate::message::MessageBus;
 use crate::strategy::testing::Prembule;
 use crate::strategy::testing::TestVisionMoveTo;
 use crate::strategy::Strategy;
@@ -16,13 +18,24 @@ const ROBOT_ID: u8 = 0;
 /// Test_manager add before your strategie the prembule to testing the bot to know if it is working or not
 pub struct TestManager {
     strategies: Vec<Box<dyn Strategy>>,
+    /// The messages between the strategies, the ones sent to the manager are ignored.
+    bus: MessageBus,
+    /// Calls the lifecycle hooks of the strategies.
+    lifecycle: StrategyLifecycle,
 }
 
 impl TestManager {
     /// Creates a new `TestManager` instance with the desired strategies to test.
     pub fn new() -> Self {
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Prembule::new(ROBOT_ID)),Box::new(TestVisionMoveTo::new(vec![ROBOT_ID]))];
+        let mut lifecycle = StrategyLifecycle::default();
+        for strategy in strategies.iter_mut() {
+            lifecycle.enter(strategy.as_mut());
+        }
         Self {
-            strategies: vec![Box::new(Prembule::new(ROBOT_ID)),Box::new(TestVisionMoveTo::new(vec![ROBOT_ID]))],
+            strategies,
+            bus: MessageBus::default(),
+            lifecycle,
         }
     }
 }
@@ -35,16 +48,26 @@ impl Manager for TestManager {
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     ) {
+        self.lifecycle.update(world, &mut self.strategies, action_wrapper);
+        self.bus.deliver(&mut self.strategies);
         //execute only first strategy step then remove it
         if self.strategies.len() > 0 {
             let end = self.strategies[0].step(world, tools_data, action_wrapper);
+            self.bus.collect(&mut self.strategies);
             if end{
-                self.strategies.remove(0);
+                let mut strategy = self.strategies.remove(0);
+                self.lifecycle.exit(strategy.as_mut());
             }
         }
         
         
         
         
+    }
+
+    /// Queues the strategy after the current ones, taking its robots from them.
+    /// The strategies left without robots are stopped.
+    fn spawn_strategy(&mut self, strategy: Box<dyn Strategy>) {
+        self.lifecycle.reassign(&mut self.strategies, strategy);
     }
 }
@@ -1,31 +1,107 @@
 use crabe_math::shape::Line;
 
+/// The `bus` module delivers the messages between the strategies and their manager, once per frame.
+pub mod bus;
+pub use self::bus::{Envelope, MessageBus};
+
 // Possible messages types to transmit
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Message {
     WantToGoRight,
     WantToGoLeft,
     WantToBeAligned,
     AttackerMessage(AttackerMessage),
+    /// Acknowledges the message with the given sequence number, see `MessageData::ack`.
+    Ack(u64),
+    /// Tells the sender of a message asking for an acknowledgment that it was received.
+    Acknowledged(Box<Message>),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum AttackerMessage{
     WantToPassBallTo(u8, Line),
     NoNeedReceiver,
     BallPassed(u8),
 }
 
+/// The topic of a message, used to deliver it to the strategies subscribed to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// The messages of the testing strategies.
+    Testing,
+    /// The messages about the passes between the attacker and the receivers.
+    Pass,
+    /// The acknowledgments.
+    Ack,
+}
+
+impl Message {
+    /// Returns the topic of the message.
+    pub fn topic(&self) -> Topic {
+        match self {
+            Message::WantToGoRight | Message::WantToGoLeft | Message::WantToBeAligned => {
+                Topic::Testing
+            }
+            Message::AttackerMessage(_) => Topic::Pass,
+            Message::Ack(_) | Message::Acknowledged(_) => Topic::Ack,
+        }
+    }
+}
+
+/// The recipient of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recipient {
+    /// The manager, which processes the message to change the strategies.
+    Manager,
+    /// The strategy moving the given robot.
+    Robot(u8),
+    /// The strategies with the given name.
+    Strategy(&'static str),
+    /// The strategies subscribed to the topic of the message.
+    Subscribers,
+}
 
 // Data type to share information from the bot to the manager
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct MessageData {
     pub message: Message,
+    /// The id of the robot sending the message.
     pub id: u8,
+    /// The recipient of the message, the manager by default.
+    pub to: Recipient,
+    /// True if the message is delivered again on each frame until the recipient acknowledges it.
+    pub needs_ack: bool,
 }
 
 impl MessageData {
     pub fn new(message: Message, id: u8) -> Self {
-        Self { message, id }
+        Self {
+            message,
+            id,
+            to: Recipient::Manager,
+            needs_ack: false,
+        }
     }
-}
\ No newline at end of file
+
+    /// Sends the message to the given recipient instead of the manager.
+    pub fn to(mut self, recipient: Recipient) -> Self {
+        self.to = recipient;
+        self
+    }
+
+    /// Delivers the message again on each frame until the recipient acknowledges it.
+    pub fn with_ack(mut self) -> Self {
+        self.needs_ack = true;
+        self
+    }
+
+    /// Creates the acknowledgment of a received message.
+    /// Its sender then receives a `Message::Acknowledged` with the content of the message.
+    ///
+    /// # Arguments
+    /// - `envelope`: The received message.
+    /// - `id`: The id of the robot acknowledging the message.
+    pub fn ack(envelope: &Envelope, id: u8) -> Self {
+        Self::new(Message::Ack(envelope.seq), id).to(Recipient::Robot(envelope.data.id))
+    }
+}
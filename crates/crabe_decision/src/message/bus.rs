@@ -0,0 +1,158 @@
+use crate::message::{Message, MessageData, Recipient};
+use crate::strategy::Strategy;
+use log::warn;
+
+/// Number of frames a message asking for an acknowledgment is delivered again before being dropped.
+const ACK_TIMEOUT_FRAMES: u32 = 30;
+
+/// A message posted on the bus, with the sequence number used to acknowledge it.
+#[derive(Clone, Debug)]
+pub struct Envelope {
+    /// The sequence number of the message, unique on the bus.
+    pub seq: u64,
+    pub data: MessageData,
+}
+
+/// A message waiting for its acknowledgment.
+struct Pending {
+    envelope: Envelope,
+    frames_left: u32,
+}
+
+/// The `MessageBus` struct carries the messages of the strategies, once per frame:
+/// the messages sent during a step are collected after it,
+/// then delivered to their recipients at the beginning of the next step and cleared.
+/// The messages asking for an acknowledgment are delivered again on each frame
+/// until their recipient acknowledges them, or until they time out.
+///
+/// # Example
+/// ```
+/// use crabe_decision::message::{Message, MessageBus, MessageData, Recipient};
+/// use crabe_decision::strategy::formations::Halt;
+/// use crabe_decision::strategy::Strategy;
+///
+/// let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Halt::new(vec![1]))];
+/// let mut bus = MessageBus::default();
+/// bus.post(MessageData::new(Message::WantToGoLeft, 1));
+/// bus.post(MessageData::new(Message::WantToGoRight, 2).to(Recipient::Robot(1)).with_ack());
+///
+/// // The messages to the manager are returned, the others are given to the strategies
+/// let to_manager = bus.deliver(&mut strategies);
+/// assert_eq!(to_manager.len(), 1);
+/// assert_eq!(bus.pending_count(), 1);
+/// // The delivered messages are cleared, the ones waiting for an acknowledgment are kept
+/// assert!(bus.deliver(&mut strategies).is_empty());
+/// assert_eq!(bus.pending_count(), 1);
+/// ```
+#[derive(Default)]
+pub struct MessageBus {
+    next_seq: u64,
+    /// The messages posted since the last delivery.
+    queue: Vec<Envelope>,
+    /// The delivered messages waiting for their acknowledgment.
+    pending: Vec<Pending>,
+}
+
+impl MessageBus {
+    /// Posts a message, delivered on the next call to `deliver`.
+    /// An acknowledgment stops the redelivery of its message and notifies its sender.
+    pub fn post(&mut self, data: MessageData) {
+        if let Message::Ack(seq) = data.message {
+            if let Some(index) = self
+                .pending
+                .iter()
+                .position(|pending| pending.envelope.seq == seq)
+            {
+                let acknowledged = self.pending.remove(index).envelope.data;
+                self.post(
+                    MessageData::new(
+                        Message::Acknowledged(Box::new(acknowledged.message)),
+                        data.id,
+                    )
+                    .to(Recipient::Robot(acknowledged.id)),
+                );
+            }
+            return;
+        }
+        self.queue.push(Envelope {
+            seq: self.next_seq,
+            data,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Collects the messages sent by the strategies during their step.
+    pub fn collect(&mut self, strategies: &mut [Box<dyn Strategy>]) {
+        for strategy in strategies.iter_mut() {
+            for message in strategy.take_messages() {
+                self.post(message);
+            }
+        }
+    }
+
+    /// Returns the number of delivered messages waiting for their acknowledgment.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns true if the strategy is a recipient of the message.
+    fn is_recipient(strategy: &dyn Strategy, envelope: &Envelope) -> bool {
+        match envelope.data.to {
+            Recipient::Manager => false,
+            Recipient::Robot(id) => strategy.get_ids().contains(&id),
+            Recipient::Strategy(name) => strategy.name() == name,
+            Recipient::Subscribers => strategy.topics().contains(&envelope.data.message.topic()),
+        }
+    }
+
+    /// Delivers the posted messages to the strategies, with the ones still waiting for their acknowledgment,
+    /// then clears them.
+    ///
+    /// # Arguments
+    /// - `strategies`: The strategies receiving the messages.
+    ///
+    /// # Returns
+    /// The messages sent to the manager.
+    pub fn deliver(&mut self, strategies: &mut [Box<dyn Strategy>]) -> Vec<MessageData> {
+        let mut delivered: Vec<Envelope> = vec![];
+        self.pending.retain_mut(|pending| {
+            if pending.frames_left == 0 {
+                warn!(
+                    "message {:?} of robot {} dropped without acknowledgment",
+                    pending.envelope.data.message, pending.envelope.data.id
+                );
+                return false;
+            }
+            pending.frames_left -= 1;
+            delivered.push(pending.envelope.clone());
+            true
+        });
+
+        let mut to_manager = vec![];
+        for envelope in std::mem::take(&mut self.queue) {
+            if envelope.data.to == Recipient::Manager {
+                to_manager.push(envelope.data);
+                continue;
+            }
+            if envelope.data.needs_ack {
+                self.pending.push(Pending {
+                    envelope: envelope.clone(),
+                    frames_left: ACK_TIMEOUT_FRAMES,
+                });
+            }
+            delivered.push(envelope);
+        }
+
+        for strategy in strategies.iter_mut() {
+            let inbox: Vec<Envelope> = delivered
+                .iter()
+                .filter(|envelope| Self::is_recipient(strategy.as_ref(), envelope))
+                .cloned()
+                .collect();
+            if !inbox.is_empty() {
+                strategy.receive_messages(&inbox);
+            }
+        }
+        to_manager
+    }
+}
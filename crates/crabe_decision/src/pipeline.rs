@@ -3,16 +3,44 @@ use crate::manager::manual::Manual;
 use crate::manager::bigbro::BigBro;
 use crate::manager::test_manager::TestManager;
 use crate::manager::Manager;
-use clap::Args;
+use crate::play_file::PlayFile;
+use crate::strategy::registry::{create_strategy, parse_strategy_request};
+use clap::{Args, ValueEnum};
 use crabe_framework::component::{Component, DecisionComponent};
 use crabe_framework::config::CommonConfig;
 use crabe_framework::data::output::CommandMap;
-use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::tool::{GuardData, StrategyRequest, ToolCommands, ToolData};
 use crabe_framework::data::world::World;
+use log::{error, warn};
+use std::path::PathBuf;
+
+/// The managers that can decide the strategies of the robots.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ManagerKind {
+    /// The game manager, choosing the strategies from the state of the game.
+    Bigbro,
+    /// Executes the strategies given with `--strategy` and in the `strategies` of the play file.
+    Manual,
+    /// Executes the strategies of the `TestManager`.
+    Test,
+}
 
 /// The `DecisionConfig` struct is used to hold configuration options for the decision pipeline.
 #[derive(Args)]
-pub struct DecisionConfig {}
+pub struct DecisionConfig {
+    /// The manager deciding the strategies of the robots.
+    #[arg(long, value_enum, default_value_t = ManagerKind::Bigbro)]
+    manager: ManagerKind,
+    /// Strategy executed by the manual manager, written as `Name=id,id;parameter=value`
+    /// (for example `DefenseWall=1,2` or `BotMarking=1;enemy_id=4`).
+    /// It can be repeated to execute several strategies.
+    #[arg(long = "strategy", value_parser = parse_strategy_request)]
+    strategies: Vec<StrategyRequest>,
+    /// Path of the TOML play file describing the plays, formations and strategy parameters.
+    /// It is reloaded when modified by the bigbro manager.
+    #[arg(long)]
+    play_file: Option<PathBuf>,
+}
 
 /// The `DecisionPipeline` struct represents the decision-making pipeline used by the robot.
 /// It consists of an action wrapper and a manager, both of which can be customized
@@ -22,14 +50,55 @@ pub struct DecisionConfig {}
 pub struct DecisionPipeline {
     action_wrapper: ActionWrapper,
     manager: Box<dyn Manager>,
+    /// The robots pulled off the field by the guards, which are driven by the guards instead of the strategies.
+    pulled_robots: Vec<u8>,
 }
 
 impl DecisionPipeline {
     /// Creates a new `DecisionPipeline` instance with the given configuration and common configuration options.
-    pub fn with_config(_decision_cfg: DecisionConfig, _common_cfg: &CommonConfig) -> Self {
+    pub fn with_config(decision_cfg: DecisionConfig, _common_cfg: &CommonConfig) -> Self {
+        if decision_cfg.manager == ManagerKind::Test && decision_cfg.play_file.is_some() {
+            warn!("the play file is not used by the test manager");
+        }
+        if decision_cfg.manager != ManagerKind::Manual && !decision_cfg.strategies.is_empty() {
+            warn!("the strategies are only used by the manual manager");
+        }
+        let manager: Box<dyn Manager> = match decision_cfg.manager {
+            ManagerKind::Bigbro => match decision_cfg.play_file {
+                Some(path) => Box::new(BigBro::with_play_file(path)),
+                None => Box::new(BigBro::new()),
+            },
+            ManagerKind::Manual => {
+                let mut requests = decision_cfg.strategies;
+                if let Some(path) = decision_cfg.play_file {
+                    match PlayFile::load(&path) {
+                        Ok(play_file) => requests.extend(play_file.strategies),
+                        Err(e) => error!("failed to load the play file {}: {}", path.display(), e),
+                    }
+                }
+                if requests.is_empty() {
+                    Box::new(Manual::new())
+                } else {
+                    Box::new(Manual::with_strategies(
+                        requests
+                            .iter()
+                            .filter_map(|request| {
+                                create_strategy(request)
+                                    .map_err(|e| {
+                                        error!("failed to create the strategy {}: {}", request.name, e)
+                                    })
+                                    .ok()
+                            })
+                            .collect(),
+                    ))
+                }
+            }
+            ManagerKind::Test => Box::new(TestManager::new()),
+        };
         Self {
             action_wrapper: ActionWrapper::default(),
-            manager: Box::new(BigBro::new()),
+            manager,
+            pulled_robots: vec![],
         }
     }
 }
@@ -46,10 +115,30 @@ impl DecisionComponent for DecisionPipeline {
         let mut tool_data = ToolData::default();
         self.manager
             .step(world, &mut tool_data, &mut self.action_wrapper);
+        // the robots pulled off the field stay in the world, to be avoided, but the guards drive them
+        for id in &self.pulled_robots {
+            self.action_wrapper.clear(*id);
+        }
 
         (
             self.action_wrapper.compute(world, &mut tool_data),
             tool_data,
         )
     }
+
+    /// Starts the strategies requested by the tools, through the registry.
+    fn handle_tool_commands(&mut self, tool_commands: &mut ToolCommands) {
+        for request in tool_commands.strategies.drain(..) {
+            match create_strategy(&request) {
+                Ok(strategy) => self.manager.spawn_strategy(strategy),
+                Err(e) => error!("failed to create the strategy {}: {}", request.name, e),
+            }
+        }
+    }
+
+    /// Leaves the robots pulled off the field by the guards out of the strategies of the next decisions.
+    fn handle_guard_data(&mut self, guard_data: &GuardData) {
+        self.pulled_robots.clone_from(&guard_data.pulled_robots);
+        self.manager.exclude_robots(&self.pulled_robots);
+    }
 }
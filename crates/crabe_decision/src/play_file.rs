@@ -0,0 +1,345 @@
+use crate::strategy::registry::STRATEGIES;
+use crate::utils::bigbro_decisions::{BIGBRO_PARAMETERS, GAME_STATE_ROLES};
+use crate::utils::playbook::{Play, Playbook};
+use crabe_framework::data::tool::{ParameterValue, StrategyRequest};
+use log::{error, info};
+use nalgebra::Point2;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Time between two checks of the play file modification time.
+const RELOAD_PERIOD: Duration = Duration::from_secs(1);
+
+/// A role given to some of the line robots.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RoleSlot {
+    /// The name of the strategy played by the robots, like `"DefenseWall"`.
+    pub role: String,
+    /// The maximum number of robots playing the role.
+    pub count: usize,
+}
+
+/// The `PlayFile` struct describes the behavior of the team without code: the parameters of the strategies,
+/// the formations, the roles given to the robots for each game state, the set-piece plays
+/// and the strategies run by the manual manager.
+/// It is written in TOML, every section being optional:
+///
+/// ```toml
+/// [parameters.BigBro]
+/// attacker_switch_time_margin = 0.5
+///
+/// [formations]
+/// prepare_start = [[-1.0, 0.0], [-2.0, 1.0], [-2.0, -1.0]]
+///
+/// [[roles.set_piece]]
+/// role = "SetPiece"
+/// count = 2
+/// [[roles.set_piece]]
+/// role = "DefenseWall"
+/// count = 5
+///
+/// [[plays]]
+/// name = "QuickShot"
+/// kind = "DirectShot"
+/// ball_x_range = [1.0, inf]
+/// positions = [[-0.5, 0.0]]
+/// steps = [{ role = 0, start = 0.0, action = "Shoot" }]
+///
+/// [[strategies]]
+/// name = "BotMarking"
+/// ids = [1]
+/// parameters = { enemy_id = 4, mode = "PassLane" }
+/// ```
+///
+/// # Example
+/// ```
+/// use crabe_decision::play_file::PlayFile;
+///
+/// let play_file = PlayFile::parse(r#"
+///     [parameters.BigBro]
+///     attacker_switch_time_margin = 0.5
+///
+///     [[roles.set_piece]]
+///     role = "SetPiece"
+///     count = 2
+///     [[roles.set_piece]]
+///     role = "DefenseWall"
+///     count = 1
+/// "#).unwrap();
+/// assert_eq!(play_file.parameter("BigBro", "attacker_switch_time_margin", 0.3), 0.5);
+/// assert_eq!(play_file.parameter("BigBro", "unknown", 0.3), 0.3);
+/// // The robots left when all the roles are full play the last role
+/// let roles = play_file.role_counts("set_piece", 5).unwrap();
+/// assert_eq!(roles, vec![("SetPiece".to_string(), 2), ("DefenseWall".to_string(), 3)]);
+/// assert!(play_file.role_counts("kick_off", 5).is_none());
+/// assert!(play_file.invalid_names().is_empty());
+///
+/// // The names are checked against the strategies and the game states of `BigBro`
+/// let play_file = PlayFile::parse(r#"
+///     [parameters.BigBro]
+///     attacker_switch_margin = 0.5
+///
+///     [[roles.run]]
+///     role = "SetPiece"
+///     count = 2
+/// "#).unwrap();
+/// assert_eq!(play_file.invalid_names().len(), 2);
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PlayFile {
+    /// The parameters of the strategies, by strategy name then parameter name.
+    pub parameters: HashMap<String, HashMap<String, f64>>,
+    /// The positions of the line robots in the formations, by formation name.
+    pub formations: HashMap<String, Vec<Point2<f64>>>,
+    /// The roles given to the line robots, from the most to the least important,
+    /// by game state: `"run"`, `"kick_off"` or `"set_piece"`.
+    pub roles: HashMap<String, Vec<RoleSlot>>,
+    /// The set-piece plays, replacing the default playbook when not empty.
+    pub plays: Vec<Play>,
+    /// The strategies executed by the manual manager, created through the strategy registry.
+    pub strategies: Vec<StrategyRequest>,
+}
+
+impl PlayFile {
+    /// Parses a play file from its TOML content.
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Reads and parses a play file.
+    /// The unknown names of strategies, parameters, game states and roles are reported,
+    /// and the file is refused so that a typo doesn't go unnoticed.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let play_file = Self::parse(&content).map_err(|e| e.to_string())?;
+        let invalid_names = play_file.invalid_names();
+        for invalid_name in &invalid_names {
+            error!("{}: {}", path.display(), invalid_name);
+        }
+        if !invalid_names.is_empty() {
+            return Err(format!("{} invalid names", invalid_names.len()));
+        }
+        Ok(play_file)
+    }
+
+    /// Checks the names of the strategies and parameters of the `[parameters]` section against the strategy registry,
+    /// and the game states and roles of the `[roles]` section against the ones played by `BigBro`.
+    ///
+    /// # Returns
+    /// A message for each invalid name, empty if the file is valid.
+    pub fn invalid_names(&self) -> Vec<String> {
+        let mut invalid_names = vec![];
+        for (strategy, parameters) in &self.parameters {
+            let schema = if strategy == "BigBro" {
+                Some(BIGBRO_PARAMETERS)
+            } else {
+                STRATEGIES
+                    .iter()
+                    .find(|entry| entry.name == strategy)
+                    .map(|entry| entry.parameters)
+            };
+            let Some(schema) = schema else {
+                invalid_names.push(format!("unknown strategy {} in the parameters", strategy));
+                continue;
+            };
+            for (name, value) in parameters {
+                match schema.iter().find(|parameter| parameter.name == name) {
+                    Some(parameter) => {
+                        if let Err(e) = parameter.check(&ParameterValue::Number(*value)) {
+                            invalid_names.push(format!("{}: {}", strategy, e));
+                        }
+                    }
+                    None => invalid_names.push(format!(
+                        "unknown parameter {} of {}, expected one of: {}",
+                        name,
+                        strategy,
+                        schema
+                            .iter()
+                            .map(|parameter| parameter.name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                }
+            }
+        }
+        for (state, slots) in &self.roles {
+            let Some((_, roles)) = GAME_STATE_ROLES.iter().find(|(name, _)| name == state) else {
+                invalid_names.push(format!(
+                    "unknown game state {} in the roles, expected one of: {}",
+                    state,
+                    GAME_STATE_ROLES
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                continue;
+            };
+            for slot in slots {
+                if !roles.contains(&slot.role.as_str()) {
+                    invalid_names.push(format!(
+                        "the role {} isn't played during {}, expected one of: {}",
+                        slot.role,
+                        state,
+                        roles.join(", ")
+                    ));
+                }
+            }
+        }
+        invalid_names
+    }
+
+    /// Returns a parameter of a strategy, or the default value if the file doesn't set it.
+    ///
+    /// # Arguments
+    /// - `strategy`: The name of the strategy.
+    /// - `name`: The name of the parameter.
+    /// - `default`: The value used when the parameter is not set.
+    pub fn parameter(&self, strategy: &str, name: &str, default: f64) -> f64 {
+        self.parameters
+            .get(strategy)
+            .and_then(|parameters| parameters.get(name))
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// Returns a request to create a strategy through the strategy registry, with its parameters from the file.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the strategy, like `"DefenseWall"`.
+    /// - `ids`: The ids of the robots moved by the strategy.
+    pub fn strategy_request(&self, name: &str, ids: Vec<u8>) -> StrategyRequest {
+        let parameters = self
+            .parameters
+            .get(name)
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .map(|(name, value)| (name.clone(), ParameterValue::Number(*value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        StrategyRequest {
+            name: name.to_string(),
+            ids,
+            parameters,
+        }
+    }
+
+    /// Returns the positions of a formation, None if the file doesn't describe it.
+    pub fn formation(&self, name: &str) -> Option<&Vec<Point2<f64>>> {
+        self.formations
+            .get(name)
+            .filter(|positions| !positions.is_empty())
+    }
+
+    /// Distributes the line robots among the roles of a game state, following their priorities.
+    /// The robots left when all the roles are full play the last role.
+    ///
+    /// # Arguments
+    /// - `state`: The name of the game state, like `"set_piece"`.
+    /// - `robots_count`: The number of line robots to distribute.
+    ///
+    /// # Returns
+    /// The number of robots of each role, None if the file doesn't describe the game state.
+    pub fn role_counts(&self, state: &str, robots_count: usize) -> Option<Vec<(String, usize)>> {
+        let slots = self.roles.get(state).filter(|slots| !slots.is_empty())?;
+        let mut remaining = robots_count;
+        let mut counts: Vec<(String, usize)> = slots
+            .iter()
+            .map(|slot| {
+                let count = slot.count.min(remaining);
+                remaining -= count;
+                (slot.role.clone(), count)
+            })
+            .collect();
+        if let Some(last) = counts.last_mut() {
+            last.1 += remaining;
+        }
+        Some(counts)
+    }
+
+    /// Returns the number of robots given to a role in a game state, None if the file doesn't describe the game state.
+    pub fn role_count(&self, state: &str, role: &str, robots_count: usize) -> Option<usize> {
+        self.role_counts(state, robots_count).map(|counts| {
+            counts
+                .iter()
+                .filter(|(name, _)| name == role)
+                .map(|(_, count)| count)
+                .sum()
+        })
+    }
+
+    /// Returns the set-piece plays, the default ones if the file doesn't describe any.
+    pub fn playbook(&self) -> Playbook {
+        if self.plays.is_empty() {
+            Playbook::default()
+        } else {
+            Playbook {
+                plays: self.plays.clone(),
+            }
+        }
+    }
+}
+
+/// The `PlayFileWatcher` struct reloads a play file when it is modified,
+/// so that the plays can be tuned without restarting.
+pub struct PlayFileWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl PlayFileWatcher {
+    /// Creates a new `PlayFileWatcher` and loads the play file for the first time.
+    /// If the file can't be loaded, the default play file is used until it is fixed.
+    pub fn new(path: PathBuf) -> (Self, PlayFile) {
+        let mut watcher = Self {
+            path,
+            modified: None,
+            last_check: Instant::now(),
+        };
+        let play_file = watcher.reload().unwrap_or_default();
+        (watcher, play_file)
+    }
+
+    fn reload(&mut self) -> Option<PlayFile> {
+        self.modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        match PlayFile::load(&self.path) {
+            Ok(play_file) => {
+                info!("play file {} loaded", self.path.display());
+                Some(play_file)
+            }
+            Err(e) => {
+                error!(
+                    "failed to load the play file {}: {}",
+                    self.path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the new play file if it was modified since the last load.
+    /// A file that fails to load is reported and ignored, the previous one staying in use.
+    pub fn poll(&mut self) -> Option<PlayFile> {
+        if self.last_check.elapsed() < RELOAD_PERIOD {
+            return None;
+        }
+        self.last_check = Instant::now();
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.reload()
+    }
+}
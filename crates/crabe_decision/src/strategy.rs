@@ -1,6 +1,7 @@
 use crate::action::ActionWrapper;
-use crate::message::MessageData;
+use crate::message::{Envelope, MessageData, Topic};
 use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::game_state::GameState;
 use crabe_framework::data::world::World;
 
 /// The `testing` module contains different strategies used for testing purposes. These strategies
@@ -11,6 +12,8 @@ pub mod defensive;
 pub mod offensive;
 pub mod formations;
 pub mod basics;
+/// The `registry` module lists the strategies that can be created from their name, like from the command line.
+pub mod registry;
 
 pub struct StrategyData {
     pub ids: Vec<u8>,
@@ -26,9 +29,29 @@ pub struct StrategyData {
 pub trait Strategy {
     /// Name of the strategy, that we use as simple reference
     fn name(&self) -> &'static str;
-    fn get_messages(&self) -> &Vec<MessageData> ;
+    /// Takes the messages sent by the strategy since the last call, to be posted on the message bus.
+    fn take_messages(&mut self) -> Vec<MessageData>;
+    /// Receives the messages delivered to the strategy this frame, before its step.
+    fn receive_messages(&mut self, _messages: &[Envelope]) {}
+    /// The topics the strategy is subscribed to, receiving the messages sent to `Recipient::Subscribers`.
+    fn topics(&self) -> Vec<Topic> {
+        vec![]
+    }
     fn get_ids(&self) -> Vec<u8>;
     fn put_ids(&mut self, ids: Vec<u8>);
+    /// Called by the manager when the strategy starts, before its first step.
+    fn on_enter(&mut self) {}
+    /// Called by the manager when the strategy stops. The orders of its robots are cleared afterwards.
+    fn on_exit(&mut self) {}
+    /// Called by the manager when a robot is given to the strategy.
+    fn on_robot_added(&mut self, _id: u8) {}
+    /// Called by the manager when a robot is taken from the strategy. Its orders are cleared afterwards.
+    fn on_robot_removed(&mut self, _id: u8) {}
+    /// Called by the manager when the game state given by the referee changes, before the step.
+    fn on_game_state_change(&mut self, _previous: GameState, _current: GameState) {}
+    /// Called by the manager when the actions of one of its robots failed, like on a timeout, before the step.
+    /// The failed actions are cleared, the robot stays still until the strategy gives it new ones.
+    fn on_action_failed(&mut self, _id: u8) {}
     /// Executes one step of the strategy, updating the state of the robot and issuing commands
     /// to it through the given `ActionWrapper`.
     ///
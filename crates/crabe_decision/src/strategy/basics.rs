@@ -8,3 +8,6 @@ mod comeback;
 pub use self::comeback::*;
 mod move_away_from_point;
 pub use self::move_away_from_point::*;
+
+mod mark;
+pub use self::mark::*;
\ No newline at end of file
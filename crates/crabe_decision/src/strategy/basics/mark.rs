@@ -0,0 +1,55 @@
+use crate::action::move_to::MoveTo;
+use crabe_framework::data::world::{AllyInfo, Ball, EnemyInfo, Robot, World};
+use crabe_math::shape::Line;
+use crabe_math::vectors;
+
+/// Distance kept between the marker and the marked enemy, in addition to the robots radius.
+const MARK_DISTANCE: f64 = 0.2;
+
+/// Where to stand when marking an enemy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkingMode {
+    /// Between the enemy and our goal, to block its shots.
+    GoalSide,
+    /// Between the enemy and the ball, to block the passes to it.
+    PassLane,
+}
+
+/// Mark an enemy, intercepting the ball if it is passed to him
+///
+/// # Arguments
+/// - `robot` : The robot marking the enemy
+/// - `enemy` : The marked enemy
+/// - `ball` : The ball
+/// - `mode` : Where to stand relatively to the enemy
+/// - `world` : The current world state
+///
+/// # Returns
+/// A `MoveTo` action that will make the robot mark the enemy
+pub fn mark(
+    robot: &Robot<AllyInfo>,
+    enemy: &Robot<EnemyInfo>,
+    ball: &Ball,
+    mode: MarkingMode,
+    world: &World,
+) -> MoveTo {
+    let ball_position = ball.position_2d();
+    let enemy_position = enemy.pose.position;
+    let orientation = vectors::angle_to_point(robot.pose.position, ball_position);
+
+    // If the ball is passed to the enemy, intercept it
+    let ball_trajectory = Line::new(ball_position, ball_position + ball.velocity.xy().normalize() * 100.);
+    if ball.velocity.norm() > 0.1 && ball_trajectory.distance_to_point(&enemy_position) < 1. {
+        let target = ball_trajectory.closest_point_on_segment(&robot.pose.position);
+        return MoveTo::new(target, orientation, 1., false, None, true, true);
+    }
+
+    let protected_point = match mode {
+        MarkingMode::GoalSide => world.geometry.ally_goal.line.center(),
+        MarkingMode::PassLane => ball_position,
+    };
+    let distance = world.geometry.robot_radius * 2. + MARK_DISTANCE;
+    let direction = (protected_point - enemy_position).try_normalize(f64::EPSILON).unwrap_or_default();
+    let target = enemy_position + direction * distance;
+    MoveTo::new(target, orientation, 0., false, None, false, true)
+}
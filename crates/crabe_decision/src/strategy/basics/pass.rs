@@ -1,10 +1,14 @@
 use crate::action::move_to::MoveTo;
+use crate::utils::{GRAVITY, PASS_SPEED};
 use crabe_framework::data::output::Kick;
 use crabe_framework::data::world::{AllyInfo, Ball, Robot, World};
 use crabe_math::shape::Line;
 use crabe_math::vectors;
+use nalgebra::Point2;
 
 const GO_BEHIND_BALL_DIST: f64 = 0.3;
+/// Maximum speed of a chipped ball (m/s).
+const MAX_CHIP_SPEED: f64 = 5.;
 
 /// Pass the ball to the receiver
 /// (before kicking he makes sure to be aligned with the receiver)
@@ -22,6 +26,59 @@ pub fn pass(
     receiver: &Robot<AllyInfo>,
     ball: &Ball,
     world: &World,
+) -> MoveTo {
+    pass_to_point(robot, &receiver.pose.position, ball, world)
+}
+
+/// Pass the ball to a receiving point
+/// (before kicking he makes sure to be aligned with the point)
+/// 
+/// # Arguments
+/// - `robot` : The robot that will pass the ball
+/// - `target` : The point where the ball will be received
+/// - `ball` : The ball
+/// - `world` : The current world state
+/// 
+/// # Returns
+/// A `MoveTo` action that will make the robot pass the ball to the receiving point
+pub fn pass_to_point(
+    robot: &Robot<AllyInfo>,
+    target: &Point2<f64>,
+    ball: &Ball,
+    world: &World,
+) -> MoveTo {
+    kick_to_point(robot, target, ball, world, Kick::StraightKick { power: PASS_SPEED as f32 })
+}
+
+/// Chip the ball over the robots standing in the way to a receiving point
+/// (before kicking he makes sure to be aligned with the point)
+/// 
+/// # Arguments
+/// - `robot` : The robot that will chip the ball
+/// - `target` : The point where the ball will land
+/// - `ball` : The ball
+/// - `world` : The current world state
+/// 
+/// # Returns
+/// A `MoveTo` action that will make the robot chip the ball to the receiving point
+pub fn chip_to_point(
+    robot: &Robot<AllyInfo>,
+    target: &Point2<f64>,
+    ball: &Ball,
+    world: &World,
+) -> MoveTo {
+    // The chipper kicks at 45 degrees, so the ball lands at speed² / g
+    let speed = ((target - ball.position_2d()).norm() * GRAVITY).sqrt().min(MAX_CHIP_SPEED);
+    kick_to_point(robot, target, ball, world, Kick::ChipKick { power: speed as f32 })
+}
+
+/// Kick the ball to a point once aligned with it.
+fn kick_to_point(
+    robot: &Robot<AllyInfo>,
+    target: &Point2<f64>,
+    ball: &Ball,
+    world: &World,
+    kick: Kick,
 ) -> MoveTo {
     let robot_position = robot.pose.position;
     let robot_direction = vectors::vector_from_angle(robot.pose.orientation);
@@ -31,13 +88,13 @@ pub fn pass(
     let dist_to_ball: f64 = robot_to_ball.norm();
 
     // Calculate the position behind the ball to prepare the pass
-    let behind_ball_position = ball_position + (ball_position - receiver.pose.position).normalize() * GO_BEHIND_BALL_DIST; 
+    let behind_ball_position = ball_position + (ball_position - target).normalize() * GO_BEHIND_BALL_DIST; 
             
     // Check if the pass trajectory will arrive near the ally
     let robot_passing_trajectory = Line::new(robot_position, robot_position + robot_to_ball * 100.);
-    let passing_trajectory_will_land = match robot_passing_trajectory.orthogonal_projection_point_on_segment(&receiver.pose.position) {
+    let passing_trajectory_will_land = match robot_passing_trajectory.orthogonal_projection_point_on_segment(target) {
         Ok(closest_point) => {
-            let dist_to_receiver = (receiver.pose.position - closest_point).norm();
+            let dist_to_receiver = (target - closest_point).norm();
             dist_to_receiver < 0.3
         },
         Err(_) => false,
@@ -45,9 +102,9 @@ pub fn pass(
 
     if passing_trajectory_will_land && dot_with_ball > 0.95{
         let kick: Option<Kick> = if dist_to_ball < (world.geometry.robot_radius + world.geometry.ball_radius + 1.) { 
-            Some(Kick::StraightKick {  power: 4. }) 
+            Some(kick)
         }else {None};
-        return MoveTo::new(ball_position, vectors::angle_to_point(robot_position,receiver.pose.position), 400.,  true, kick, true, false);
+        return MoveTo::new(ball_position, vectors::angle_to_point(robot_position,*target), 400.,  true, kick, true, false);
     }
-    MoveTo::new(behind_ball_position, vectors::angle_to_point(robot_position, receiver.pose.position), 0., false, None, true, true)
+    MoveTo::new(behind_ball_position, vectors::angle_to_point(robot_position, *target), 0., false, None, true, true)
 }
\ No newline at end of file
@@ -9,3 +9,7 @@ pub use self::bot_contesting::BotContesting;
 
 mod bot_marking;
 pub use self::bot_marking::BotMarking;
+
+
+mod man_marking;
+pub use self::man_marking::ManMarking;
\ No newline at end of file
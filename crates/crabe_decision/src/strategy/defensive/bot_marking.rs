@@ -1,32 +1,31 @@
 use crate::{
-    action::{move_to::MoveTo, ActionWrapper}, message::MessageData, strategy::Strategy
+    action::ActionWrapper, message::MessageData, strategy::Strategy
 };
+use crate::strategy::basics::{mark, MarkingMode};
 use crabe_framework::data::{
-    output::Kick::StraightKick,
     tool::ToolData,
     world::World,
 };
-use nalgebra::{Matrix, Point2};
 
-use crabe_math::{shape::Line, vectors::angle_to_point};
 
-
-/// The BotMarking struct represents a strategy that commands a robot to move in a BotMarking shape
-/// in a counter-clockwise. It is used for testing purposes.
+/// The BotMarking struct represents a strategy that commands a robot to mark a given enemy,
+/// staying between the enemy and our goal or between the enemy and the ball.
 pub struct BotMarking {
     /// The id of the robot to move.
     id: u8,
     messages: Vec<MessageData>,
     enemy_id: u8,
+    mode: MarkingMode,
 }
 
 impl BotMarking {
-    /// Creates a new BotMarking instance with the desired robot id.
-    pub fn new(id: u8, enemy_id: u8) -> Self {
+    /// Creates a new BotMarking instance with the desired robot id, marked enemy and marking mode.
+    pub fn new(id: u8, enemy_id: u8, mode: MarkingMode) -> Self {
         Self { 
             id,
             messages: vec![],
             enemy_id,
+            mode,
         }
     }
 }
@@ -36,8 +35,8 @@ impl Strategy for BotMarking {
         "BotMarking"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData>  {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         vec![self.id]
@@ -64,50 +63,22 @@ impl Strategy for BotMarking {
             }
         };
 
-        let robot = &match world.allies_bot.get(&self.id) {
+        let robot = match world.allies_bot.get(&self.id) {
             Some(r) => r,
             None => {
                 return false;
             }
         };
-        let robot_pos = &robot.pose;
 
-        let enemy = &match world.enemies_bot.get(&self.enemy_id) {
+        let enemy = match world.enemies_bot.get(&self.enemy_id) {
             Some(r) => r,
             None => {
                 return false;
             }
         };
-        let enemy_pos = &enemy.pose;
-
-        let mut dribbler = 0.0;
-        let ball_pos = ball.position_2d();
-        if robot.distance(&ball_pos) < 1. {
-            dribbler = 1.0;
-        }
-
-        //ANGLE TO BALL
-        let angle = angle_to_point(robot_pos.position, ball_pos);
-
-        // VELOCITY CATCH
-        let ball_velocity_trajectory = Line::new(ball_pos, ball_pos + ball.velocity.xy().normalize() * 100.);
-        if ball.velocity.norm() > 0.1 && ball_velocity_trajectory.distance_to_point(&enemy_pos.position) < 1. {
-            let target = ball_velocity_trajectory.closest_point_on_segment(&robot_pos.position);
-            action_wrapper.push(self.id,  MoveTo::new(Point2::new(target.x, target.y), angle , dribbler , false , Some(StraightKick { power: 0.0 }), true, true));
-        } else {
-
-            // VECTEUR BALL -> ENEMY
-            let enemy_to_ball = ball_pos - enemy_pos.position;
-            let enemy_ball_distance = enemy_to_ball.norm();
-            let coef_distance_to_enemy: f64 = world.geometry.robot_radius + 0.2/enemy_ball_distance;
-            let target = enemy_pos.position -  Point2::new(enemy_to_ball.x, enemy_to_ball.y)*(-coef_distance_to_enemy);
-            action_wrapper.push(self.id,  MoveTo::new(Point2::new(target.x, target.y), angle , dribbler , false , Some(StraightKick { power: 0.0 }), false, true));
-        }
-
-        
-        
-        return false;
 
+        action_wrapper.push(self.id, mark(robot, enemy, ball, self.mode, world));
+        false
     }
     
 }
@@ -1,65 +1,262 @@
-use std::cmp::Ordering;
 use std::f64::consts::PI;
-use crate::utils::{closest_bot_to_point, object_in_bot_trajectory, KEEPER_ID};
+use crate::utils::{closest_bot_to_point, KEEPER_ID};
+use crate::utils::prediction::BallTrajectory;
 use crate::{action::move_to::MoveTo, message::MessageData};
 use crate::action::ActionWrapper;
 use crate::strategy::Strategy;
+use crabe_framework::data::geometry::Penalty;
 use crabe_framework::data::output::Kick;
 use crabe_framework::data::tool::ToolData;
-use crabe_framework::data::world::{AllyInfo, Robot, World};
+use crabe_framework::data::world::{AllyInfo, Ball, Robot, World};
 use crabe_math::shape::{Circle, Line};
 use crabe_math::vectors;
-use std::time::{SystemTime, UNIX_EPOCH};
+use nalgebra::{Point2, Vector2};
 
 const KICK_RANGE: f64 = 0.4;
+/// Distance between the penalty area and the line where the defenders stand (m).
+const WALL_OFFSET: f64 = 0.3;
+/// Space left between the blocked shot directions of two neighbouring defenders (m).
+const DEFENDER_MARGIN: f64 = 0.02;
+/// Ball speed over which the wall covers the enemy receiving the ball instead of the ball (m/s).
+const MOVING_BALL_SPEED: f64 = 1.;
+/// Gaps of the shot cone narrower than this angle are ignored (rad).
+const MIN_GAP_ANGLE: f64 = 0.005;
+/// Number of lines, each one further from the penalty area, on which the defenders can stand.
+const MAX_WALL_LINES: usize = 3;
 
-/// The Square struct represents a strategy that commands a robot to move in a square shape
-/// in a counter-clockwise. It is used for testing purposes.
+/// An interval of shot directions, as angles relative to the direction from the shooting position to the goal center.
+#[derive(Clone, Copy, Debug)]
+struct Interval {
+    start: f64,
+    end: f64,
+}
+
+impl Interval {
+    fn new(start: f64, end: f64) -> Self {
+        Self { start: start.min(end), end: start.max(end) }
+    }
+
+    fn width(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Removes a covered interval from a list of intervals.
+fn subtract(intervals: Vec<Interval>, covered: Interval) -> Vec<Interval> {
+    let mut remaining = vec![];
+    for interval in intervals {
+        if covered.end <= interval.start || covered.start >= interval.end {
+            remaining.push(interval);
+            continue;
+        }
+        if covered.start > interval.start {
+            remaining.push(Interval::new(interval.start, covered.start));
+        }
+        if covered.end < interval.end {
+            remaining.push(Interval::new(covered.end, interval.end));
+        }
+    }
+    remaining.retain(|interval| interval.width() > MIN_GAP_ANGLE);
+    remaining
+}
+
+/// The DefenseWall strategy places the defenders along our penalty area to block the shots on our goal.
+/// The defenders cover the directions of the goal that the keeper doesn't cover, side by side without overlapping.
+/// The defenders which aren't needed to cover the goal stand next to the others along the penalty area,
+/// or on a line further from it when there is no room left.
+///
+/// # Example
+/// ```
+/// use crabe_decision::action::ActionWrapper;
+/// use crabe_decision::strategy::defensive::DefenseWall;
+/// use crabe_decision::strategy::Strategy;
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::annotation::Annotation;
+/// use crabe_framework::data::tool::ToolData;
+/// use crabe_framework::data::world::{AllyInfo, Ball, Pose, Robot, World};
+/// use nalgebra::{Point2, Point3};
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.ball = Some(Ball { position: Point3::new(-2., 0., 0.), ..Default::default() });
+/// let ids: Vec<u8> = (10..40).collect();
+/// for (i, id) in ids.iter().enumerate() {
+///     let pose = Pose::new(Point2::new(-1., i as f64 * 0.1 - 1.5), 0.);
+///     world.allies_bot.insert(*id, Robot::<AllyInfo> { id: *id, pose, ..Default::default() });
+/// }
+///
+/// // There are more defenders than needed to cover the goal, each of them still gets its own position
+/// let mut wall = DefenseWall::new(ids.clone());
+/// let mut action_wrapper = ActionWrapper::default();
+/// let mut tools = ToolData::default();
+/// wall.step(&world, &mut tools, &mut action_wrapper);
+/// assert!(ids.iter().all(|id| action_wrapper.actions.contains_key(id)));
+/// let positions: Vec<Point2<f64>> = ids.iter().map(|id| match tools.annotations.get(&format!("wall_{}", id)) {
+///     Some(Annotation::Circle(circle)) => circle.center,
+///     _ => panic!("robot {} has no wall position", id),
+/// }).collect();
+/// for (i, position) in positions.iter().enumerate() {
+///     assert!(positions[i + 1..].iter().all(|other| (other - position).norm() > world.geometry.robot_radius));
+/// }
+/// ```
 #[derive(Default)]
 pub struct DefenseWall {
-    /// The id of the robot to move.
+    /// The ids of the robots to move.
     ids: Vec<u8>,
     messages: Vec<MessageData>,
 }
 
+/// The shot cone seen from the shooting position.
+struct ShotCone {
+    /// The position from where the enemy is expected to shoot.
+    origin: Point2<f64>,
+    /// The direction from the shooting position to the goal center, angles are relative to it.
+    reference: Vector2<f64>,
+    /// The distance used to cast the shot directions, long enough to cross the penalty area.
+    reach: f64,
+}
+
+impl ShotCone {
+    /// Returns the angle of the direction from the shooting position to a point.
+    fn angle_to(&self, point: &Point2<f64>) -> f64 {
+        let direction = point - self.origin;
+        self.reference.perp(&direction).atan2(self.reference.dot(&direction))
+    }
+
+    /// Returns the point where a shot direction crosses the wall line.
+    fn wall_point(&self, wall: &Penalty, angle: f64) -> Option<Point2<f64>> {
+        let direction = vectors::rotate_vector(self.reference, angle);
+        wall.intersection_segment(Line::new(self.origin, self.origin + direction * self.reach))
+    }
+
+    /// Returns the half of the angle covered by a robot or the keeper at a position.
+    fn half_angle(&self, world: &World, position: &Point2<f64>) -> f64 {
+        let radius = world.geometry.robot_radius + world.geometry.ball_radius + DEFENDER_MARGIN;
+        let distance = (position - self.origin).norm();
+        (radius / distance).min(1.).asin()
+    }
+}
+
 impl DefenseWall {
     /// Creates a new DefenseWall instance with the desired robot id.
     pub fn new(ids: Vec<u8>) -> Self {
         Self { ids, messages: vec![], }
     }
 
-    /// Move around the penalty zone
-    pub fn oscillate(
-        &mut self,
-        world: &World,
-        action_wrapper: &mut ActionWrapper,
-    )-> bool {
-        let enlarged_penalty = world.geometry.ally_penalty.enlarged_penalty(0.3);
-        for id in self.ids.clone() {
-            action_wrapper.clear(id);
+    /// Returns the position from where the enemy is expected to shoot:
+    /// the ball, or the point where an enemy will receive it if it is moving.
+    /// When this position is inside the wall, it is pushed out along the direction from the goal,
+    /// so that the wall still faces the ball.
+    fn shooting_position(world: &World, ball: &Ball, wall: &Penalty) -> Point2<f64> {
+        let mut origin = ball.position_2d();
+        if ball.velocity.norm() > MOVING_BALL_SPEED {
+            let trajectory = BallTrajectory::new(ball, &world.geometry);
+            let interceptions = trajectory.interceptions(world.enemies_bot.values().collect(), world.geometry.robot_radius);
+            if let Some(interception) = interceptions.first() {
+                origin = interception.point;
+            }
         }
-        let current_time = SystemTime::now();
-        let mut x = 0.;
-        if let Ok(duration) = current_time.duration_since(UNIX_EPOCH) {
-            let current_time_ms = duration.as_millis() as f64;
-            x = current_time_ms ;
-        } 
-        let oscillating_value = (0.00005 * 2.0 * std::f64::consts::PI * x).sin() * 0.5 + 0.5;
-        let pos = enlarged_penalty.on_penalty_line(oscillating_value);
-        for id in self.ids.clone() {
-            action_wrapper.push(id, MoveTo::new(pos, 0., 0., false, None, false, true));
+        if wall.is_inside(&origin) {
+            let goal_center = world.geometry.ally_goal.line.center();
+            let direction = (origin - goal_center).try_normalize(f64::EPSILON).unwrap_or(Vector2::x());
+            origin = goal_center + direction * (wall.depth + wall.width);
         }
-        false
+        origin
+    }
+
+    /// Computes the positions of the defenders on the wall line.
+    /// Each defender is placed at the edge of the largest shot direction gap left by the keeper and the previous defenders.
+    /// When the whole cone is covered, the remaining defenders stand on its sides.
+    fn wall_positions(world: &World, cone: &ShotCone, wall: &Penalty, defenders: usize) -> Vec<Point2<f64>> {
+        let goal = &world.geometry.ally_goal.line;
+        let full_cone = Interval::new(cone.angle_to(&goal.start), cone.angle_to(&goal.end));
+        let mut gaps = vec![full_cone];
+        let mut occupied = vec![full_cone];
+        if let Some(keeper) = world.allies_bot.get(&KEEPER_ID) {
+            let angle = cone.angle_to(&keeper.pose.position);
+            let half_angle = cone.half_angle(world, &keeper.pose.position);
+            let keeper_coverage = Interval::new(angle - half_angle, angle + half_angle);
+            gaps = subtract(gaps, keeper_coverage);
+            occupied.push(keeper_coverage);
+        }
+
+        let mut positions = vec![];
+        let mut left_side = true;
+        let mut attempts = 0;
+        while positions.len() < defenders && attempts < defenders * 3 {
+            attempts += 1;
+            let largest_gap = gaps.iter().copied().max_by(|a, b| a.width().total_cmp(&b.width()));
+            let angle = match largest_gap {
+                Some(gap) => {
+                    let center = (gap.start + gap.end) / 2.;
+                    let Some(center_point) = cone.wall_point(wall, center) else {
+                        gaps = subtract(gaps, gap);
+                        continue;
+                    };
+                    let half_angle = cone.half_angle(world, &center_point);
+                    if 2. * half_angle < gap.width() { gap.start + half_angle } else { center }
+                }
+                None => {
+                    // The cone is covered, extend the wall on its sides
+                    let start = occupied.iter().map(|interval| interval.start).fold(f64::INFINITY, f64::min);
+                    let end = occupied.iter().map(|interval| interval.end).fold(f64::NEG_INFINITY, f64::max);
+                    let edge = if left_side { start } else { end };
+                    left_side = !left_side;
+                    let Some(edge_point) = cone.wall_point(wall, edge) else { continue };
+                    let half_angle = cone.half_angle(world, &edge_point);
+                    if edge == start { start - half_angle } else { end + half_angle }
+                }
+            };
+            let Some(position) = cone.wall_point(wall, angle) else { continue };
+            let half_angle = cone.half_angle(world, &position);
+            let coverage = Interval::new(angle - half_angle, angle + half_angle);
+            gaps = subtract(gaps, coverage);
+            occupied.push(coverage);
+            positions.push(position);
+        }
+        Self::extra_positions(world, wall, &mut positions, defenders);
+        positions
     }
 
+    /// Gives a position to the defenders left without one when the shot directions no longer cross the wall line:
+    /// they are spaced along the wall line next to the last wall position, alternating on both of its sides.
+    /// When the wall line is full, the next defenders stand on a second line further from the penalty area, and so on.
+    /// The defenders left without a position after the last line aren't given one.
+    fn extra_positions(world: &World, wall: &Penalty, positions: &mut Vec<Point2<f64>>, defenders: usize) {
+        let goal_center = world.geometry.ally_goal.line.center();
+        let spacing = 2. * world.geometry.robot_radius + DEFENDER_MARGIN;
+        let mut line = wall.clone();
+        for _ in 0..MAX_WALL_LINES {
+            if positions.len() >= defenders {
+                return;
+            }
+            let last_ratio = positions.last()
+                .and_then(|last| line.intersection_line_as_ratio(Line::new(*last, goal_center)))
+                .unwrap_or(0.5);
+            let step = spacing / (2. * line.depth + line.width);
+            let mut offset = 0.;
+            while positions.len() < defenders && offset <= 1. {
+                for ratio in [last_ratio + offset, last_ratio - offset] {
+                    if positions.len() >= defenders || !(0. ..=1.).contains(&ratio) {
+                        continue;
+                    }
+                    let position = line.on_penalty_line(ratio);
+                    if positions.iter().all(|other| (other - position).norm() >= spacing) {
+                        positions.push(position);
+                    }
+                }
+                offset += step;
+            }
+            line = line.enlarged_penalty(spacing);
+        }
+    }
 }
 
 impl Strategy for DefenseWall {
     fn name(&self) -> &'static str {
         "DefenseWall"
     }
-    fn get_messages(&self) -> &Vec<MessageData> {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         self.ids.clone()
@@ -70,18 +267,19 @@ impl Strategy for DefenseWall {
 
     /// Executes the DefenseWall strategy.
     ///
-    /// This strategy commands the robot with the specified ID to move around the goal line
-    /// 
+    /// This strategy computes the shot cone from the shooting position to our goal,
+    /// removes the part covered by the keeper and spreads the defenders along the enlarged penalty area
+    /// to cover the remaining gaps. The defender closest to the ball clears it when it comes close.
+    ///
     /// # Arguments
     ///
     /// * world: The current state of the game world.
-    /// * tools_data: A collection of external tools used by the strategy, such as a viewer.    
+    /// * tools_data: A collection of external tools used by the strategy, such as a viewer.
     /// * action_wrapper: An `ActionWrapper` instance used to issue actions to the robot.
     ///
     /// # Returns
     ///
     /// A boolean value indicating whether the strategy is finished or not.
-    #[allow(unused_variables)]
     fn step(
         &mut self,
         world: &World,
@@ -91,95 +289,53 @@ impl Strategy for DefenseWall {
         for id in &self.ids{
             action_wrapper.clear(*id);
         }
-        
-        let ball_pos = match world.ball.clone() {
+
+        let ball = match &world.ball {
             None => {return false;}
-            Some(ball) => {ball.position.xy() }
+            Some(ball) => ball,
         };
-        
-        let enlarged_penalty = world.geometry.ally_penalty.enlarged_penalty(0.3);
+        let ball_pos = ball.position_2d();
 
+        let enlarged_penalty = world.geometry.ally_penalty.enlarged_penalty(WALL_OFFSET);
         let goal_center = world.geometry.ally_goal.line.center();
-        let ball_to_goal = Line::new( ball_pos, goal_center);
-
-        if let Some(intersection_shooting_dir_ratio) = enlarged_penalty.intersection_line_as_ratio(ball_to_goal) {//if ball to goal center intersect the penalty line
-            let tot_penalty_line_length = enlarged_penalty.depth * 2. + enlarged_penalty.width;
-            let bot_diameter = world.geometry.robot_radius * 2.;
-			let bot_spacing_ratio = (bot_diameter + world.geometry.ball_radius / 2.) / tot_penalty_line_length; // bot diameter between 0 and 1 relatively to the penalty line length
-            
-            // Get the robots (so that we know how many of them can be move)
-            let mut robots: Vec<(f64, &Robot<AllyInfo>)> = vec![];
-            for id in self.ids.clone() {
-                if let Some(robot) = world.allies_bot.get(&id) {
-                    if let Some(current_pos) = enlarged_penalty.intersection_line_as_ratio(Line::new(robot.pose.position, goal_center)){
-                        robots.push((current_pos, robot));
-                    }
-                } 
-            }
-            //order them by their position on the penalty line
-            // note: partial_cmp only fails if a value is NaN, the value in the unwrap_or_else() is just a safety band-aid
-            robots.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or_else(|| Ordering::Less));
-            let robot_nb = robots.len() as f64;
-            
-            let mut wall_starting_pos = intersection_shooting_dir_ratio - (bot_spacing_ratio / 2.) * (robot_nb - 1.);
-            
-            // Clamp the position of the wall so that he's not going out of the field
-            let closest = closest_bot_to_point(robots.iter().map(|(_, r)| *r).collect(), ball_pos);
-            wall_starting_pos = wall_starting_pos.clamp(bot_spacing_ratio / 2., 1. - bot_spacing_ratio / 2. - (robot_nb-1.)*bot_spacing_ratio);
-            let mut i = 0;
-            let keeper: Option<&Robot<AllyInfo>> = match world.allies_bot.get(&KEEPER_ID) {
-                Some(keeper) => Some(keeper),
-                None => None,
-            };
-            let keeper_line = match keeper {
-                Some(keeper) => {
-                    let dir = vectors::vector_from_angle(keeper.pose.orientation);
-                    Some(Line::new(keeper.pose.position, keeper.pose.position + dir))
-                },
-                None => None,
-            };
-            let keeper_intersection = match keeper_line {
-                Some(line) => {
-                    enlarged_penalty.intersection_line(line)
-                },
-                None => None,
-            };
-            
-            for (current_pos, robot) in robots {
-                //clamp new bot position so they have to move along the penalty line instead of just moving through the goal field
-                let robot_wall_destination = wall_starting_pos + (i as f64) * bot_spacing_ratio;
-                let pos_on_penalty_line = enlarged_penalty.on_penalty_line(robot_wall_destination);
-                if world.geometry.ally_penalty.is_inside(&ball_pos){
-                    if let Some(intersection) = keeper_intersection {
-                        if (pos_on_penalty_line - intersection).norm()<=world.geometry.robot_radius *2.{
-                            i+=1;
-                        }
-                    }
-                }
-                let robot_to_goal = Line::new(robot.pose.position, goal_center);
-                let dist_to_goal = if let Some(intersection) = enlarged_penalty.intersection_line(robot_to_goal){
-                    (robot.pose.position - intersection).norm()
-                }else{0.};
-                let avoidance = dist_to_goal > 0.5;
-                let orientation = vectors::angle_to_point(robot.pose.position,  world.geometry.ally_goal.line.center()) + PI;
-                let distance_to_ball = (ball_pos - robot.pose.position.xy()).norm();
-                if dist_to_goal < 0.4 && distance_to_ball < KICK_RANGE + world.geometry.robot_radius + world.geometry.ball_radius {
-                    if let Some(closest_bot_to_ball) = closest{
-                        if closest_bot_to_ball.id == robot.id && !enlarged_penalty.is_inside(&ball_pos){
-                            let ball_orientation = vectors::angle_to_point(robot.pose.position, ball_pos);
-                            action_wrapper.push(robot.id, MoveTo::new(ball_pos, ball_orientation, 0., true, Some(Kick::StraightKick { power: 4. }), false, avoidance));
-                        }else {
-                            action_wrapper.push(robot.id, MoveTo::new(pos_on_penalty_line, orientation, 0., false, None, true, avoidance));
-                        }
-                    }else {
-                        action_wrapper.push(robot.id, MoveTo::new(pos_on_penalty_line, orientation, 0., false, None, true, avoidance));
-                    }
-                } else {
-                    action_wrapper.push(robot.id, MoveTo::new(pos_on_penalty_line, orientation, 0., false, None, true, avoidance));
-                }
-                i+=1;
+        let origin = Self::shooting_position(world, ball, &enlarged_penalty);
+        let cone = ShotCone {
+            origin,
+            reference: (goal_center - origin).normalize(),
+            reach: (goal_center - origin).norm() + world.geometry.ally_goal.depth,
+        };
+        tools_data.annotations.add_line("shot_cone_start".to_string(), Line::new(origin, world.geometry.ally_goal.line.start));
+        tools_data.annotations.add_line("shot_cone_end".to_string(), Line::new(origin, world.geometry.ally_goal.line.end));
+
+        // Get the robots and order them by their direction from the goal, like the wall positions,
+        // so that they don't cross each other
+        let direction_from_goal = |position: &Point2<f64>| vectors::angle_to_point(goal_center, *position);
+        let mut robots: Vec<&Robot<AllyInfo>> = self.ids.iter().filter_map(|id| world.allies_bot.get(id)).collect();
+        robots.sort_by(|a, b| direction_from_goal(&a.pose.position).total_cmp(&direction_from_goal(&b.pose.position)));
+        let mut positions = Self::wall_positions(world, &cone, &enlarged_penalty, robots.len());
+        positions.sort_by(|a, b| direction_from_goal(a).total_cmp(&direction_from_goal(b)));
+
+        let closest = closest_bot_to_point(robots.clone(), ball_pos);
+        for (robot, pos_on_penalty_line) in robots.into_iter().zip(positions) {
+            tools_data.annotations.add_circle(format!("wall_{}", robot.id), Circle::new(pos_on_penalty_line, world.geometry.robot_radius));
+            let robot_to_goal = Line::new(robot.pose.position, goal_center);
+            let dist_to_goal = if let Some(intersection) = enlarged_penalty.intersection_line(robot_to_goal){
+                (robot.pose.position - intersection).norm()
+            }else{0.};
+            let avoidance = dist_to_goal > 0.5;
+            let orientation = vectors::angle_to_point(robot.pose.position, goal_center) + PI;
+            let distance_to_ball = (ball_pos - robot.pose.position.xy()).norm();
+            let clearing = dist_to_goal < 0.4
+                && distance_to_ball < KICK_RANGE + world.geometry.robot_radius + world.geometry.ball_radius
+                && closest.is_some_and(|closest_bot_to_ball| closest_bot_to_ball.id == robot.id)
+                && !enlarged_penalty.is_inside(&ball_pos);
+            if clearing {
+                let ball_orientation = vectors::angle_to_point(robot.pose.position, ball_pos);
+                action_wrapper.push(robot.id, MoveTo::new(ball_pos, ball_orientation, 0., true, Some(Kick::StraightKick { power: 4. }), false, avoidance));
+            } else {
+                action_wrapper.push(robot.id, MoveTo::new(pos_on_penalty_line, orientation, 0., false, None, true, avoidance));
             }
         }
         false
     }
-}
\ No newline at end of file
+}
@@ -2,52 +2,162 @@ use std::backtrace;
 
 use crate::action::move_to::{self, MoveTo};
 use crate::action::ActionWrapper;
+use crate::behavior_tree::{ActionLeaf, BehaviorTree, Node, Selector};
 use crate::message::MessageData;
 use crate::strategy::basics::pass;
 use crate::strategy::Strategy;
 use crate::utils::{closest_bot_to_point, closest_bots_to_point, object_in_bot_trajectory};
+use crate::utils::prediction::BallTrajectory;
 use crabe_framework::data::geometry::Penalty;
 use crabe_framework::data::output::Kick;
 use crabe_framework::data::tool::ToolData;
-use crabe_framework::data::world::{Ball, EnemyInfo, Robot, World};
+use crabe_framework::data::world::{AllyInfo, Ball, EnemyInfo, Robot, World};
 use crabe_math::{shape::Line, vectors};
 use crabe_math::vectors::vector_from_angle;
 use crabe_protocol::protobuf::simulation_packet::MoveGlobalVelocity;
 use nalgebra::Point2;
 
+/// How much the keeper shifts toward the usual enemy shot target when the ball trajectory is unknown, between 0 and 1.
+const OPPONENT_SHOT_BIAS: f64 = 0.3;
+
 /// The GoalKeeper strategy is responsible for keeping the goal safe by moving the robot to the best position to block the ball.
-#[derive(Default)]
+/// It runs a behavior tree: the keeper clears the ball when it lies still in our penalty area,
+/// otherwise it blocks the shot.
+///
+/// # Example
+/// ```
+/// use crabe_decision::action::ActionWrapper;
+/// use crabe_decision::strategy::defensive::GoalKeeper;
+/// use crabe_decision::strategy::Strategy;
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::tool::ToolData;
+/// use crabe_framework::data::world::{AllyInfo, Ball, Pose, Robot, World};
+/// use nalgebra::{Point2, Point3};
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// let pose = Pose::new(Point2::new(-4., 0.5), 0.);
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, pose, ..Default::default() });
+/// let mut keeper = GoalKeeper::new(0, vec![]);
+/// let mut tools = ToolData::default();
+///
+/// // The ball is far, the keeper blocks the shot
+/// world.ball = Some(Ball { position: Point3::new(1., 0., 0.), ..Default::default() });
+/// keeper.step(&world, &mut tools, &mut ActionWrapper::default());
+/// assert_eq!(tools.behavior_trees[&0], vec![vec!["GoalKeeper".to_string(), "BlockShot".to_string()]]);
+///
+/// // The ball lies in our penalty area, the keeper clears it
+/// world.ball = Some(Ball { position: Point3::new(-4., 0., 0.), ..Default::default() });
+/// keeper.step(&world, &mut tools, &mut ActionWrapper::default());
+/// assert_eq!(tools.behavior_trees[&0], vec![vec!["GoalKeeper".to_string(), "ClearBall".to_string()]]);
+/// ```
 pub struct GoalKeeper {
     /// The id of the robot to move.
     id: u8,
     ids_to_not_pass: Vec<u8>,
     messages: Vec<MessageData>,
+    /// The behavior tree driving the keeper.
+    tree: BehaviorTree,
+}
+
+impl Default for GoalKeeper {
+    fn default() -> Self {
+        Self::new(0, vec![])
+    }
 }
 
 impl GoalKeeper {
     /// Creates a new GoalKeeper instance with the desired robot id.
     pub fn new(id: u8, ids_to_not_pass: Vec<u8>) -> Self {
-        Self { id, ids_to_not_pass, messages: vec![]}
+        Self {
+            id,
+            ids_to_not_pass,
+            messages: vec![],
+            tree: BehaviorTree::new("GoalKeeper", vec![id], Self::tree),
+        }
     }
 
-    /// Calculates the trajectory of the ball based on its velocity.
-    /// The trajectory is calculated by extending the ball's position in the direction of its velocity.
-    /// If the ball's velocity is too low (less than 0.1), the function returns None.
-    fn follow_velocity_trajectory(&self, ball: &Ball, world: &World) -> Option<Point2<f64>> {
-        let ball_pos = ball.position_2d();
-        let ball_velocity_trajectory = Line::new(ball_pos, ball_pos + ball.velocity.xy().normalize() * 100.);
-        if ball.velocity.norm() > 0.01 {
-            if let Ok(intersection) = world.geometry.ally_goal.line.intersection_segments(&ball_velocity_trajectory) {
-                return Some(intersection);
+    /// Builds the behavior tree of the keeper, the first leaf able to run drives the robot:
+    /// - `ClearBall`: kicks the ball out when it lies still in our penalty area.
+    /// - `BlockShot`: stands where the ball is expected to cross the goal line, facing the ball.
+    fn tree() -> Box<dyn Node> {
+        Box::new(Selector::new("GoalKeeper", vec![
+            Box::new(ActionLeaf::reactive("ClearBall", |id, world, _blackboard| {
+                let robot = world.allies_bot.get(&id)?;
+                let ball = world.ball.as_ref().filter(|ball| Self::ball_to_clear(ball, world))?;
+                let ball_position = ball.position_2d();
+                let orientation = vectors::angle_to_point(robot.pose.position, ball_position);
+                Some(MoveTo::new(ball_position, orientation, 0.0, true, Some(Kick::StraightKick { power: 4. }), false, false).into())
+            })),
+            Box::new(ActionLeaf::reactive("BlockShot", |id, world, _blackboard| {
+                let robot = world.allies_bot.get(&id)?;
+                let (position, orientation) = Self::blocking_pose(robot, world);
+                Some(MoveTo::new(position, orientation, 0., false, None, true, false).into())
+            })),
+        ]))
+    }
+
+    /// Returns true if the ball lies still in our penalty area, without going toward our goal.
+    fn ball_to_clear(ball: &Ball, world: &World) -> bool {
+        Self::follow_velocity_trajectory(ball, world).is_none()
+            && ball.velocity.norm() < 0.1
+            && world.geometry.ally_penalty.is_inside(&ball.position_2d())
+    }
+
+    /// Returns the position and the orientation of the keeper blocking the shot.
+    /// Without ball, the keeper stands at the center of the goal, facing the center of the field.
+    fn blocking_pose(robot: &Robot<AllyInfo>, world: &World) -> (Point2<f64>, f64) {
+        let mut orientation_target = Point2::new(0., 0.);
+        let mut position_target = world.geometry.ally_goal.line.center();
+
+        // If the ball is present, the position and orientation have to be updated
+        if let Some(ball) = &world.ball {
+            orientation_target = ball.position_2d();
+            if let Some(intersection) = Self::follow_velocity_trajectory(ball, world) {
+                position_target = intersection;
+            } else {
+                position_target = Self::guess_shot_target(ball, world);
+                // Lean toward where the enemies usually shoot
+                if let Some(preferred_y) = world.opponent.preferred_shot_y() {
+                    position_target.y += (preferred_y - position_target.y) * OPPONENT_SHOT_BIAS;
+                }
             }
         }
+
+        // Calculate the orientation of the robot towards the orientation target
+        let orientation = vectors::angle_to_point(robot.pose.position, orientation_target);
+
+        // clamp the y position of the robot to the goal width so that he's not colliding with the goal walls
+        let goal_half_width = world.geometry.ally_goal.width /2.;
+        if goal_half_width > world.geometry.robot_radius {
+            position_target.y = position_target.y.clamp(-goal_half_width + world.geometry.robot_radius, goal_half_width - world.geometry.robot_radius);
+        }
+        (position_target, orientation)
+    }
+
+    /// Calculates the point where the ball will cross the goal line, using the predicted trajectory of the ball.
+    /// The prediction takes the deceleration of the ball and the wall bounces into account.
+    /// If the ball is too slow (less than 0.01) or doesn't reach the goal, the function returns None.
+    fn follow_velocity_trajectory(ball: &Ball, world: &World) -> Option<Point2<f64>> {
+        if ball.velocity.norm() <= 0.01 {
+            return None;
+        }
+        let goal_line = &world.geometry.ally_goal.line;
+        let trajectory = BallTrajectory::new(ball, &world.geometry);
+        for samples in trajectory.samples().windows(2) {
+            let (previous, current) = (samples[0].position_2d(), samples[1].position_2d());
+            if (previous.x - goal_line.start.x).signum() == (current.x - goal_line.start.x).signum() {
+                continue;
+            }
+            let crossing = Line::new(previous, current);
+            return goal_line.intersection_segments(&crossing).ok();
+        }
         None
     }
 
     /// Calculates the trajectory of the ball based on the enemy's position.
     /// The trajectory is calculated by extending the line from the enemy to the ball's position.
     /// If the trajectory intersects with the goal line, the function returns the intersection point.
-    fn follow_enemy_to_ball_trajectory(&self, ball: &Ball, world: &World, enemy: &Robot<EnemyInfo>) -> Option<Point2<f64>> {
+    fn follow_enemy_to_ball_trajectory(ball: &Ball, world: &World, enemy: &Robot<EnemyInfo>) -> Option<Point2<f64>> {
         let ball_pos = ball.position_2d();
         let enemy_to_ball = ball_pos - enemy.pose.position;
         let enemy_to_ball_trajectory = Line::new(ball_pos, ball_pos + enemy_to_ball.normalize() * 100.);
@@ -60,7 +170,7 @@ impl GoalKeeper {
     /// Calculates the trajectory of the ball based on the enemy's direction.
     /// The trajectory is calculated by extending the line from the enemy in the direction of its orientation.
     /// If the trajectory intersects with the goal line, the function returns the intersection point.
-    fn follow_enemy_direction(&self, world: &World, enemy: &Robot<EnemyInfo>) -> Option<Point2<f64>> {
+    fn follow_enemy_direction(world: &World, enemy: &Robot<EnemyInfo>) -> Option<Point2<f64>> {
         let enemy_dir = vector_from_angle(enemy.pose.orientation) * 100.;
         let enemy_dir_trajectory = Line::new(enemy.pose.position, enemy.pose.position + enemy_dir);
         if let Ok(intersection) = world.geometry.ally_goal.line.intersection_segments(&enemy_dir_trajectory) {
@@ -68,6 +178,20 @@ impl GoalKeeper {
         }
         None
     }
+
+    /// Guesses where the next shot will cross the goal line when the ball is not moving toward our goal,
+    /// from the position and the orientation of the enemy closest to the ball.
+    /// Falls back to the y of the ball on the goal line.
+    fn guess_shot_target(ball: &Ball, world: &World) -> Point2<f64> {
+        let ball_position = ball.position_2d();
+        let follow_ball_y_position = Point2::new(world.geometry.ally_goal.line.start.x, ball_position.y);
+        match closest_bot_to_point(world.enemies_bot.values().collect(), ball_position) {
+            Some(closest_enemy) => Self::follow_enemy_to_ball_trajectory(ball, world, closest_enemy)
+                .or_else(|| Self::follow_enemy_direction(world, closest_enemy))
+                .unwrap_or(follow_ball_y_position),
+            None => follow_ball_y_position,
+        }
+    }
 }
 
 impl Strategy for GoalKeeper {
@@ -76,8 +200,8 @@ impl Strategy for GoalKeeper {
         return "GoalKeeper";
     }
     
-    fn get_messages(&self) -> &Vec<MessageData>  {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         vec![self.id]
@@ -85,9 +209,12 @@ impl Strategy for GoalKeeper {
     fn put_ids(&mut self, ids: Vec<u8>) {
         if ids.len() == 1{
             self.id = ids[0];
+            self.tree.put_ids(ids);
         }
     }
 
+    /// Ticks the behavior tree of the keeper, see `GoalKeeper::tree`.
+    ///
     /// # Arguments
     ///
     /// * world: The current state of the game world.
@@ -97,64 +224,13 @@ impl Strategy for GoalKeeper {
     /// # Returns
     ///
     /// A boolean value indicating whether the strategy is finished or not.
-    #[allow(unused_variables)]
     fn step(
         &mut self,
         world: &World,
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     ) -> bool {
-        // Clean the action wrapper otherwise the previous commands will still have to be runned before the one he will calculate now
-        action_wrapper.clear(self.id);
-
-        // Get the GoalKeeper robot, otherwise exit the function
-        let robot = match world.allies_bot.get(&self.id) {
-            Some(robot) => robot,
-            None => return false,
-        };
-        
-        // Set the default position and orientation target
-        // - position : the center of the goal
-        // - orientation : towards the center of the field
-        let mut orientation_target = Point2::new(0., 0.);
-        let mut position_target = world.geometry.ally_goal.line.center();
-        
-        // If the ball is present, the position and orientation have to be updated
-        if let Some(ball) = &world.ball{
-            let ball_position = ball.position_2d();
-            let follow_ball_y_position = Point2::new(world.geometry.ally_goal.line.start.x, ball_position.y);
-            let penalty = &world.geometry.ally_penalty;
-            orientation_target = ball_position;
-            if let Some(intersection) = self.follow_velocity_trajectory(ball, world){
-                position_target = intersection;
-            } else if ball.velocity.norm() < 0.1 && penalty.is_inside(&ball_position) {
-                action_wrapper.push(robot.id, MoveTo::new(ball_position, vectors::angle_to_point(robot.pose.position, ball_position), 0.0, true, Some(Kick::StraightKick { power: 4. }), false, false));
-                return false;
-            } else if let Some(closest_enemy) = closest_bot_to_point(world.enemies_bot.values().collect(), ball_position){
-                if let Some(intersection) = self.follow_enemy_to_ball_trajectory(ball, world, closest_enemy){
-                    position_target = intersection;
-                } else if let Some(intersection) = self.follow_enemy_direction( world, closest_enemy){
-                    position_target = intersection;
-                } else{
-                    position_target = follow_ball_y_position;
-                }
-            }else{
-                position_target = follow_ball_y_position;
-            }
-        }
-
-        // Calculate the orientation of the robot towards the orientation target
-        let orientation = vectors::angle_to_point(robot.pose.position, orientation_target);
-
-        // clamp the y position of the robot to the goal width so that he's not colliding with the goal walls
-        let goal_half_width = world.geometry.ally_goal.width /2.;
-        if goal_half_width > world.geometry.robot_radius {
-            position_target.y = position_target.y.clamp(-goal_half_width + world.geometry.robot_radius, goal_half_width - world.geometry.robot_radius);
-        }
-
-        // Move the robot to the calculated position and orientation
-        action_wrapper.push(self.id, MoveTo::new(position_target, orientation, 0., false, None, true, false));
-        false
+        self.tree.step(world, tools_data, action_wrapper)
     }
 
 }
\ No newline at end of file
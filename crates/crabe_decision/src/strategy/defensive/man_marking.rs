@@ -0,0 +1,129 @@
+use crate::action::move_to::MoveTo;
+use crate::action::ActionWrapper;
+use crate::message::MessageData;
+use crate::strategy::basics::{mark, MarkingMode};
+use crate::strategy::Strategy;
+use crate::utils::assignment::optimal_assignment;
+use crate::utils::prediction::robot_time_to_point;
+use crate::utils::threat::{enemy_threats, MARK_THREAT_THRESHOLD, RELEASE_THREAT_THRESHOLD};
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::{AllyInfo, EnemyInfo, Robot, World};
+use crabe_math::shape::Line;
+use crabe_math::vectors;
+
+/// Distance to our goal under which an enemy is marked goal-side rather than on the pass lane (m).
+const GOAL_SIDE_DISTANCE: f64 = 3.;
+/// Distance from our goal, towards the ball, where the markers without enemy wait (m).
+const WAITING_DISTANCE: f64 = 2.;
+
+/// The ManMarking strategy coordinates the markers: it ranks the enemies by threat, assigns
+/// the markers to the most dangerous ones with an optimal matching and releases the enemies
+/// that become harmless.
+#[derive(Default)]
+pub struct ManMarking {
+    /// The ids of the markers.
+    ids: Vec<u8>,
+    /// The ids of the enemies currently marked.
+    marked: Vec<u8>,
+    messages: Vec<MessageData>,
+}
+
+impl ManMarking {
+    /// Creates a new ManMarking instance with the desired robot ids.
+    pub fn new(ids: Vec<u8>) -> Self {
+        Self { ids, marked: vec![], messages: vec![] }
+    }
+
+    /// Updates the marked enemies: the enemies over the marking threshold start being marked,
+    /// the marked enemies are released under the release threshold. Only the most dangerous
+    /// enemies are marked when there are not enough markers.
+    fn update_marked(&mut self, world: &World) {
+        let threats = enemy_threats(world);
+        self.marked = threats.iter()
+            .filter(|threat| {
+                let threshold = if self.marked.contains(&threat.id) { RELEASE_THREAT_THRESHOLD } else { MARK_THREAT_THRESHOLD };
+                threat.score >= threshold
+            })
+            .take(self.ids.len())
+            .map(|threat| threat.id)
+            .collect();
+    }
+
+    /// Chooses how to mark an enemy: goal-side when it is close to our goal, on the pass lane otherwise.
+    fn marking_mode(world: &World, enemy: &Robot<EnemyInfo>) -> MarkingMode {
+        if (world.geometry.ally_goal.line.center() - enemy.pose.position).norm() < GOAL_SIDE_DISTANCE {
+            MarkingMode::GoalSide
+        } else {
+            MarkingMode::PassLane
+        }
+    }
+}
+
+impl Strategy for ManMarking {
+    fn name(&self) -> &'static str {
+        "ManMarking"
+    }
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
+    }
+    fn get_ids(&self) -> Vec<u8> {
+        self.ids.clone()
+    }
+    fn put_ids(&mut self, ids: Vec<u8>) {
+        self.ids = ids;
+    }
+
+    /// Executes the ManMarking strategy.
+    ///
+    /// The markers are assigned to the marked enemies minimizing the total time to reach them.
+    /// The markers without enemy wait between the ball and our goal.
+    ///
+    /// # Arguments
+    ///
+    /// * world: The current state of the game world.
+    /// * tools_data: A collection of external tools used by the strategy, such as a viewer.
+    /// * action_wrapper: An `ActionWrapper` instance used to issue actions to the robot.
+    ///
+    /// # Returns
+    ///
+    /// A boolean value indicating whether the strategy is finished or not.
+    fn step(
+        &mut self,
+        world: &World,
+        tools_data: &mut ToolData,
+        action_wrapper: &mut ActionWrapper,
+    ) -> bool {
+        for id in &self.ids {
+            action_wrapper.clear(*id);
+        }
+        let ball = match &world.ball {
+            Some(ball) => ball,
+            None => return false,
+        };
+        self.update_marked(world);
+
+        let markers: Vec<&Robot<AllyInfo>> = self.ids.iter().filter_map(|id| world.allies_bot.get(id)).collect();
+        let enemies: Vec<&Robot<EnemyInfo>> = self.marked.iter().filter_map(|id| world.enemies_bot.get(id)).collect();
+        let costs: Vec<Vec<f64>> = markers.iter()
+            .map(|marker| enemies.iter().map(|enemy| robot_time_to_point(*marker, &enemy.pose.position, 0.)).collect())
+            .collect();
+        let assignment = optimal_assignment(&costs);
+
+        let goal_center = world.geometry.ally_goal.line.center();
+        let waiting_position = goal_center + (ball.position_2d() - goal_center).try_normalize(f64::EPSILON).unwrap_or_default() * WAITING_DISTANCE;
+        for (marker, enemy_index) in markers.iter().zip(assignment) {
+            match enemy_index.map(|index| enemies[index]) {
+                Some(enemy) => {
+                    let mode = Self::marking_mode(world, enemy);
+                    tools_data.annotations.add_line(format!("marking_{}", marker.id), Line::new(marker.pose.position, enemy.pose.position));
+                    action_wrapper.push(marker.id, mark(marker, enemy, ball, mode, world));
+                }
+                None => {
+                    let orientation = vectors::angle_to_point(marker.pose.position, ball.position_2d());
+                    action_wrapper.push(marker.id, MoveTo::new(waiting_position, orientation, 0., false, None, false, true));
+                }
+            }
+        }
+        false
+    }
+}
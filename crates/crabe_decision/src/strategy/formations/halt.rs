@@ -31,8 +31,8 @@ impl Strategy for Halt {
         "Halt"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData> {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         self.ids.clone()
@@ -34,8 +34,8 @@ impl Strategy for PrepareKickOff {
         "PrepareKickOff"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData> {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         self.ids.clone()
@@ -6,12 +6,15 @@ use crate::utils::KEEPER_ID;
 use crabe_framework::data::tool::ToolData;
 use crabe_framework::data::world::World;
 use crabe_math::vectors::angle_to_point;
+use nalgebra::Point2;
 
 
 /// Strategy prep
 #[derive(Default)]
 pub struct PrepareStart {
     ids: Vec<u8>,
+    /// The positions of the line robots, the default line in front of our penalty area if empty.
+    formation: Vec<Point2<f64>>,
     messages: Vec<MessageData>,
 }
 
@@ -20,6 +23,16 @@ impl PrepareStart {
     pub fn new(ids: Vec<u8>) -> Self {
         Self {
             ids,
+            formation: vec![],
+            messages: vec![],
+        }
+    }
+
+    /// Creates a new PrepareStart instance placing the line robots on the given positions
+    pub fn with_formation(ids: Vec<u8>, formation: Vec<Point2<f64>>) -> Self {
+        Self {
+            ids,
+            formation,
             messages: vec![],
         }
     }
@@ -30,8 +43,8 @@ impl Strategy for PrepareStart {
         "PrepareStart"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData> {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         self.ids.clone()
@@ -54,6 +67,9 @@ impl Strategy for PrepareStart {
                 let orientation = angle_to_point(robot.pose.position, nalgebra::Point2::new(0.0, 0.0));
                 if *id == KEEPER_ID {
                     action_wrapper.push(*id, MoveTo::new(world.geometry.ally_goal.line.center(), orientation, 0.0, false, None, true, true));
+                } else if let Some(target) = self.formation.get(i) {
+                    action_wrapper.push(*id, MoveTo::new(*target, orientation, 0.0, false, None, true, true));
+                    i += 1;
                 } else {
                     let target = nalgebra::Point2::new(world.geometry.ally_penalty.front_line.center().x + 0.2, i as f64 * (world.geometry.robot_radius * 2. + 0.02) - (((self.ids.len() as f64 -2.) / 2.) * (world.geometry.robot_radius * 2. + 0.02)));
                     action_wrapper.push(*id, MoveTo::new(target, orientation, 0.0, false, None, true, true));
@@ -3,4 +3,8 @@
 mod attacker;
 pub use self::attacker::Attacker;
 mod receiver;
-pub use self::receiver::Receiver;
\ No newline at end of file
+pub use self::receiver::Receiver;
+mod support_attacker;
+pub use self::support_attacker::SupportAttacker;
+mod set_piece;
+pub use self::set_piece::SetPiece;
\ No newline at end of file
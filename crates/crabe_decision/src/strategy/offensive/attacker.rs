@@ -2,17 +2,18 @@ use std::f64::consts::PI;
 use crate::action::move_to::MoveTo;
 use crate::message::AttackerMessage;
 use crate::message::Message;
-use crate::strategy::basics::pass;
+use crate::strategy::basics::pass_to_point;
 use crate::strategy::basics::shoot;
 use crate::strategy::basics::intercept;
 use crate::action::ActionWrapper;
 use crate::message::MessageData;
+use crate::message::Recipient;
 use crate::strategy::Strategy;
-use crate::utils::get_best_shooting_window_bot;
+use crate::utils::{annotate_pass_heat_map, pass_candidates};
 use crate::utils::get_open_shoot_window;
 use crate::utils::object_in_bot_trajectory;
-use crate::utils::KEEPER_ID;
 use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::game_state::GameState;
 use crabe_framework::data::world::AllyInfo;
 use crabe_framework::data::world::Ball;
 use crabe_framework::data::world::Robot;
@@ -35,24 +36,25 @@ impl Attacker {
         Self { id, messages: vec![]}
     }
 
-    /// Find the best ally to pass the ball to
+    /// Find the best pass to an ally, scoring receiving points around each ally
     fn pass_to_ally(&mut self, world: &World, robot: &Robot<AllyInfo>, ball: &Ball, tools : &mut ToolData) -> MoveTo{
-        // grab allies in the enemy side
-        let allies_in_positive_x : Vec<&Robot<AllyInfo>> = world.allies_bot.values().filter(|ally| ally.pose.position.x > 0. && ally.id != self.id && ally.id != KEEPER_ID).collect();
-        if allies_in_positive_x.len() == 0{
-            return shoot(robot, &ball, &world.geometry.enemy_goal.line.center(), world);
-        }
-        let robot_position = robot.pose.position;
-        let closest_ally: Option<&Robot<AllyInfo>> = get_best_shooting_window_bot(&allies_in_positive_x, world);
-        match closest_ally {
-            Some(ally) => {
-                let robot_to_ally = (ally.pose.position - robot_position).normalize();
-                let passing_trajectory = Line::new(robot_position + robot_to_ally, robot_position + robot_to_ally * 10.);
-                let move_to_command = pass(&robot, &ally, &ball, world);
+        let ball_position = ball.position_2d();
+        let candidates = pass_candidates(world, self.id, &ball_position);
+        annotate_pass_heat_map(tools, &candidates);
+        let best = candidates.into_iter().max_by(|a, b| a.score.total_cmp(&b.score));
+        match best {
+            Some(best_pass) => {
+                tools.annotations.add_point("pass_target".to_string(), best_pass.point);
+                let robot_to_target = (best_pass.point - robot.pose.position).normalize();
+                let passing_trajectory = Line::new(ball_position, ball_position + robot_to_target * 10.);
+                let move_to_command = pass_to_point(&robot, &best_pass.point, &ball, world);
                 if move_to_command.kicker.is_some(){
-                    self.messages.push(MessageData::new(Message::AttackerMessage(AttackerMessage::BallPassed(ally.id)), self.id));
+                    self.messages.push(MessageData::new(Message::AttackerMessage(AttackerMessage::BallPassed(best_pass.receiver_id)), self.id));
                 }else{
-                    self.messages.push(MessageData::new(Message::AttackerMessage(AttackerMessage::WantToPassBallTo(ally.id, passing_trajectory)), self.id));
+                    let message = Message::AttackerMessage(AttackerMessage::WantToPassBallTo(best_pass.receiver_id, passing_trajectory));
+                    // the manager gives the receiver role, then the receiver follows the trajectory while it changes
+                    self.messages.push(MessageData::new(message.clone(), self.id));
+                    self.messages.push(MessageData::new(message, self.id).to(Recipient::Robot(best_pass.receiver_id)));
                 }
                 move_to_command
             },
@@ -69,8 +71,8 @@ impl Strategy for Attacker {
         return "Attacker";
     }
 
-    fn get_messages(&self) -> &Vec<MessageData>  {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         vec![self.id]
@@ -81,6 +83,11 @@ impl Strategy for Attacker {
         }
     }
 
+    /// The pass announced before the change of the game state is dropped.
+    fn on_game_state_change(&mut self, _previous: GameState, _current: GameState) {
+        self.messages.clear();
+    }
+
     /// # Arguments
     ///
     /// * world: The current state of the game world.
@@ -116,6 +123,7 @@ impl Strategy for Attacker {
             action_wrapper.push(self.id, intercept(
                 &robot,
                 &ball,
+                world,
             ));
             return false;
         }
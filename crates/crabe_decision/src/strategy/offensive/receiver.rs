@@ -1,9 +1,10 @@
 use crate::action::move_to::MoveTo;
 use crate::action::ActionWrapper;
-use crate::message::MessageData;
+use crate::message::{AttackerMessage, Envelope, Message, MessageData};
 use crate::strategy::basics::intercept;
 use crate::strategy::Strategy;
 use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::game_state::GameState;
 use crabe_framework::data::world::World;
 use crabe_math::shape::Line;
 use crabe_math::vectors;
@@ -28,9 +29,19 @@ impl Strategy for Receiver {
         return "Receiver";
     }
     
-    fn get_messages(&self) -> &Vec<MessageData>  {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
+
+    /// Follows the passing trajectory sent by the passer.
+    fn receive_messages(&mut self, messages: &[Envelope]) {
+        for envelope in messages.iter().filter(|envelope| envelope.data.id == self.passer_id) {
+            if let Message::AttackerMessage(AttackerMessage::WantToPassBallTo(_, passing_trajectory)) = envelope.data.message {
+                self.passing_trajectory = passing_trajectory;
+            }
+        }
+    }
+
     fn get_ids(&self) -> Vec<u8> {
         vec![self.id]
     }
@@ -40,6 +51,11 @@ impl Strategy for Receiver {
         }
     }
 
+    /// The pass is over once the game state changes, the manager takes the receiver role back.
+    fn on_game_state_change(&mut self, _previous: GameState, _current: GameState) {
+        self.messages.push(MessageData::new(Message::AttackerMessage(AttackerMessage::NoNeedReceiver), self.id));
+    }
+
     /// # Arguments
     ///
     /// * world: The current state of the game world.
@@ -78,7 +94,7 @@ impl Strategy for Receiver {
         }
 
         if ball.velocity.norm() > 0.2 {
-            action_wrapper.push(self.id, intercept(robot, ball));   
+            action_wrapper.push(self.id, intercept(robot, ball, world));
             return false;         
         }
         let interception_point = self.passing_trajectory.closest_point_on_segment(&robot_position);
@@ -0,0 +1,389 @@
+use crate::action::move_to::MoveTo;
+use crate::action::ActionWrapper;
+use crate::message::MessageData;
+use crate::play_file::PlayFile;
+use crate::strategy::basics::{chip_to_point, intercept, pass_to_point, shoot};
+use crate::strategy::Strategy;
+use crate::utils::playbook::{play_frame_to_world, Play, PlayAction, Playbook};
+use crate::utils::{get_open_shoot_window, optimal_assignment};
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::game_state::GameState;
+use crabe_framework::data::world::{AllyInfo, Ball, Robot, World};
+use crabe_math::shape::Line;
+use crabe_math::vectors;
+use nalgebra::Point2;
+use std::time::Instant;
+
+/// Distance the ball has to move during the setup to select a new play (m).
+const RESELECT_DISTANCE: f64 = 0.5;
+/// Distance kept from the ball while setting up, in addition to the distance required by the referee (m).
+const SETUP_BALL_MARGIN: f64 = 0.1;
+/// Time kept before the end of the free kick budget to take the kick (s).
+const BUDGET_MARGIN: f64 = 1.;
+/// Speed over which the ball is considered kicked to the receiver (m/s).
+const RECEIVE_BALL_SPEED: f64 = 0.2;
+
+/// The SetPiece strategy executes a play of the playbook during our free kicks and corner kicks.
+/// While the game is stopped, the robots go to the positions of the play,
+/// then they follow its timed actions once the kick is allowed,
+/// speeding the play up if the free kick time budget is running out.
+pub struct SetPiece {
+    /// The ids of the robots taking part in the set piece.
+    ids: Vec<u8>,
+    /// The plays to choose from.
+    playbook: Playbook,
+    /// The play being executed.
+    play: Option<Play>,
+    /// The position of the ball when the play was selected.
+    origin: Point2<f64>,
+    /// The id of the robot playing each role of the play.
+    roles: Vec<Option<u8>>,
+    /// The time since the kick was allowed, in the play clock (s).
+    elapsed: f64,
+    /// The time of the previous step while the kick is allowed.
+    last_step: Option<Instant>,
+    /// Time kept before the end of the free kick budget to take the kick (s).
+    budget_margin: f64,
+    messages: Vec<MessageData>,
+}
+
+impl SetPiece {
+    /// Creates a new SetPiece instance with the desired robot ids, using the default playbook.
+    pub fn new(ids: Vec<u8>) -> Self {
+        Self::with_playbook(ids, Playbook::default())
+    }
+
+    /// Creates a new SetPiece instance with the desired robot ids and plays.
+    pub fn with_playbook(ids: Vec<u8>, playbook: Playbook) -> Self {
+        Self {
+            ids,
+            playbook,
+            play: None,
+            origin: Point2::origin(),
+            roles: vec![],
+            elapsed: 0.,
+            last_step: None,
+            budget_margin: BUDGET_MARGIN,
+            messages: vec![],
+        }
+    }
+
+    /// Creates a new SetPiece instance with the desired robot ids, using the plays and parameters of a play file.
+    pub fn from_play_file(ids: Vec<u8>, play_file: &PlayFile) -> Self {
+        Self::with_playbook(ids, play_file.playbook())
+            .with_budget_margin(play_file.parameter("SetPiece", "budget_margin", BUDGET_MARGIN))
+    }
+
+    /// Sets the time kept before the end of the free kick budget to take the kick (s).
+    pub fn with_budget_margin(mut self, budget_margin: f64) -> Self {
+        self.budget_margin = budget_margin;
+        self
+    }
+
+    /// Selects the best play for the current situation and restarts the play clock.
+    fn select_play(&mut self, world: &World, ball_position: Point2<f64>) {
+        let robots_count = self
+            .ids
+            .iter()
+            .filter(|id| world.allies_bot.contains_key(id))
+            .count();
+        self.play = self
+            .playbook
+            .select(world, &ball_position, robots_count)
+            .cloned();
+        self.origin = ball_position;
+        self.elapsed = 0.;
+        self.last_step = None;
+    }
+
+    /// Gives the kick to the robot closest to the ball, and the other roles to the robots
+    /// minimizing the total distance to their positions.
+    fn assign_roles(&mut self, world: &World, play: &Play) {
+        let mut robots: Vec<&Robot<AllyInfo>> = self
+            .ids
+            .iter()
+            .filter_map(|id| world.allies_bot.get(id))
+            .collect();
+        self.roles = vec![None; play.roles_count()];
+        robots.sort_by(|a, b| {
+            a.distance(&self.origin)
+                .total_cmp(&b.distance(&self.origin))
+        });
+        if robots.is_empty() {
+            return;
+        }
+        self.roles[0] = Some(robots.remove(0).id);
+
+        let positions: Vec<Point2<f64>> = play
+            .positions
+            .iter()
+            .skip(1)
+            .map(|position| play_frame_to_world(world, &self.origin, position))
+            .collect();
+        let costs: Vec<Vec<f64>> = robots
+            .iter()
+            .map(|robot| {
+                positions
+                    .iter()
+                    .map(|position| robot.distance(position))
+                    .collect()
+            })
+            .collect();
+        for (robot, role) in robots.iter().zip(optimal_assignment(&costs)) {
+            if let Some(role) = role {
+                self.roles[role + 1] = Some(robot.id);
+            }
+        }
+    }
+
+    /// Advances the play clock, faster when the time left to take the kick is shorter than the play.
+    fn advance_clock(&mut self, world: &World, play: &Play) {
+        let now = Instant::now();
+        let dt = self
+            .last_step
+            .map_or(0., |last_step| now.duration_since(last_step).as_secs_f64());
+        self.last_step = Some(now);
+
+        let remaining_play = play.duration() - self.elapsed;
+        let budget = world
+            .data
+            .ref_orders
+            .current_action_time_remaining
+            .map(|remaining| remaining.num_milliseconds() as f64 / 1000. - self.budget_margin);
+        match budget {
+            Some(budget) if remaining_play > 0. && budget <= 0. => self.elapsed = play.duration(),
+            Some(budget) if remaining_play > 0. => {
+                self.elapsed += dt * (remaining_play / budget).max(1.)
+            }
+            _ => self.elapsed += dt,
+        }
+    }
+
+    /// Returns the target position of a role in the world frame.
+    fn role_position(&self, world: &World, play: &Play, role: usize) -> Point2<f64> {
+        match self
+            .roles
+            .get(role)
+            .copied()
+            .flatten()
+            .and_then(|id| world.allies_bot.get(&id))
+        {
+            Some(robot) => robot.pose.position,
+            None => play_frame_to_world(
+                world,
+                &self.origin,
+                &play.final_position(role).unwrap_or_default(),
+            ),
+        }
+    }
+
+    /// Computes the order of a robot playing a role while the kick is allowed.
+    fn role_order(
+        &self,
+        world: &World,
+        play: &Play,
+        role: usize,
+        robot: &Robot<AllyInfo>,
+        ball: &Ball,
+    ) -> MoveTo {
+        let ball_position = ball.position_2d();
+        let orientation = vectors::angle_to_point(robot.pose.position, ball_position);
+        let action = play
+            .action_at(role, self.elapsed)
+            .unwrap_or(PlayAction::MoveTo(play.positions[role]));
+        match action {
+            PlayAction::MoveTo(position) => {
+                let target = play_frame_to_world(world, &self.origin, &position);
+                MoveTo::new(target, orientation, 0., false, None, false, true)
+            }
+            PlayAction::Shoot => {
+                let shoot_windows = get_open_shoot_window(&ball_position, world);
+                let target = shoot_windows
+                    .iter()
+                    .max_by(|a, b| a.norm().total_cmp(&b.norm()))
+                    .map_or(world.geometry.enemy_goal.line.center(), Line::center);
+                shoot(robot, ball, &target, world)
+            }
+            PlayAction::Pass(receiver_role) => pass_to_point(
+                robot,
+                &self.role_position(world, play, receiver_role),
+                ball,
+                world,
+            ),
+            PlayAction::Chip(receiver_role) => chip_to_point(
+                robot,
+                &self.role_position(world, play, receiver_role),
+                ball,
+                world,
+            ),
+            PlayAction::Receive => {
+                if ball.velocity.norm() > RECEIVE_BALL_SPEED {
+                    intercept(robot, ball, world)
+                } else {
+                    MoveTo::new(
+                        robot.pose.position,
+                        orientation,
+                        1.,
+                        false,
+                        None,
+                        false,
+                        true,
+                    )
+                }
+            }
+        }
+    }
+
+    /// Returns a position kept out of the distance to the ball required by the referee.
+    fn away_from_ball(
+        world: &World,
+        ball_position: &Point2<f64>,
+        position: Point2<f64>,
+    ) -> Point2<f64> {
+        let min_distance = world
+            .data
+            .ref_orders
+            .min_dist_from_ball
+            .map_or(0., |dist| dist as f64)
+            + SETUP_BALL_MARGIN;
+        let ball_to_position = position - ball_position;
+        if ball_to_position.norm() >= min_distance {
+            return position;
+        }
+        let direction = ball_to_position
+            .try_normalize(f64::EPSILON)
+            .unwrap_or((world.geometry.ally_goal.line.center() - ball_position).normalize());
+        ball_position + direction * min_distance
+    }
+}
+
+impl Strategy for SetPiece {
+    fn name(&self) -> &'static str {
+        "SetPiece"
+    }
+
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
+    }
+
+    fn get_ids(&self) -> Vec<u8> {
+        self.ids.clone()
+    }
+
+    fn put_ids(&mut self, ids: Vec<u8>) {
+        self.ids = ids;
+    }
+
+    /// The roles are given again, so that a removed robot doesn't keep the kick.
+    fn on_robot_removed(&mut self, id: u8) {
+        if self.roles.contains(&Some(id)) {
+            self.roles.clear();
+        }
+    }
+
+    /// A new stop after the kick starts a new set piece, the play is selected again.
+    fn on_game_state_change(&mut self, previous: GameState, current: GameState) {
+        if matches!(previous, GameState::Running(_)) && !matches!(current, GameState::Running(_)) {
+            self.play = None;
+            self.roles.clear();
+            self.elapsed = 0.;
+            self.last_step = None;
+        }
+    }
+
+    /// # Arguments
+    ///
+    /// * world: The current state of the game world.
+    /// * tools_data: A collection of external tools used by the strategy, such as a viewer.
+    /// * action_wrapper: An `ActionWrapper` instance used to issue actions to the robot.
+    ///
+    /// # Returns
+    ///
+    /// A boolean value indicating whether the strategy is finished or not.
+    fn step(
+        &mut self,
+        world: &World,
+        tools_data: &mut ToolData,
+        action_wrapper: &mut ActionWrapper,
+    ) -> bool {
+        for id in &self.ids {
+            action_wrapper.clear(*id);
+        }
+        let ball = match &world.ball {
+            Some(ball) => ball,
+            None => return false,
+        };
+        let ball_position = ball.position_2d();
+        let kick_allowed = matches!(world.data.ref_orders.state, GameState::Running(_));
+
+        // The ball can be placed again during the setup, the play is then selected again
+        if self.play.is_none()
+            || (!kick_allowed && (ball_position - self.origin).norm() > RESELECT_DISTANCE)
+        {
+            self.select_play(world, ball_position);
+        }
+        let play = match self.play.clone() {
+            Some(play) => play,
+            None => return false,
+        };
+
+        // The roles are fixed once the kick is allowed, so that the taker doesn't change
+        if !kick_allowed || self.roles.len() != play.roles_count() {
+            self.assign_roles(world, &play);
+        }
+        if kick_allowed {
+            self.advance_clock(world, &play);
+        } else {
+            self.elapsed = 0.;
+            self.last_step = None;
+        }
+
+        for (role, id) in self.roles.iter().enumerate() {
+            let Some(robot) = id.and_then(|id| world.allies_bot.get(&id)) else {
+                continue;
+            };
+            let order = if kick_allowed {
+                self.role_order(world, &play, role, robot, ball)
+            } else {
+                let position = play_frame_to_world(world, &self.origin, &play.positions[role]);
+                let target = Self::away_from_ball(world, &ball_position, position);
+                tools_data
+                    .annotations
+                    .add_point(format!("set_piece_{}", role), target);
+                MoveTo::new(
+                    target,
+                    vectors::angle_to_point(target, ball_position),
+                    0.,
+                    false,
+                    None,
+                    false,
+                    true,
+                )
+            };
+            action_wrapper.push(robot.id, order);
+        }
+
+        // The robots without a role stay out of the way
+        for robot in self
+            .ids
+            .iter()
+            .filter(|id| !self.roles.contains(&Some(**id)))
+            .filter_map(|id| world.allies_bot.get(id))
+        {
+            let target = Self::away_from_ball(world, &ball_position, robot.pose.position);
+            action_wrapper.push(
+                robot.id,
+                MoveTo::new(
+                    target,
+                    vectors::angle_to_point(target, ball_position),
+                    0.,
+                    false,
+                    None,
+                    false,
+                    true,
+                ),
+            );
+        }
+        false
+    }
+}
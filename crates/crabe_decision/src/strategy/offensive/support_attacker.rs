@@ -0,0 +1,186 @@
+use crate::action::move_to::MoveTo;
+use crate::action::ActionWrapper;
+use crate::message::MessageData;
+use crate::strategy::Strategy;
+use crate::utils::get_open_shoot_window;
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::game_state::GameState;
+use crabe_framework::data::world::World;
+use crabe_math::shape::{Circle, Line};
+use crabe_math::vectors;
+use nalgebra::Point2;
+use std::collections::HashMap;
+
+/// Distance between two sampled support positions (m).
+const GRID_STEP: f64 = 0.4;
+/// Distance kept from the field lines (m).
+const FIELD_MARGIN: f64 = 0.3;
+/// Distance kept from the enemy penalty area (m).
+const PENALTY_MARGIN: f64 = 0.2;
+/// Minimum distance from the ball, to leave space to the attacker (m).
+const MIN_BALL_DISTANCE: f64 = 1.;
+/// Distance from the other supports under which a position is penalized (m).
+const MIN_SUPPORT_SEPARATION: f64 = 1.5;
+/// Distance to the enemies or the pass lane over which a position is considered fully open (m).
+const OPEN_DISTANCE: f64 = 1.;
+/// Score bonus given to the current target of a robot, so that it only moves for a clearly better position.
+const HYSTERESIS: f64 = 0.15;
+
+/// Weight of the open shooting window in the score of a position.
+const SHOT_WEIGHT: f64 = 0.5;
+/// Weight of the pass lane openness in the score of a position.
+const LANE_WEIGHT: f64 = 0.3;
+/// Weight of the distance to the enemies in the score of a position.
+const ENEMY_WEIGHT: f64 = 0.2;
+
+/// The SupportAttacker strategy places robots where they can receive a pass and shoot,
+/// away from the enemies and from each other.
+#[derive(Default)]
+pub struct SupportAttacker {
+    /// The ids of the robots to move.
+    ids: Vec<u8>,
+    /// The position chosen for each robot at the previous step.
+    targets: HashMap<u8, Point2<f64>>,
+    messages: Vec<MessageData>,
+}
+
+impl SupportAttacker {
+    /// Creates a new SupportAttacker instance with the desired robot ids.
+    pub fn new(ids: Vec<u8>) -> Self {
+        Self { ids, targets: HashMap::new(), messages: vec![] }
+    }
+
+    /// Returns true if a support robot is allowed to go to a position: on the field,
+    /// out of the enemy penalty area and far enough from the ball.
+    fn is_allowed(world: &World, ball_position: &Point2<f64>, position: &Point2<f64>) -> bool {
+        let half_length = world.geometry.field.length / 2. - FIELD_MARGIN;
+        let half_width = world.geometry.field.width / 2. - FIELD_MARGIN;
+        let enemy_penalty = world.geometry.enemy_penalty.enlarged_penalty(PENALTY_MARGIN);
+        let min_ball_distance = world.data.ref_orders.min_dist_from_ball.map_or(0., |dist| dist as f64).max(MIN_BALL_DISTANCE);
+        position.x.abs() <= half_length
+            && position.y.abs() <= half_width
+            && !enemy_penalty.is_inside(position)
+            && (position - ball_position).norm() >= min_ball_distance
+    }
+
+    /// Returns the positions sampled in the enemy half where a support robot is allowed to go.
+    fn candidate_positions(world: &World, ball_position: &Point2<f64>) -> Vec<Point2<f64>> {
+        let half_length = world.geometry.field.length / 2. - FIELD_MARGIN;
+        let half_width = world.geometry.field.width / 2. - FIELD_MARGIN;
+        let mut positions = vec![];
+        let mut x = 0.;
+        while x <= half_length {
+            let mut y = -half_width;
+            while y <= half_width {
+                let position = Point2::new(x, y);
+                if Self::is_allowed(world, ball_position, &position) {
+                    positions.push(position);
+                }
+                y += GRID_STEP;
+            }
+            x += GRID_STEP;
+        }
+        positions
+    }
+
+    /// Scores a position between 0 and 1, from its open shooting window,
+    /// the openness of the pass lane from the ball and the distance to the enemies.
+    fn score(world: &World, ball_position: &Point2<f64>, position: &Point2<f64>) -> f64 {
+        let open_width: f64 = get_open_shoot_window(position, world).iter().map(|window| window.norm()).sum();
+        let shot = (open_width / world.geometry.enemy_goal.width).clamp(0., 1.);
+
+        let lane = Line::new(*ball_position, *position);
+        let lane_distance = world.enemies_bot.values()
+            .map(|enemy| (lane.closest_point_on_segment(&enemy.pose.position) - enemy.pose.position).norm())
+            .fold(OPEN_DISTANCE, f64::min);
+        let enemy_distance = world.enemies_bot.values()
+            .map(|enemy| (enemy.pose.position - position).norm())
+            .fold(OPEN_DISTANCE, f64::min);
+
+        SHOT_WEIGHT * shot + LANE_WEIGHT * lane_distance / OPEN_DISTANCE + ENEMY_WEIGHT * enemy_distance / OPEN_DISTANCE
+    }
+}
+
+impl Strategy for SupportAttacker {
+    fn name(&self) -> &'static str {
+        "SupportAttacker"
+    }
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
+    }
+    fn get_ids(&self) -> Vec<u8> {
+        self.ids.clone()
+    }
+    fn put_ids(&mut self, ids: Vec<u8>) {
+        self.targets.retain(|id, _| ids.contains(id));
+        self.ids = ids;
+    }
+
+    /// The positions are chosen again after a change of the game state, the ball having moved.
+    fn on_game_state_change(&mut self, _previous: GameState, _current: GameState) {
+        self.targets.clear();
+    }
+
+    /// Executes the SupportAttacker strategy.
+    ///
+    /// Each robot, in turn, takes the best scored position far enough from the positions already taken.
+    /// A robot keeps its previous position unless a new one is better by more than the hysteresis.
+    ///
+    /// # Arguments
+    ///
+    /// * world: The current state of the game world.
+    /// * tools_data: A collection of external tools used by the strategy, such as a viewer.
+    /// * action_wrapper: An `ActionWrapper` instance used to issue actions to the robot.
+    ///
+    /// # Returns
+    ///
+    /// A boolean value indicating whether the strategy is finished or not.
+    fn step(
+        &mut self,
+        world: &World,
+        tools_data: &mut ToolData,
+        action_wrapper: &mut ActionWrapper,
+    ) -> bool {
+        for id in &self.ids {
+            action_wrapper.clear(*id);
+        }
+        let ball_position = match &world.ball {
+            Some(ball) => ball.position_2d(),
+            None => return false,
+        };
+
+        let scored_positions: Vec<(Point2<f64>, f64)> = Self::candidate_positions(world, &ball_position)
+            .into_iter()
+            .map(|position| (position, Self::score(world, &ball_position, &position)))
+            .collect();
+        let mut taken: Vec<Point2<f64>> = vec![];
+        let mut ids = self.ids.clone();
+        ids.sort();
+        for id in ids {
+            let robot = match world.allies_bot.get(&id) {
+                Some(robot) => robot,
+                None => continue,
+            };
+            let free = |position: &Point2<f64>| taken.iter().all(|other| (other - position).norm() >= MIN_SUPPORT_SEPARATION);
+            let best = scored_positions.iter()
+                .filter(|(position, _)| free(position))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .copied();
+            let previous = self.targets.get(&id)
+                .filter(|position| free(position))
+                .filter(|position| Self::is_allowed(world, &ball_position, position))
+                .map(|position| (*position, Self::score(world, &ball_position, position) + HYSTERESIS));
+            let target = match (best, previous) {
+                (Some(best), Some(previous)) => if previous.1 >= best.1 { previous.0 } else { best.0 },
+                (Some(best), None) => best.0,
+                (None, Some(previous)) => previous.0,
+                (None, None) => continue,
+            };
+            taken.push(target);
+            self.targets.insert(id, target);
+            tools_data.annotations.add_circle(format!("support_{}", id), Circle::new(target, 0.1));
+            action_wrapper.push(id, MoveTo::new(target, vectors::angle_to_point(robot.pose.position, ball_position), 0., false, None, false, true));
+        }
+        false
+    }
+}
@@ -0,0 +1,539 @@
+use crate::strategy::basics::MarkingMode;
+use crate::strategy::defensive::{BotContesting, BotMarking, DefenseWall, GoalKeeper, ManMarking};
+use crate::strategy::formations::{
+    Halt, LateralAttack, MoveAwayFromBall, PrepareKickOff, PrepareStart,
+};
+use crate::strategy::offensive::{Attacker, Receiver, SetPiece, SupportAttacker};
+use crate::strategy::testing::{Aligned, GoLeft, GoRight, Prembule, Square, TestVisionMoveTo};
+use crate::strategy::Strategy;
+use crabe_framework::data::tool::{ParameterValue, StrategyRequest};
+use crabe_framework::data::world::TeamColor;
+use crabe_math::shape::Line;
+use nalgebra::Point2;
+use std::collections::HashMap;
+
+/// The type of a strategy parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterKind {
+    /// A robot id, from 0 to 255.
+    Id,
+    /// A real number.
+    Float,
+    /// `true` or `false`.
+    Bool,
+    /// One of the listed names, ignoring the case.
+    Choice(&'static [&'static str]),
+}
+
+/// The description of a parameter expected by a strategy.
+#[derive(Clone, Copy, Debug)]
+pub struct ParameterSchema {
+    /// The name of the parameter, like `"enemy_id"`.
+    pub name: &'static str,
+    /// The type of the parameter.
+    pub kind: ParameterKind,
+    /// The value used when the parameter is not given, written as on the command line.
+    /// The parameter is required if there is none.
+    pub default: Option<&'static str>,
+    /// What the parameter is used for.
+    pub description: &'static str,
+}
+
+impl ParameterSchema {
+    /// Checks a value against the type of the parameter, converting it if it is written as text.
+    ///
+    /// # Returns
+    /// The value with the type of the parameter, or an error if it doesn't fit.
+    pub fn check(&self, value: &ParameterValue) -> Result<ParameterValue, String> {
+        let invalid = || {
+            format!(
+                "invalid value {:?} for the parameter {}, expected {:?}",
+                value, self.name, self.kind
+            )
+        };
+        let value = match value {
+            ParameterValue::Text(text) => match self.kind {
+                ParameterKind::Id | ParameterKind::Float => {
+                    ParameterValue::Number(text.trim().parse().map_err(|_| invalid())?)
+                }
+                ParameterKind::Bool => {
+                    ParameterValue::Bool(text.trim().parse().map_err(|_| invalid())?)
+                }
+                ParameterKind::Choice(_) => value.clone(),
+            },
+            _ => value.clone(),
+        };
+        match (self.kind, &value) {
+            (ParameterKind::Id, ParameterValue::Number(number))
+                if number.fract() == 0. && (0. ..=u8::MAX as f64).contains(number) =>
+            {
+                Ok(value)
+            }
+            (ParameterKind::Float, ParameterValue::Number(_))
+            | (ParameterKind::Bool, ParameterValue::Bool(_)) => Ok(value),
+            (ParameterKind::Choice(choices), ParameterValue::Text(text)) => choices
+                .iter()
+                .find(|choice| choice.eq_ignore_ascii_case(text.trim()))
+                .map(|choice| ParameterValue::Text(choice.to_string()))
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The parameters given to a strategy, checked against its schema.
+pub struct Parameters {
+    values: HashMap<&'static str, ParameterValue>,
+}
+
+impl Parameters {
+    /// Checks the given parameters against a schema, filling the missing ones with their default value.
+    ///
+    /// # Returns
+    /// The checked parameters, or an error if a parameter is unknown, missing or has the wrong type.
+    pub fn check(
+        schema: &'static [ParameterSchema],
+        given: &HashMap<String, ParameterValue>,
+    ) -> Result<Self, String> {
+        if let Some(unknown) = given.keys().find(|name| {
+            !schema
+                .iter()
+                .any(|parameter| parameter.name == name.as_str())
+        }) {
+            return Err(format!("unknown parameter {}", unknown));
+        }
+        let mut values = HashMap::new();
+        for parameter in schema {
+            let value = match (given.get(parameter.name), parameter.default) {
+                (Some(value), _) => value.clone(),
+                (None, Some(default)) => ParameterValue::Text(default.to_string()),
+                (None, None) => return Err(format!("missing parameter {}", parameter.name)),
+            };
+            values.insert(parameter.name, parameter.check(&value)?);
+        }
+        Ok(Self { values })
+    }
+
+    fn get(&self, name: &str) -> Result<&ParameterValue, String> {
+        self.values
+            .get(name)
+            .ok_or_else(|| format!("missing parameter {}", name))
+    }
+
+    /// Returns a parameter of type `ParameterKind::Float`.
+    pub fn float(&self, name: &str) -> Result<f64, String> {
+        match self.get(name)? {
+            ParameterValue::Number(number) => Ok(*number),
+            value => Err(format!(
+                "the parameter {} is not a number: {:?}",
+                name, value
+            )),
+        }
+    }
+
+    /// Returns a parameter of type `ParameterKind::Id`.
+    pub fn id(&self, name: &str) -> Result<u8, String> {
+        self.float(name).map(|number| number as u8)
+    }
+
+    /// Returns a parameter of type `ParameterKind::Bool`.
+    pub fn bool(&self, name: &str) -> Result<bool, String> {
+        match self.get(name)? {
+            ParameterValue::Bool(value) => Ok(*value),
+            value => Err(format!(
+                "the parameter {} is not a boolean: {:?}",
+                name, value
+            )),
+        }
+    }
+
+    /// Returns a parameter of type `ParameterKind::Choice`, as written in the schema.
+    pub fn choice(&self, name: &str) -> Result<&str, String> {
+        match self.get(name)? {
+            ParameterValue::Text(text) => Ok(text),
+            value => Err(format!("the parameter {} is not a name: {:?}", name, value)),
+        }
+    }
+}
+
+/// Creates a strategy for the given robots and checked parameters.
+type StrategyConstructor = fn(Vec<u8>, &Parameters) -> Result<Box<dyn Strategy>, String>;
+
+/// A strategy that can be created from its name.
+pub struct StrategyEntry {
+    /// The name of the strategy, the same as `Strategy::name`.
+    pub name: &'static str,
+    /// What the strategy does.
+    pub description: &'static str,
+    /// True if the strategy moves a single robot.
+    pub single_robot: bool,
+    /// The parameters expected by the strategy, in addition to the robot ids.
+    pub parameters: &'static [ParameterSchema],
+    /// Creates the strategy for the given robots and checked parameters.
+    create: StrategyConstructor,
+}
+
+impl StrategyEntry {
+    /// Creates the strategy for the given robots.
+    ///
+    /// # Arguments
+    /// - `ids`: The ids of the robots moved by the strategy.
+    /// - `parameters`: The parameters of the strategy, by name.
+    ///
+    /// # Returns
+    /// The strategy, or an error if the robots or the parameters don't fit the strategy.
+    pub fn create(
+        &self,
+        ids: Vec<u8>,
+        parameters: &HashMap<String, ParameterValue>,
+    ) -> Result<Box<dyn Strategy>, String> {
+        if self.single_robot && ids.len() != 1 {
+            return Err(format!(
+                "{} moves exactly one robot, got {:?}",
+                self.name, ids
+            ));
+        }
+        let parameters = Parameters::check(self.parameters, parameters)
+            .map_err(|e| format!("{}: {}", self.name, e))?;
+        (self.create)(ids, &parameters)
+    }
+}
+
+const fn parameter(
+    name: &'static str,
+    kind: ParameterKind,
+    default: Option<&'static str>,
+    description: &'static str,
+) -> ParameterSchema {
+    ParameterSchema {
+        name,
+        kind,
+        default,
+        description,
+    }
+}
+
+/// The strategies that can be created from their name.
+pub const STRATEGIES: &[StrategyEntry] = &[
+    StrategyEntry {
+        name: "Attacker",
+        description: "Takes the ball and shoots to the enemy goal.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(Attacker::new(ids[0]))),
+    },
+    StrategyEntry {
+        name: "GoalKeeper",
+        description: "Defends our goal.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(GoalKeeper::new(ids[0], vec![]))),
+    },
+    StrategyEntry {
+        name: "BotContesting",
+        description: "Contests the ball with the enemy holding it.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(BotContesting::new(ids[0]))),
+    },
+    StrategyEntry {
+        name: "BotMarking",
+        description: "Marks an enemy robot.",
+        single_robot: true,
+        parameters: &[
+            parameter(
+                "enemy_id",
+                ParameterKind::Id,
+                None,
+                "The id of the marked enemy.",
+            ),
+            parameter(
+                "mode",
+                ParameterKind::Choice(&["GoalSide", "PassLane"]),
+                Some("GoalSide"),
+                "Whether to block the shots or the passes of the enemy.",
+            ),
+        ],
+        create: |ids, parameters| {
+            let mode = match parameters.choice("mode")? {
+                "PassLane" => MarkingMode::PassLane,
+                _ => MarkingMode::GoalSide,
+            };
+            Ok(Box::new(BotMarking::new(
+                ids[0],
+                parameters.id("enemy_id")?,
+                mode,
+            )))
+        },
+    },
+    StrategyEntry {
+        name: "DefenseWall",
+        description: "Forms a wall between the ball and our goal.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(DefenseWall::new(ids))),
+    },
+    StrategyEntry {
+        name: "ManMarking",
+        description: "Marks the most threatening enemies.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(ManMarking::new(ids))),
+    },
+    StrategyEntry {
+        name: "SupportAttacker",
+        description: "Places the robots to receive a pass from the attacker.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(SupportAttacker::new(ids))),
+    },
+    StrategyEntry {
+        name: "Receiver",
+        description: "Receives a pass along the given trajectory.",
+        single_robot: true,
+        parameters: &[
+            parameter(
+                "passer_id",
+                ParameterKind::Id,
+                None,
+                "The id of the passer.",
+            ),
+            parameter(
+                "start_x",
+                ParameterKind::Float,
+                None,
+                "The x of the start of the pass (m).",
+            ),
+            parameter(
+                "start_y",
+                ParameterKind::Float,
+                None,
+                "The y of the start of the pass (m).",
+            ),
+            parameter(
+                "end_x",
+                ParameterKind::Float,
+                None,
+                "The x of the end of the pass (m).",
+            ),
+            parameter(
+                "end_y",
+                ParameterKind::Float,
+                None,
+                "The y of the end of the pass (m).",
+            ),
+        ],
+        create: |ids, parameters| {
+            let trajectory = Line::new(
+                Point2::new(parameters.float("start_x")?, parameters.float("start_y")?),
+                Point2::new(parameters.float("end_x")?, parameters.float("end_y")?),
+            );
+            Ok(Box::new(Receiver::new(
+                ids[0],
+                parameters.id("passer_id")?,
+                trajectory,
+            )))
+        },
+    },
+    StrategyEntry {
+        name: "LateralAttack",
+        description: "Runs along the side of the field to receive a pass.",
+        single_robot: true,
+        parameters: &[parameter(
+            "passer_id",
+            ParameterKind::Id,
+            None,
+            "The id of the passer.",
+        )],
+        create: |ids, parameters| {
+            Ok(Box::new(LateralAttack::new(
+                ids[0],
+                parameters.id("passer_id")?,
+            )))
+        },
+    },
+    StrategyEntry {
+        name: "SetPiece",
+        description: "Executes a play of the default playbook.",
+        single_robot: false,
+        parameters: &[parameter(
+            "budget_margin",
+            ParameterKind::Float,
+            Some("1.0"),
+            "The time kept before the end of the free kick budget to take the kick (s).",
+        )],
+        create: |ids, parameters| {
+            Ok(Box::new(
+                SetPiece::new(ids).with_budget_margin(parameters.float("budget_margin")?),
+            ))
+        },
+    },
+    StrategyEntry {
+        name: "Halt",
+        description: "Stops the robots.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(Halt::new(ids))),
+    },
+    StrategyEntry {
+        name: "MoveAwayFromBall",
+        description: "Keeps the robots away from the ball.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(MoveAwayFromBall::new(ids))),
+    },
+    StrategyEntry {
+        name: "PrepareKickOff",
+        description: "Places the robots for a kick off.",
+        single_robot: false,
+        parameters: &[parameter(
+            "team",
+            ParameterKind::Choice(&["Blue", "Yellow"]),
+            None,
+            "The team taking the kick off.",
+        )],
+        create: |ids, parameters| {
+            let team = match parameters.choice("team")? {
+                "Blue" => TeamColor::Blue,
+                _ => TeamColor::Yellow,
+            };
+            Ok(Box::new(PrepareKickOff::new(ids, team)))
+        },
+    },
+    StrategyEntry {
+        name: "PrepareStart",
+        description: "Places the robots before the game starts.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(PrepareStart::new(ids))),
+    },
+    StrategyEntry {
+        name: "Aligned",
+        description: "Aligns the robots, for testing.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(Aligned::new(ids))),
+    },
+    StrategyEntry {
+        name: "TestVisionMoveTo",
+        description: "Moves the robots to test the vision, for testing.",
+        single_robot: false,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(TestVisionMoveTo::new(ids))),
+    },
+    StrategyEntry {
+        name: "Square",
+        description: "Moves the robot in a square, for testing.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(Square::new(ids[0]))),
+    },
+    StrategyEntry {
+        name: "GoLeft",
+        description: "Moves the robot to the left, for testing.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(GoLeft::new(ids[0]))),
+    },
+    StrategyEntry {
+        name: "GoRight",
+        description: "Moves the robot to the right, for testing.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(GoRight::new(ids[0]))),
+    },
+    StrategyEntry {
+        name: "Prembule",
+        description: "Checks that the robot moves, for testing.",
+        single_robot: true,
+        parameters: &[],
+        create: |ids, _| Ok(Box::new(Prembule::new(ids[0]))),
+    },
+];
+
+/// Finds a strategy of the registry from its name, ignoring the case.
+pub fn find_strategy(name: &str) -> Option<&'static StrategyEntry> {
+    STRATEGIES
+        .iter()
+        .find(|entry| entry.name.eq_ignore_ascii_case(name))
+}
+
+/// Creates a strategy from a request, like one sent by a tool or read from a config file.
+///
+/// # Arguments
+/// - `request`: The name of the strategy, ignoring the case, its robots and its parameters.
+///
+/// # Returns
+/// The strategy, or an error if the name is unknown or the robots or the parameters don't fit the strategy.
+///
+/// # Example
+/// ```
+/// use crabe_decision::strategy::registry::{create_strategy, parse_strategy_request};
+///
+/// let strategy = create_strategy(&parse_strategy_request("defensewall=1,2").unwrap()).unwrap();
+/// assert_eq!(strategy.name(), "DefenseWall");
+/// assert_eq!(strategy.get_ids(), vec![1, 2]);
+/// let request = parse_strategy_request("BotMarking=1;enemy_id=4;mode=passlane").unwrap();
+/// assert_eq!(create_strategy(&request).unwrap().name(), "BotMarking");
+/// assert!(parse_strategy_request("BotMarking=1").is_err());
+/// assert!(parse_strategy_request("BotMarking=1;enemy_id=4.5").is_err());
+/// assert!(parse_strategy_request("Attacker=1,2").is_err());
+/// assert!(parse_strategy_request("Unknown=1").is_err());
+/// ```
+pub fn create_strategy(request: &StrategyRequest) -> Result<Box<dyn Strategy>, String> {
+    match find_strategy(&request.name) {
+        Some(entry) => entry.create(request.ids.clone(), &request.parameters),
+        None => Err(format!(
+            "unknown strategy {}, expected one of: {}",
+            request.name,
+            STRATEGIES
+                .iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Parses a strategy request written on the command line as `Name=id,id;parameter=value`,
+/// for example `BotMarking=1;enemy_id=4;mode=PassLane`.
+/// The request is checked against the registry, so that a mistake is reported before starting.
+pub fn parse_strategy_request(s: &str) -> Result<StrategyRequest, String> {
+    let (name, rest) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected Name=id,id;parameter=value, got {}", s))?;
+    let mut fields = rest.split(';');
+    let ids = fields
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|id| {
+            id.trim()
+                .parse::<u8>()
+                .map_err(|e| format!("invalid robot id {}: {}", id, e))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    let parameters = fields
+        .map(|field| {
+            field
+                .split_once('=')
+                .map(|(name, value)| {
+                    (
+                        name.trim().to_string(),
+                        ParameterValue::Text(value.trim().to_string()),
+                    )
+                })
+                .ok_or_else(|| format!("expected parameter=value, got {}", field))
+        })
+        .collect::<Result<HashMap<String, ParameterValue>, String>>()?;
+    let request = StrategyRequest {
+        name: name.trim().to_string(),
+        ids,
+        parameters,
+    };
+    let strategy = create_strategy(&request)?;
+    Ok(StrategyRequest {
+        name: strategy.name().to_string(),
+        ..request
+    })
+}
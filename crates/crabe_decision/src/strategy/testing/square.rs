@@ -1,25 +1,83 @@
 use crate::action::move_to::MoveTo;
+use crate::action::timeout::Timeout;
 use crate::action::ActionWrapper;
 use crate::strategy::Strategy;
 use crate::message::MessageData;
 use crabe_framework::data::tool::ToolData;
 use crabe_framework::data::world::World;
+use log::warn;
 use nalgebra::Point2;
 use std::f64::consts::PI;
+use std::time::Duration;
+
+/// The time given to the robot to reach each corner of the square.
+const LEG_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// The Square struct represents a strategy that commands a robot to move in a square shape
 /// in a counter-clockwise. It is used for testing purposes.
-#[derive(Default)]
+/// Each corner must be reached in time, otherwise the square is started again.
+///
+/// # Example
+/// ```
+/// use crabe_decision::action::state::State;
+/// use crabe_decision::action::ActionWrapper;
+/// use crabe_decision::manager::manual::Manual;
+/// use crabe_decision::manager::Manager;
+/// use crabe_decision::strategy::testing::Square;
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::tool::ToolData;
+/// use crabe_framework::data::world::World;
+/// use std::time::Duration;
+///
+/// let world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// let mut tools = ToolData::default();
+/// let mut action_wrapper = ActionWrapper::default();
+/// let square = Square::new(0).with_timeout(Duration::from_millis(1));
+/// let mut manager = Manual::with_strategies(vec![Box::new(square)]);
+///
+/// manager.step(&world, &mut tools, &mut action_wrapper);
+/// action_wrapper.compute(&world, &mut tools);
+/// std::thread::sleep(Duration::from_millis(5));
+/// action_wrapper.compute(&world, &mut tools);
+/// action_wrapper.compute(&world, &mut tools);
+/// assert_eq!(action_wrapper.state(0), Some(State::Failed));
+///
+/// // The failure reaches the strategy, which starts the square again
+/// manager.step(&world, &mut tools, &mut action_wrapper);
+/// assert_eq!(action_wrapper.state(0), Some(State::Running));
+/// assert_eq!(action_wrapper.actions[&0].actions.len(), 4);
+/// ```
 pub struct Square {
     /// The id of the robot to move.
     id: u8,
     messages: Vec<MessageData>,
+    /// The time given to the robot to reach each corner.
+    timeout: Duration,
+    /// Whether the square must be (re)started at the next step.
+    restart: bool,
+}
+
+impl Default for Square {
+    fn default() -> Self {
+        Self::new(0)
+    }
 }
 
 impl Square {
     /// Creates a new Square instance with the desired robot id.
     pub fn new(id: u8) -> Self {
-        Self { id, messages: vec![]}
+        Self {
+            id,
+            messages: vec![],
+            timeout: LEG_TIMEOUT,
+            restart: true,
+        }
+    }
+
+    /// Sets the time given to the robot to reach each corner of the square.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
     }
 }
 
@@ -28,8 +86,8 @@ impl Strategy for Square {
         "Square"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData> {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
     fn get_ids(&self) -> Vec<u8> {
         vec![self.id]
@@ -37,12 +95,18 @@ impl Strategy for Square {
     fn put_ids(&mut self, ids: Vec<u8>) {
         if ids.len() == 1{
             self.id = ids[0];
+            self.restart = true;
         }
     }
+    fn on_action_failed(&mut self, id: u8) {
+        warn!("Robot {} didn't reach a corner of the square in time, starting again", id);
+        self.restart = true;
+    }
     /// Executes the Square strategy.
     ///
     /// This strategy commands the robot with the specified ID to move in a square shape in a
-    /// counter-clockwise direction.
+    /// counter-clockwise direction. The square is given once, and again when one of its corners
+    /// isn't reached in time.
     ///
     /// # Arguments
     ///
@@ -60,22 +124,25 @@ impl Strategy for Square {
         tools_data: &mut ToolData,
         action_wrapper: &mut ActionWrapper,
     ) -> bool {
-        action_wrapper.push(
-            self.id,
-            MoveTo::new(Point2::new(-1.0, 1.0), -PI / 4., 0.0, false, None, true, true),
-        );
-        action_wrapper.push(
-            self.id,
-            MoveTo::new(Point2::new(1.0, 1.0), -3.* PI / 4., 0.0, false, None, true, true),
-        );
-        action_wrapper.push(
-            self.id,
-            MoveTo::new(Point2::new(1.0, -1.0), 3.* PI / 4., 0.0, false, None, true, true),
-        );
-        action_wrapper.push(
-            self.id,
-            MoveTo::new(Point2::new(-1.0, -1.0), PI / 4., 0.0, false, None, true, true),
-        );
-        true
+        if !self.restart {
+            return false;
+        }
+        self.restart = false;
+        let corners = [
+            (Point2::new(-1.0, 1.0), -PI / 4.),
+            (Point2::new(1.0, 1.0), -3. * PI / 4.),
+            (Point2::new(1.0, -1.0), 3. * PI / 4.),
+            (Point2::new(-1.0, -1.0), PI / 4.),
+        ];
+        for (corner, orientation) in corners {
+            action_wrapper.push(
+                self.id,
+                Timeout::new(
+                    MoveTo::new(corner, orientation, 0.0, false, None, true, true),
+                    self.timeout,
+                ),
+            );
+        }
+        false
     }
 }
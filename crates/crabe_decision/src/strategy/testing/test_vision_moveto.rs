@@ -41,8 +41,8 @@ impl Strategy for TestVisionMoveTo {
         "TestVisionMoveTo"
     }
 
-    fn get_messages(&self) -> &Vec<MessageData>  {
-        &self.messages
+    fn take_messages(&mut self) -> Vec<MessageData> {
+        std::mem::take(&mut self.messages)
     }
 
     fn get_ids(&self) -> Vec<u8> {
@@ -7,5 +7,20 @@ pub use self::constants::*;
 pub mod navigation;
 pub use self::navigation::*;
 
+pub mod prediction;
+pub use self::prediction::*;
+
+pub mod passing;
+pub use self::passing::*;
+
+pub mod assignment;
+pub use self::assignment::*;
+
+pub mod threat;
+pub use self::threat::*;
+
+pub mod playbook;
+pub use self::playbook::*;
+
 pub mod bigbro_decisions;
 pub use self::bigbro_decisions::*;
\ No newline at end of file
@@ -0,0 +1,102 @@
+/// Finds the assignment of rows to columns minimizing the total cost (Hungarian algorithm).
+/// Rows are typically our robots and columns their possible tasks.
+/// When there are more rows than columns, some rows are left unassigned.
+///
+/// # Arguments
+/// - `costs`: The cost of assigning each row to each column, every row must have the same length.
+///
+/// # Returns
+/// The column assigned to each row, None if the row is unassigned.
+///
+/// # Example
+/// ```
+/// use crabe_decision::utils::assignment::optimal_assignment;
+///
+/// // The greedy choice (0 -> 0) would force 1 -> 1 for a total of 1 + 10
+/// let costs = vec![vec![1., 2.], vec![2., 10.]];
+/// assert_eq!(optimal_assignment(&costs), vec![Some(1), Some(0)]);
+///
+/// let costs = vec![vec![3.], vec![1.], vec![2.]];
+/// assert_eq!(optimal_assignment(&costs), vec![None, Some(0), None]);
+///
+/// assert_eq!(optimal_assignment(&vec![vec![1., 0.5, 2.]]), vec![Some(1)]);
+/// assert!(optimal_assignment(&vec![]).is_empty());
+/// ```
+pub fn optimal_assignment(costs: &[Vec<f64>]) -> Vec<Option<usize>> {
+    let rows = costs.len();
+    let columns = costs.first().map_or(0, |row| row.len());
+    if rows == 0 || columns == 0 {
+        return vec![None; rows];
+    }
+    // Square matrix padded with zero costs, indexed from 1 (0 is a virtual column)
+    let size = rows.max(columns);
+    let cost = |row: usize, column: usize| -> f64 {
+        if row < rows && column < columns {
+            costs[row][column]
+        } else {
+            0.
+        }
+    };
+
+    let mut row_potential = vec![0.; size + 1];
+    let mut column_potential = vec![0.; size + 1];
+    let mut column_row = vec![0usize; size + 1];
+    let mut way = vec![0usize; size + 1];
+    for row in 1..=size {
+        column_row[0] = row;
+        let mut current_column = 0;
+        let mut min_slack = vec![f64::INFINITY; size + 1];
+        let mut used = vec![false; size + 1];
+        loop {
+            used[current_column] = true;
+            let current_row = column_row[current_column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+            for column in 1..=size {
+                if used[column] {
+                    continue;
+                }
+                let slack = cost(current_row - 1, column - 1)
+                    - row_potential[current_row]
+                    - column_potential[column];
+                if slack < min_slack[column] {
+                    min_slack[column] = slack;
+                    way[column] = current_column;
+                }
+                if min_slack[column] < delta {
+                    delta = min_slack[column];
+                    next_column = column;
+                }
+            }
+            for column in 0..=size {
+                if used[column] {
+                    row_potential[column_row[column]] += delta;
+                    column_potential[column] -= delta;
+                } else {
+                    min_slack[column] -= delta;
+                }
+            }
+            current_column = next_column;
+            if column_row[current_column] == 0 {
+                break;
+            }
+        }
+        // Follow the augmenting path back to the virtual column
+        loop {
+            let previous_column = way[current_column];
+            column_row[current_column] = column_row[previous_column];
+            current_column = previous_column;
+            if current_column == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![None; rows];
+    for (column, &row) in column_row.iter().enumerate().skip(1) {
+        if row >= 1 && row <= rows && column <= columns {
+            assignment[row - 1] = Some(column - 1);
+        }
+    }
+    assignment
+}
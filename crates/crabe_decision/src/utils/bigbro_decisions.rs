@@ -1,8 +1,33 @@
-use crabe_framework::data::{tool::ToolData, world::{AllyInfo, Ball, Robot, TeamColor, World}};
+use crabe_framework::data::{tool::{ParameterValue, ToolData}, world::{AllyInfo, Ball, Robot, TeamColor, World}};
 
-use crate::{manager::bigbro::BigBro, strategy::{self, defensive::{DefenseWall, GoalKeeper}, formations::{Halt, MoveAwayFromBall, PrepareKickOff, PrepareStart}, offensive::Attacker}};
+use crate::{manager::{bigbro::BigBro, roles::Role}, strategy::{formations::PrepareStart, offensive::SetPiece, registry::{ParameterKind, ParameterSchema}}};
+use std::collections::HashMap;
 
-use super::{closest_bot_to_point, closest_bots_to_point, filter_robots_not_in_ids, get_enemy_keeper_id, KEEPER_ID};
+use log::error;
+
+use super::prediction::BallTrajectory;
+use super::threat::{enemy_threats, MARK_THREAT_THRESHOLD, RELEASE_THREAT_THRESHOLD};
+use super::{closest_bots_to_point, filter_robots_not_in_ids, get_enemy_keeper_id, KEEPER_ID};
+
+/// Time in seconds by which another robot has to reach the ball before the current attacker to replace it.
+const ATTACKER_SWITCH_TIME_MARGIN: f64 = 0.3;
+/// Maximum number of line robots taking part in our set pieces.
+const SET_PIECE_ROBOTS: usize = 3;
+
+/// The parameters of `BigBro`, set in the `[parameters.BigBro]` section of the play file.
+pub const BIGBRO_PARAMETERS: &[ParameterSchema] = &[ParameterSchema {
+    name: "attacker_switch_time_margin",
+    kind: ParameterKind::Float,
+    default: Some("0.3"),
+    description: "The time by which another robot has to reach the ball before the current attacker to replace it (s).",
+}];
+
+/// The roles which can be given to the line robots in the play file, by game state.
+pub const GAME_STATE_ROLES: &[(&str, &[&str])] = &[
+    ("run", &["DefenseWall", "Attacker", "ManMarking", "SupportAttacker"]),
+    ("kick_off", &["DefenseWall", "PrepareKickOff"]),
+    ("set_piece", &["SetPiece", "DefenseWall"]),
+];
 
 /// Put all bots to the Halt strategy.
 pub fn everyone_halt(bigbro: &mut BigBro, world: &World) {
@@ -10,12 +35,7 @@ pub fn everyone_halt(bigbro: &mut BigBro, world: &World) {
     for bot in world.allies_bot.values() {
         ids.push(bot.id);
     }
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("Halt") {
-        bigbro.move_bots_to_existing_strategy(ids, strategy_index);
-    }else{
-        let strategy = Box::new(Halt::new(vec![]));
-        bigbro.move_bots_to_new_strategy(ids, strategy);
-    }
+    bigbro.assign_strategy("Halt", ids, HashMap::new());
 }
 
 /// Put all bots to the Halt strategy.
@@ -24,12 +44,7 @@ pub fn everyone_stop(bigbro: &mut BigBro, world: &World) {
     for bot in world.allies_bot.values() {
         ids.push(bot.id);
     }
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("MoveAwayFromBall") {
-        bigbro.move_bots_to_existing_strategy(ids, strategy_index);
-    }else{
-        let strategy = Box::new(MoveAwayFromBall::new(vec![]));
-        bigbro.move_bots_to_new_strategy(ids, strategy);
-    }
+    bigbro.assign_strategy("MoveAwayFromBall", ids, HashMap::new());
 }
 
 /// Prepare start
@@ -38,11 +53,10 @@ pub fn prepare_start(bigbro: &mut BigBro, world: &World) {
     for bot in world.allies_bot.values() {
         ids.push(bot.id);
     }
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("PrepareStart") {
-        bigbro.move_bots_to_existing_strategy(ids, strategy_index);
-    }else{
-        let strategy = Box::new(PrepareStart::new(vec![]));
-        bigbro.move_bots_to_new_strategy(ids, strategy);
+    let formation = bigbro.play_file.formation("prepare_start").cloned();
+    match formation {
+        Some(formation) => bigbro.assign(Role::PrepareStart, ids, || Box::new(PrepareStart::with_formation(vec![], formation))),
+        None => bigbro.assign_strategy("PrepareStart", ids, HashMap::new()),
     }
 }
 
@@ -55,12 +69,7 @@ pub fn everyone_stop_except_keeper(bigbro: &mut BigBro, world: &World) {
         }
         ids.push(bot.id);
     }
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("MoveAwayFromBall") {
-        bigbro.move_bots_to_existing_strategy(ids, strategy_index);
-    }else{
-        let strategy = Box::new(MoveAwayFromBall::new(vec![]));
-        bigbro.move_bots_to_new_strategy(ids, strategy);
-    }
+    bigbro.assign_strategy("MoveAwayFromBall", ids, HashMap::new());
 }
 
 pub fn prepare_kick_off(bigbro: &mut BigBro, world: &World, team: TeamColor) {
@@ -72,43 +81,34 @@ pub fn prepare_kick_off(bigbro: &mut BigBro, world: &World, team: TeamColor) {
         ids.push(bot.id);
     }
     let ally_count = ids.len();
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("PrepareKickOff") {
-        bigbro.move_bots_to_existing_strategy(ids, strategy_index);
-    }else{
-        let strategy = Box::new(PrepareKickOff::new(vec![], team));
-        bigbro.move_bots_to_new_strategy(ids, strategy);
-    }
-    if ally_count > 1{
-        put_defense_wall(bigbro, world, &filter_robots_not_in_ids(world.allies_bot.values().collect(), &vec![KEEPER_ID]), ally_count -1);
+    bigbro.assign_strategy("PrepareKickOff", ids, HashMap::from([("team".to_string(), ParameterValue::Text(format!("{:?}", team)))]));
+    let wall_count = bigbro.play_file.role_count("kick_off", "DefenseWall", ally_count).unwrap_or(ally_count.saturating_sub(1));
+    if wall_count > 0{
+        put_defense_wall(bigbro, world, &filter_robots_not_in_ids(world.allies_bot.values().collect(), &vec![KEEPER_ID]), wall_count);
     }
     put_goal(bigbro);
 }
 
 pub fn penalty_state(bigbro: &mut BigBro, world: &World, team: TeamColor){
-    for bot in world.allies_bot.values(){
-        bigbro.remove_bot_from_strategies(bot.id);
-    }
+    bigbro.roles.clear();
     if team == world.team_color{
-        bigbro.strategies.push(Box::new(Attacker::new(KEEPER_ID)));
+        bigbro.assign_strategy("Attacker", vec![KEEPER_ID], HashMap::new());
         // if let Some(ball) = &world.ball{
         //     put_attacker(bigbro, world, &world.allies_bot.values().collect(), ball);
         // }
         
     }else{
-        bigbro.strategies.push(Box::new(GoalKeeper::new(KEEPER_ID, vec![])));
+        bigbro.assign_strategy("GoalKeeper", vec![KEEPER_ID], HashMap::new());
     }
     
 }
 
 /// Put the goal keeper to the GoalKeeper strategy.
 fn put_goal(bigbro: &mut BigBro) {
-    if let Some(current_strategy) = bigbro.get_bot_current_strategy(KEEPER_ID) {
-        if current_strategy.name() == "GoalKeeper" {
-            return;
-        }
+    if bigbro.roles.role_of(KEEPER_ID) == Some(Role::GoalKeeper) {
+        return;
     }
-    let strategy = Box::new(GoalKeeper::new(KEEPER_ID,vec![]));
-    bigbro.move_bot_to_new_strategy(KEEPER_ID, strategy);
+    bigbro.assign_strategy("GoalKeeper", vec![KEEPER_ID], HashMap::new());
 }
 
 /// Put the num_robots closest bots to the goal to the DefenseWall strategy.
@@ -123,54 +123,112 @@ fn put_defense_wall(bigbro: &mut BigBro, world: &World, robots: &Vec<&Robot<Ally
     for robot in allies_closest.iter().take(num_robots) {
         ids.push(robot.id);
     }
-    if let Some(strategy_index) = bigbro.get_index_strategy_with_name("DefenseWall") {
-        bigbro.strategies[strategy_index].put_ids(vec![]);
-        bigbro.move_bots_to_existing_strategy(ids.clone(), strategy_index);
-    } else{
-        let strategy = Box::new(DefenseWall::new(vec![]));
-        bigbro.move_bots_to_new_strategy(ids.clone(), strategy); // clone because move bots to new strategy don't take refs but values
-    }
+    bigbro.assign_strategy("DefenseWall", ids.clone(), HashMap::new());
     ids
 }
 
-/// Put the closest bot to the ball to the Attacker strategy. (if there is already an attacker and he's close to the ball, don't change)
+/// Put the bot reaching the ball first to the Attacker strategy. (if there is already an attacker close to the ball or almost as fast, don't change)
 fn put_attacker(bigbro: &mut BigBro, world: &World, bots: &Vec<&Robot<AllyInfo>>, ball: &Ball) -> u8 {
-    let closest_bot = match closest_bot_to_point(bots.iter().map(|bot| *bot).collect(), ball.position_2d()) {
-        Some(bot) => bot,
+    let trajectory = BallTrajectory::new(ball, &world.geometry);
+    let interceptions = trajectory.interceptions(bots.to_vec(), world.geometry.robot_radius);
+    let fastest_bot = match interceptions.first() {
+        Some(interception) => *interception,
         None => return 7,
     };
-    if let Some(attacker_strategy_index) = bigbro.get_index_strategy_with_name("Attacker") {
-        let current_attackers = bigbro.strategies[attacker_strategy_index].get_ids();
-        if let Some(current_attacker_id) = current_attackers.last() {
-            if *current_attacker_id == closest_bot.id  { // already the closest bot who's attacker
-                return closest_bot.id;
+    if let Some(current_attacker_id) = bigbro.roles.ids(Role::Attacker).last() {
+        if *current_attacker_id == fastest_bot.id  { // already the fastest bot to the ball who's attacker
+            return fastest_bot.id;
+        }
+        if let Some(current_attacker) = world.allies_bot.get(current_attacker_id) {
+            let current_attacker_dist_to_ball = current_attacker.distance(&ball.position_2d());
+            if current_attacker_dist_to_ball < 0.5 {
+                return *current_attacker_id;
             }
-            if let Some(current_attacker) = world.allies_bot.get(current_attacker_id) {
-                let current_attacker_dist_to_ball = current_attacker.distance(&ball.position_2d());
-                if current_attacker_dist_to_ball < 0.5 {
+            // keep the current attacker if it is almost as fast as the best one, to avoid switching every frame
+            if let Some(current_interception) = interceptions.iter().find(|interception| interception.id == *current_attacker_id) {
+                let switch_time_margin = bigbro.play_file.parameter("BigBro", "attacker_switch_time_margin", ATTACKER_SWITCH_TIME_MARGIN);
+                if current_interception.time - fastest_bot.time < switch_time_margin {
                     return *current_attacker_id;
                 }
             }
         }
-        bigbro.strategies[attacker_strategy_index].put_ids(vec![]);
-        bigbro.move_bot_to_existing_strategy(closest_bot.id, attacker_strategy_index);
-        return closest_bot.id;
-    } 
-    let strategy = Box::new(strategy::offensive::Attacker::new(closest_bot.id));
-    bigbro.move_bot_to_new_strategy(closest_bot.id, strategy);
-    return closest_bot.id;
+    }
+    bigbro.assign_strategy("Attacker", vec![fastest_bot.id], HashMap::new());
+    return fastest_bot.id;
 }   
 
-/// Run the strategy for the running state with 5 line robots.
+/// Put the bots to the SupportAttacker strategy. (bots receiving a pass stay in the Receiver strategy)
+fn put_support_attackers(bigbro: &mut BigBro, bots: &Vec<&Robot<AllyInfo>>) -> Vec<u8> {
+    let ids: Vec<u8> = bots.iter()
+        .map(|bot| bot.id)
+        .filter(|id| bigbro.roles.role_of(*id) != Some(Role::Receiver))
+        .collect();
+    if ids.is_empty() {
+        return ids;
+    }
+    // keep the bots already supporting first, so that they keep their targets
+    let ordered_ids = bigbro.roles.stable_order(Role::SupportAttacker, &ids);
+    bigbro.assign_strategy("SupportAttacker", ordered_ids, HashMap::new());
+    ids
+}
+
+/// Number of threatening enemies to mark. (the marked enemies are released under a lower threat, to avoid flickering)
+fn markers_count(bigbro: &BigBro, world: &World) -> usize {
+    let marking = bigbro.roles.contains(Role::Marker);
+    let threshold = if marking { RELEASE_THREAT_THRESHOLD } else { MARK_THREAT_THRESHOLD };
+    enemy_threats(world).iter().filter(|threat| threat.score >= threshold).count()
+}
+
+/// Put the bots to the ManMarking strategy.
+fn put_markers(bigbro: &mut BigBro, bots: &Vec<&Robot<AllyInfo>>) -> Vec<u8> {
+    let ids: Vec<u8> = bots.iter().map(|bot| bot.id).collect();
+    if ids.is_empty() {
+        return ids;
+    }
+    // keep the bots already marking first, so that the marked enemies are kept
+    let ordered_ids = bigbro.roles.stable_order(Role::Marker, &ids);
+    bigbro.assign_strategy("ManMarking", ordered_ids, HashMap::new());
+    ids
+}
+
+/// Number of line robots supporting the attacker, more when the ball is in the enemy half.
+fn support_attackers_count(line_robots: usize, ball: &Ball) -> usize {
+    let available = line_robots.saturating_sub(1);
+    if ball.position.x > 0. {
+        available.div_ceil(2)
+    } else {
+        available / 2
+    }
+}
+
+/// Run the strategy for the running state with the line robots.
+/// The robots are split between the roles of the play file if it describes the running state,
+/// otherwise the number of supports depends on the half of the ball and the free bots in our half mark the threatening enemies.
 fn run_state_line_robots(bigbro: &mut BigBro, allies: Vec<&Robot<AllyInfo>>, ball: &Ball, world: &World, _tools_data: &mut ToolData) {
     if allies.len() == 0{return;}
     if world.geometry.ally_penalty.is_inside(&ball.position_2d()){
-        let defense_wall_ids = put_defense_wall(bigbro, world, &allies, allies.len());
-    }else{
-        let defense_wall_ids = put_defense_wall(bigbro, world, &allies, allies.len() -1);
-        let offensive_line: Vec<&Robot<AllyInfo>> = allies.iter().filter(|bot| !defense_wall_ids.contains(&bot.id)).map(|bot| *bot).collect();
-        put_attacker(bigbro, world, &offensive_line, ball);
+        put_defense_wall(bigbro, world, &allies, allies.len());
+        return;
     }
+    let play_file = &bigbro.play_file;
+    let wall_count = play_file.role_count("run", "DefenseWall", allies.len())
+        .unwrap_or_else(|| allies.len() - 1 - support_attackers_count(allies.len(), ball));
+    let with_attacker = play_file.role_count("run", "Attacker", allies.len()) != Some(0);
+    let file_markers_count = play_file.role_count("run", "ManMarking", allies.len());
+    let defense_wall_ids = put_defense_wall(bigbro, world, &allies, wall_count);
+    let offensive_line: Vec<&Robot<AllyInfo>> = allies.iter().filter(|bot| !defense_wall_ids.contains(&bot.id)).map(|bot| *bot).collect();
+    let attacker_id = if with_attacker && !offensive_line.is_empty() { Some(put_attacker(bigbro, world, &offensive_line, ball)) } else { None };
+    let mut free_bots: Vec<&Robot<AllyInfo>> = offensive_line.iter().filter(|bot| Some(bot.id) != attacker_id).copied().collect();
+    // In our half, the most defensive free bots mark the threatening enemies
+    let markers_count = match file_markers_count {
+        Some(count) => count,
+        None if ball.position.x <= 0. => markers_count(bigbro, world),
+        None => 0,
+    }.min(free_bots.len());
+    free_bots.sort_by(|a, b| a.pose.position.x.total_cmp(&b.pose.position.x));
+    let supports = free_bots.split_off(markers_count);
+    put_markers(bigbro, &free_bots);
+    put_support_attackers(bigbro, &supports);
 }
 
 /// Run the strategy for the running state.
@@ -182,4 +240,44 @@ pub fn run_state(bigbro: &mut BigBro, world: &World, tools_data: &mut ToolData)
     };
     let allies = filter_robots_not_in_ids(world.allies_bot.values().collect(), &vec![KEEPER_ID]);
     run_state_line_robots(bigbro, allies, ball, world, tools_data);
-}
\ No newline at end of file
+}
+
+/// Put the bots closest to the ball to the SetPiece strategy. (bots already in the set piece are kept, so that the play goes on)
+fn put_set_piece(bigbro: &mut BigBro, bots: &Vec<&Robot<AllyInfo>>, ball: &Ball) -> Vec<u8> {
+    let count = bigbro.play_file.role_count("set_piece", "SetPiece", bots.len()).unwrap_or(SET_PIECE_ROBOTS.min(bots.len()));
+    let bot_ids: Vec<u8> = bots.iter().map(|bot| bot.id).collect();
+    let mut ids: Vec<u8> = bigbro.roles.ids(Role::SetPiece)
+        .into_iter()
+        .filter(|id| bot_ids.contains(id))
+        .take(count)
+        .collect();
+    for bot in closest_bots_to_point(bots.to_vec(), ball.position_2d()) {
+        if ids.len() >= count {
+            break;
+        }
+        if !ids.contains(&bot.id) {
+            ids.push(bot.id);
+        }
+    }
+    let play_file = &bigbro.play_file;
+    if let Err(e) = bigbro.roles.assign(Role::SetPiece, ids.clone(), || Box::new(SetPiece::from_play_file(vec![], play_file))) {
+        error!("failed to assign the role SetPiece: {}", e);
+    }
+    ids
+}
+
+/// Run our free kicks and corner kicks, the bots closest to the ball play the set piece and the others defend.
+pub fn set_piece_state(bigbro: &mut BigBro, world: &World) {
+    put_goal(bigbro);
+    let ball = match &world.ball {
+        Some(ball) => ball,
+        None => return,
+    };
+    let allies = filter_robots_not_in_ids(world.allies_bot.values().collect(), &vec![KEEPER_ID]);
+    if allies.is_empty() {
+        return;
+    }
+    let set_piece_ids = put_set_piece(bigbro, &allies, ball);
+    let defenders: Vec<&Robot<AllyInfo>> = allies.iter().filter(|bot| !set_piece_ids.contains(&bot.id)).copied().collect();
+    put_defense_wall(bigbro, world, &defenders, defenders.len());
+}
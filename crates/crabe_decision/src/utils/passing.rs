@@ -0,0 +1,229 @@
+use crabe_framework::data::tool::ToolData;
+use crabe_framework::data::world::{AllyInfo, Robot, World};
+use crabe_math::shape::Circle;
+use nalgebra::{Point2, Vector2};
+use std::f64::consts::TAU;
+
+use super::prediction::{
+    robot_time_to_point, BALL_ROLLING_DECELERATION, BALL_SLIDING_DECELERATION,
+    BALL_SLIDING_TO_ROLLING_RATIO,
+};
+use super::{get_open_shoot_window, KEEPER_ID};
+
+/// Speed given to the ball when passing (m/s).
+pub const PASS_SPEED: f64 = 4.;
+/// Distances from the receiver at which receiving points are sampled (m).
+const SAMPLE_RADIUSES: [f64; 2] = [0.5, 1.];
+/// Number of receiving points sampled on each circle around the receiver.
+const SAMPLES_PER_RADIUS: usize = 8;
+/// Distance between two points of the pass lane checked for interception (m).
+const LANE_STEP: f64 = 0.2;
+/// Time margin giving an interception probability of about 73% (s).
+const INTERCEPTION_TIME_SCALE: f64 = 0.2;
+/// Minimum pass length, shorter passes are not worth it (m).
+const MIN_PASS_DISTANCE: f64 = 1.;
+/// Part of the score given to a receiving point even without an open shot.
+const MIN_SHOT_FACTOR: f64 = 0.2;
+
+/// A possible pass, with the estimations used to score it.
+#[derive(Clone, Copy, Debug)]
+pub struct PassCandidate {
+    /// The id of the robot receiving the pass.
+    pub receiver_id: u8,
+    /// The point where the ball is received.
+    pub point: Point2<f64>,
+    /// The probability that an enemy intercepts the ball, between 0 and 1.
+    pub interception_probability: f64,
+    /// Time from the kick until the receiver controls the ball (s).
+    pub reception_time: f64,
+    /// The chance of scoring from the receiving point, between 0 and 1.
+    pub shot_chance: f64,
+    /// The global score of the pass, the higher the better.
+    pub score: f64,
+}
+
+/// Estimates the time needed by a kicked ball to travel a distance,
+/// sliding then rolling on the carpet.
+///
+/// # Arguments
+/// - `distance`: The distance to travel in meters.
+/// - `speed`: The speed of the ball after the kick in m/s.
+///
+/// # Returns
+/// The time in seconds, or None if the ball stops before.
+///
+/// # Example
+/// ```
+/// use crabe_decision::utils::passing::ball_travel_time;
+///
+/// assert_eq!(ball_travel_time(0., 4.), Some(0.));
+/// assert!(ball_travel_time(2., 4.).unwrap() > 0.5);
+/// assert_eq!(ball_travel_time(100., 4.), None);
+/// ```
+pub fn ball_travel_time(distance: f64, speed: f64) -> Option<f64> {
+    let rolling_speed = speed * BALL_SLIDING_TO_ROLLING_RATIO;
+    let sliding_distance =
+        (speed.powi(2) - rolling_speed.powi(2)) / (2. * BALL_SLIDING_DECELERATION);
+    if distance <= sliding_distance {
+        let delta = speed.powi(2) - 2. * BALL_SLIDING_DECELERATION * distance;
+        return Some((speed - delta.sqrt()) / BALL_SLIDING_DECELERATION);
+    }
+    let sliding_time = (speed - rolling_speed) / BALL_SLIDING_DECELERATION;
+    let delta =
+        rolling_speed.powi(2) - 2. * BALL_ROLLING_DECELERATION * (distance - sliding_distance);
+    if delta < 0. {
+        return None;
+    }
+    Some(sliding_time + (rolling_speed - delta.sqrt()) / BALL_ROLLING_DECELERATION)
+}
+
+/// Probability of winning a race to the ball given the time margin, 0.5 when both arrive together.
+fn race_probability(margin: f64) -> f64 {
+    1. / (1. + (-margin / INTERCEPTION_TIME_SCALE).exp())
+}
+
+/// Evaluates a pass to a receiving point.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+/// - `from`: The position of the ball when it is kicked.
+/// - `receiver`: The robot receiving the pass.
+/// - `point`: The point where the ball is received.
+///
+/// # Returns
+/// The evaluated `PassCandidate`, or None if the ball can't reach the point.
+pub fn evaluate_pass(
+    world: &World,
+    from: &Point2<f64>,
+    receiver: &Robot<AllyInfo>,
+    point: Point2<f64>,
+) -> Option<PassCandidate> {
+    let lane = point - from;
+    let length = lane.norm();
+    let ball_time = ball_travel_time(length, PASS_SPEED)?;
+    let reach = world.geometry.robot_radius + world.geometry.ball_radius;
+
+    // An enemy intercepts the pass if it reaches a point of the lane before the ball
+    let mut interception_probability: f64 = 0.;
+    let steps = (length / LANE_STEP).ceil() as usize;
+    for step in 1..=steps {
+        let distance = (step as f64 * LANE_STEP).min(length);
+        let lane_point = from + lane * (distance / length);
+        let Some(ball_time_at_point) = ball_travel_time(distance, PASS_SPEED) else {
+            continue;
+        };
+        for enemy in world.enemies_bot.values() {
+            let enemy_time = robot_time_to_point(enemy, &lane_point, reach);
+            interception_probability =
+                interception_probability.max(race_probability(ball_time_at_point - enemy_time));
+        }
+    }
+
+    // The receiver has to be on the point when the ball arrives
+    let receiver_time = robot_time_to_point(receiver, &point, 0.);
+    let reception_probability = race_probability(ball_time - receiver_time);
+    let reception_time = ball_time.max(receiver_time);
+
+    let goal_width = world.geometry.enemy_goal.width;
+    let open_width: f64 = get_open_shoot_window(&point, world)
+        .iter()
+        .map(|window| window.norm())
+        .sum();
+    let goal_distance = (world.geometry.enemy_goal.line.center() - point).norm();
+    let shot_chance = (open_width / goal_width).clamp(0., 1.) / (1. + goal_distance / 3.);
+
+    let score = (1. - interception_probability)
+        * reception_probability
+        * (MIN_SHOT_FACTOR + (1. - MIN_SHOT_FACTOR) * shot_chance)
+        / (1. + reception_time);
+    Some(PassCandidate {
+        receiver_id: receiver.id,
+        point,
+        interception_probability,
+        reception_time,
+        shot_chance,
+        score,
+    })
+}
+
+/// Samples receiving points around every possible receiver and evaluates the passes to them.
+/// The passer and the keeper can't receive, and the points must be on the field,
+/// outside the penalty areas and far enough from the ball.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+/// - `passer_id`: The id of the robot passing the ball.
+/// - `from`: The position of the ball when it is kicked.
+///
+/// # Returns
+/// The evaluated passes.
+pub fn pass_candidates(world: &World, passer_id: u8, from: &Point2<f64>) -> Vec<PassCandidate> {
+    let half_length = world.geometry.field.length / 2.;
+    let half_width = world.geometry.field.width / 2.;
+    let mut candidates = vec![];
+    for receiver in world
+        .allies_bot
+        .values()
+        .filter(|ally| ally.id != passer_id && ally.id != KEEPER_ID)
+    {
+        let mut points = vec![receiver.pose.position];
+        for radius in SAMPLE_RADIUSES {
+            for i in 0..SAMPLES_PER_RADIUS {
+                let angle = i as f64 * TAU / SAMPLES_PER_RADIUS as f64;
+                points
+                    .push(receiver.pose.position + Vector2::new(angle.cos(), angle.sin()) * radius);
+            }
+        }
+        for point in points {
+            if point.x.abs() > half_length
+                || point.y.abs() > half_width
+                || world.geometry.ally_penalty.is_inside(&point)
+                || world.geometry.enemy_penalty.is_inside(&point)
+                || (point - from).norm() < MIN_PASS_DISTANCE
+            {
+                continue;
+            }
+            if let Some(candidate) = evaluate_pass(world, from, receiver, point) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// Finds the best pass from a position.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+/// - `passer_id`: The id of the robot passing the ball.
+/// - `from`: The position of the ball when it is kicked.
+///
+/// # Returns
+/// The pass with the highest score, if any.
+pub fn best_pass(world: &World, passer_id: u8, from: &Point2<f64>) -> Option<PassCandidate> {
+    pass_candidates(world, passer_id, from)
+        .into_iter()
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+/// Draws the evaluated passes as a heat map, each receiving point being a circle growing with its score.
+///
+/// # Arguments
+/// - `tools_data`: The data sent to the viewer.
+/// - `candidates`: The evaluated passes.
+pub fn annotate_pass_heat_map(tools_data: &mut ToolData, candidates: &[PassCandidate]) {
+    let best_score = candidates
+        .iter()
+        .map(|candidate| candidate.score)
+        .fold(0., f64::max);
+    if best_score <= 0. {
+        return;
+    }
+    for (i, candidate) in candidates.iter().enumerate() {
+        let radius = 0.02 + 0.08 * candidate.score / best_score;
+        tools_data.annotations.add_circle(
+            format!("pass_heat_map_{}", i),
+            Circle::new(candidate.point, radius),
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crabe_framework::data::world::World;
+use crabe_math::shape::Line;
+use nalgebra::Point2;
+use serde::Deserialize;
+
+use super::get_open_shoot_window;
+
+/// Distance from the ball under which the enemies standing in front of it form a wall (m).
+const WALL_DISTANCE: f64 = 1.2;
+/// Distance from the kick line under which an enemy blocks the kick (m).
+const WALL_WIDTH: f64 = 0.3;
+/// Distance to the closest enemy over which a receiving point is considered fully open (m).
+const OPEN_DISTANCE: f64 = 1.5;
+/// Distance kept from the field lines by the play positions (m).
+const FIELD_MARGIN: f64 = 0.2;
+/// Distance kept from the enemy penalty area by the play positions (m).
+const PENALTY_MARGIN: f64 = 0.2;
+
+/// The kinds of set-piece plays, used to evaluate them against the enemy layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum PlayKind {
+    /// The taker shoots directly on the enemy goal.
+    DirectShot,
+    /// The taker passes on the ground to a close receiver.
+    ShortPass,
+    /// The taker chips the ball over the enemy wall.
+    ChipOverWall,
+    /// A robot runs to draw the markers while the taker passes to another one.
+    DecoyRun,
+}
+
+/// What a robot does from a step of a play.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum PlayAction {
+    /// Move to a position of the play frame.
+    MoveTo(Point2<f64>),
+    /// Shoot the ball in the open window of the enemy goal.
+    Shoot,
+    /// Pass the ball on the ground to the robot playing the given role.
+    Pass(usize),
+    /// Chip the ball to the robot playing the given role.
+    Chip(usize),
+    /// Receive the ball, staying on its trajectory.
+    Receive,
+}
+
+/// A timed action of a role in a play.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PlayStep {
+    /// The index of the role doing the action.
+    pub role: usize,
+    /// The time from the start of the play when the action starts (s).
+    pub start: f64,
+    /// The action to do.
+    pub action: PlayAction,
+}
+
+/// A set-piece play, described as the positions of its roles and the timed actions they do.
+///
+/// The positions are expressed in the play frame: the origin is the ball,
+/// the x axis points toward the enemy goal line and the y axis toward the middle of the field,
+/// so that a play works from both sides of the field.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Play {
+    /// The name of the play.
+    pub name: String,
+    /// The kind of the play.
+    pub kind: PlayKind,
+    /// The range of the ball x coordinate where the play can be used, in the world frame.
+    #[serde(default = "Play::whole_field")]
+    pub ball_x_range: (f64, f64),
+    /// The position of each role while setting up the play, the first role takes the kick.
+    pub positions: Vec<Point2<f64>>,
+    /// The actions of the roles, each one lasting until the next step of the same role.
+    pub steps: Vec<PlayStep>,
+}
+
+impl Play {
+    /// The range of the ball x coordinate covering the whole field.
+    fn whole_field() -> (f64, f64) {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    /// Returns the number of robots needed by the play.
+    pub fn roles_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the time from the start of the play until its last action starts (s).
+    pub fn duration(&self) -> f64 {
+        self.steps.iter().map(|step| step.start).fold(0., f64::max)
+    }
+
+    /// Returns the action of a role at a given time of the play, None before its first step.
+    ///
+    /// # Arguments
+    /// - `role`: The index of the role.
+    /// - `elapsed`: The time since the start of the play (s).
+    ///
+    /// # Example
+    /// ```
+    /// use crabe_decision::utils::playbook::{Play, PlayAction, PlayKind, PlayStep};
+    /// use nalgebra::Point2;
+    ///
+    /// let play = Play {
+    ///     name: "ShortPass".to_string(),
+    ///     kind: PlayKind::ShortPass,
+    ///     ball_x_range: (-4.5, 4.5),
+    ///     positions: vec![Point2::new(-0.5, 0.), Point2::new(1., 1.)],
+    ///     steps: vec![
+    ///         PlayStep { role: 1, start: 0., action: PlayAction::MoveTo(Point2::new(1., 1.)) },
+    ///         PlayStep { role: 0, start: 0.5, action: PlayAction::Pass(1) },
+    ///         PlayStep { role: 1, start: 0.5, action: PlayAction::Receive },
+    ///     ],
+    /// };
+    /// assert_eq!(play.duration(), 0.5);
+    /// assert_eq!(play.action_at(0, 0.2), None);
+    /// assert_eq!(play.action_at(0, 0.7), Some(PlayAction::Pass(1)));
+    /// assert_eq!(play.action_at(1, 0.2), Some(PlayAction::MoveTo(Point2::new(1., 1.))));
+    /// assert_eq!(play.action_at(1, 0.5), Some(PlayAction::Receive));
+    /// ```
+    pub fn action_at(&self, role: usize, elapsed: f64) -> Option<PlayAction> {
+        self.steps
+            .iter()
+            .filter(|step| step.role == role && step.start <= elapsed)
+            .max_by(|a, b| a.start.total_cmp(&b.start))
+            .map(|step| step.action)
+    }
+
+    /// Returns the role receiving the kick of the taker, if any.
+    pub fn receiver_role(&self) -> Option<usize> {
+        self.steps.iter().find_map(|step| match step.action {
+            PlayAction::Pass(role) | PlayAction::Chip(role) => Some(role),
+            _ => None,
+        })
+    }
+
+    /// Returns the last position a role moves to, in the play frame.
+    pub fn final_position(&self, role: usize) -> Option<Point2<f64>> {
+        self.steps
+            .iter()
+            .filter(|step| step.role == role)
+            .filter_map(|step| match step.action {
+                PlayAction::MoveTo(position) => Some((step.start, position)),
+                _ => None,
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, position)| position)
+            .or_else(|| self.positions.get(role).copied())
+    }
+
+    /// Scores the play against the current enemy layout, None if it can't be used.
+    ///
+    /// # Arguments
+    /// - `world`: The current state of the game world.
+    /// - `ball_position`: The position of the ball.
+    ///
+    /// # Returns
+    /// The score between 0 and 1, the higher the more likely the play succeeds.
+    pub fn score(&self, world: &World, ball_position: &Point2<f64>) -> Option<f64> {
+        let (min_x, max_x) = self.ball_x_range;
+        if ball_position.x < min_x || ball_position.x > max_x {
+            return None;
+        }
+        let receiver_openness = || {
+            self.receiver_role()
+                .and_then(|role| self.final_position(role))
+                .map_or(0., |position| {
+                    openness(world, &play_frame_to_world(world, ball_position, &position))
+                })
+        };
+        let score = match self.kind {
+            PlayKind::DirectShot => open_goal_ratio(world, ball_position),
+            PlayKind::ShortPass => 0.85 * receiver_openness(),
+            PlayKind::ChipOverWall => {
+                let wall_factor = if wall_size(world, ball_position) > 0 {
+                    1.
+                } else {
+                    0.4
+                };
+                wall_factor * receiver_openness()
+            }
+            PlayKind::DecoyRun => 0.75 * receiver_openness(),
+        };
+        Some(score)
+    }
+}
+
+/// The plays available for our set pieces.
+#[derive(Clone, Debug)]
+pub struct Playbook {
+    /// The plays, in no particular order.
+    pub plays: Vec<Play>,
+}
+
+impl Playbook {
+    /// Selects the play with the best score for the current ball location and enemy layout.
+    ///
+    /// # Arguments
+    /// - `world`: The current state of the game world.
+    /// - `ball_position`: The position of the ball.
+    /// - `robots_count`: The number of robots available for the play.
+    ///
+    /// # Returns
+    /// The best play, None if no play can be used.
+    pub fn select(
+        &self,
+        world: &World,
+        ball_position: &Point2<f64>,
+        robots_count: usize,
+    ) -> Option<&Play> {
+        self.plays
+            .iter()
+            .filter(|play| play.roles_count() > 0 && play.roles_count() <= robots_count)
+            .filter_map(|play| play.score(world, ball_position).map(|score| (play, score)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(play, _)| play)
+    }
+}
+
+impl Default for Playbook {
+    fn default() -> Self {
+        let step = |role, start, action| PlayStep {
+            role,
+            start,
+            action,
+        };
+        let taker = Point2::new(-0.5, 0.);
+        Self {
+            plays: vec![
+                Play {
+                    name: "DirectShot".to_string(),
+                    kind: PlayKind::DirectShot,
+                    ball_x_range: (0., f64::INFINITY),
+                    positions: vec![taker, Point2::new(1.2, 1.)],
+                    steps: vec![
+                        step(0, 0.2, PlayAction::Shoot),
+                        step(1, 0., PlayAction::MoveTo(Point2::new(1.5, 1.))),
+                    ],
+                },
+                Play {
+                    name: "ShortPass".to_string(),
+                    kind: PlayKind::ShortPass,
+                    ball_x_range: (f64::NEG_INFINITY, f64::INFINITY),
+                    positions: vec![taker, Point2::new(0.5, 1.)],
+                    steps: vec![
+                        step(1, 0., PlayAction::MoveTo(Point2::new(1., 1.2))),
+                        step(0, 0.5, PlayAction::Pass(1)),
+                        step(1, 0.5, PlayAction::Receive),
+                    ],
+                },
+                Play {
+                    name: "ChipOverWall".to_string(),
+                    kind: PlayKind::ChipOverWall,
+                    ball_x_range: (-1., f64::INFINITY),
+                    positions: vec![taker, Point2::new(1.5, 0.5)],
+                    steps: vec![
+                        step(1, 0., PlayAction::MoveTo(Point2::new(2., 0.6))),
+                        step(0, 0.8, PlayAction::Chip(1)),
+                        step(1, 0.8, PlayAction::Receive),
+                    ],
+                },
+                Play {
+                    name: "DecoyRun".to_string(),
+                    kind: PlayKind::DecoyRun,
+                    ball_x_range: (-2., f64::INFINITY),
+                    positions: vec![taker, Point2::new(0.8, 0.5), Point2::new(0.5, 2.)],
+                    steps: vec![
+                        step(1, 0., PlayAction::MoveTo(Point2::new(0.8, 0.5))),
+                        step(1, 0.6, PlayAction::MoveTo(Point2::new(2.5, 0.3))),
+                        step(2, 0., PlayAction::MoveTo(Point2::new(0.5, 2.))),
+                        step(2, 0.8, PlayAction::MoveTo(Point2::new(1.5, 1.8))),
+                        step(0, 1.4, PlayAction::Pass(2)),
+                        step(2, 1.4, PlayAction::Receive),
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+/// Converts a position of the play frame to the world frame, keeping it on the field
+/// and out of the enemy penalty area.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+/// - `ball_position`: The position of the ball, origin of the play frame.
+/// - `position`: The position in the play frame.
+///
+/// # Returns
+/// The position in the world frame.
+pub fn play_frame_to_world(
+    world: &World,
+    ball_position: &Point2<f64>,
+    position: &Point2<f64>,
+) -> Point2<f64> {
+    let side = if ball_position.y > 0. { -1. } else { 1. };
+    let half_length = world.geometry.field.length / 2. - FIELD_MARGIN;
+    let half_width = world.geometry.field.width / 2. - FIELD_MARGIN;
+    let mut target = Point2::new(
+        (ball_position.x + position.x).clamp(-half_length, half_length),
+        (ball_position.y + side * position.y).clamp(-half_width, half_width),
+    );
+    // Step back out of the enemy penalty area
+    let enemy_penalty = world
+        .geometry
+        .enemy_penalty
+        .enlarged_penalty(PENALTY_MARGIN);
+    if enemy_penalty.is_inside(&target) {
+        target.x = enemy_penalty.front_line.start.x;
+    }
+    target
+}
+
+/// Returns the ratio of the enemy goal left open by the enemies for a shot from a position, between 0 and 1.
+pub fn open_goal_ratio(world: &World, from: &Point2<f64>) -> f64 {
+    let open_length: f64 = get_open_shoot_window(from, world)
+        .iter()
+        .map(|window| window.norm())
+        .sum();
+    (open_length / world.geometry.enemy_goal.width).clamp(0., 1.)
+}
+
+/// Returns the number of enemies standing close in front of the ball, between the ball and the enemy goal.
+pub fn wall_size(world: &World, ball_position: &Point2<f64>) -> usize {
+    let kick_line = Line::new(*ball_position, world.geometry.enemy_goal.line.center());
+    world
+        .enemies_bot
+        .values()
+        .filter(|enemy| {
+            let position = enemy.pose.position;
+            (position - ball_position).norm() < WALL_DISTANCE
+                && (kick_line.closest_point_on_segment(&position) - position).norm() < WALL_WIDTH
+        })
+        .count()
+}
+
+/// Returns how far a position is from the enemies, between 0 (an enemy is on it) and 1 (fully open).
+pub fn openness(world: &World, position: &Point2<f64>) -> f64 {
+    let closest_enemy_distance = world
+        .enemies_bot
+        .values()
+        .map(|enemy| (enemy.pose.position - position).norm())
+        .fold(OPEN_DISTANCE, f64::min);
+    closest_enemy_distance / OPEN_DISTANCE
+}
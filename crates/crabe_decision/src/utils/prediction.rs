@@ -0,0 +1,282 @@
+use crabe_framework::data::geometry::Geometry;
+use crabe_framework::data::world::{Ball, Robot};
+use nalgebra::{Point2, Point3, Vector2, Vector3};
+
+/// Deceleration of the ball while it slides on the carpet, right after a straight kick (m/s²).
+pub const BALL_SLIDING_DECELERATION: f64 = 3.6;
+/// Deceleration of the ball once it rolls on the carpet (m/s²).
+pub const BALL_ROLLING_DECELERATION: f64 = 0.4;
+/// Ratio of the kick speed at which the ball stops sliding and starts rolling.
+pub const BALL_SLIDING_TO_ROLLING_RATIO: f64 = 5. / 7.;
+/// Time after a touch during which an observed ball is considered to be still sliding (s).
+const BALL_SLIDING_TIME_AFTER_TOUCH: f64 = 0.3;
+/// Gravity applied to a chipped ball (m/s²).
+pub const GRAVITY: f64 = 9.81;
+/// Part of the vertical speed kept by a chipped ball when it bounces on the carpet.
+const CHIP_VERTICAL_DAMPING: f64 = 0.5;
+/// Part of the horizontal speed kept by a chipped ball when it bounces on the carpet.
+const CHIP_HORIZONTAL_DAMPING: f64 = 0.7;
+/// Vertical speed under which a chipped ball stops bouncing (m/s).
+const CHIP_MIN_BOUNCE_SPEED: f64 = 0.3;
+/// Part of the speed kept by the ball when it bounces on the field walls.
+const WALL_DAMPING: f64 = 0.5;
+/// Height under which a robot can reach the ball (m).
+pub const ROBOT_REACH_HEIGHT: f64 = 0.15;
+/// Maximum speed used to estimate the time needed by a robot to reach a point (m/s).
+pub const ROBOT_MAX_SPEED: f64 = 3.;
+/// Maximum acceleration used to estimate the time needed by a robot to reach a point (m/s²).
+pub const ROBOT_MAX_ACCELERATION: f64 = 3.;
+/// Duration of the predicted trajectory (s).
+const PREDICTION_HORIZON: f64 = 5.;
+/// Time step of the predicted trajectory (s).
+const PREDICTION_STEP: f64 = 0.02;
+
+/// The state of the ball at a given time of its predicted trajectory.
+#[derive(Clone, Copy, Debug)]
+pub struct BallSample {
+    /// Time from now in seconds.
+    pub time: f64,
+    /// Position of the ball in meters.
+    pub position: Point3<f64>,
+    /// Velocity of the ball in meters per second.
+    pub velocity: Vector3<f64>,
+}
+
+impl BallSample {
+    /// Returns the position of the ball as a 2D point.
+    pub fn position_2d(&self) -> Point2<f64> {
+        self.position.xy()
+    }
+
+    /// Returns true if a robot can touch the ball, i.e. the ball is not flying over the robots.
+    pub fn reachable(&self) -> bool {
+        self.position.z <= ROBOT_REACH_HEIGHT
+    }
+}
+
+/// The earliest time and point at which a robot can reach the ball.
+#[derive(Clone, Copy, Debug)]
+pub struct Interception {
+    /// The id of the robot.
+    pub id: u8,
+    /// Time from now in seconds.
+    pub time: f64,
+    /// Position of the ball when the robot reaches it.
+    pub point: Point2<f64>,
+}
+
+/// The `BallTrajectory` struct contains the predicted trajectory of the ball over the next seconds.
+/// The ball slides then rolls on the carpet, flies under gravity when chipped and bounces on the field walls.
+/// It stops when it enters a goal.
+#[derive(Clone, Debug)]
+pub struct BallTrajectory {
+    samples: Vec<BallSample>,
+}
+
+impl BallTrajectory {
+    /// Predicts the trajectory of the ball.
+    ///
+    /// # Arguments
+    ///
+    /// * `ball`: The current state of the ball.
+    /// * `geometry`: The geometry of the field, used for the walls and the goals.
+    pub fn new(ball: &Ball, geometry: &Geometry) -> Self {
+        let just_kicked = ball.last_touch.as_ref().is_some_and(|touch| {
+            (ball.timestamp - touch.timestamp).num_milliseconds() as f64 / 1000.
+                < BALL_SLIDING_TIME_AFTER_TOUCH
+        });
+        let mut position = ball.position;
+        position.z = position.z.max(0.);
+        let mut velocity = ball.velocity;
+        let mut rolling_speed = if just_kicked {
+            velocity.xy().norm() * BALL_SLIDING_TO_ROLLING_RATIO
+        } else {
+            f64::INFINITY
+        };
+
+        let wall_x = geometry.field.length / 2. + geometry.boundary_width;
+        let wall_y = geometry.field.width / 2. + geometry.boundary_width;
+        let goal_x = geometry.field.length / 2.;
+        let steps = (PREDICTION_HORIZON / PREDICTION_STEP) as usize;
+        let mut samples = Vec::with_capacity(steps + 1);
+        samples.push(BallSample {
+            time: 0.,
+            position,
+            velocity,
+        });
+        for step in 1..=steps {
+            let dt = PREDICTION_STEP;
+            let flying = position.z > 0. || velocity.z > 0.;
+            if flying {
+                velocity.z -= GRAVITY * dt;
+                position += velocity * dt;
+                if position.z <= 0. {
+                    position.z = 0.;
+                    velocity.z = -velocity.z * CHIP_VERTICAL_DAMPING;
+                    velocity.x *= CHIP_HORIZONTAL_DAMPING;
+                    velocity.y *= CHIP_HORIZONTAL_DAMPING;
+                    if velocity.z < CHIP_MIN_BOUNCE_SPEED {
+                        velocity.z = 0.;
+                        rolling_speed = velocity.xy().norm() * BALL_SLIDING_TO_ROLLING_RATIO;
+                    }
+                }
+            } else {
+                let speed = velocity.xy().norm();
+                let deceleration = if speed > rolling_speed {
+                    BALL_SLIDING_DECELERATION
+                } else {
+                    BALL_ROLLING_DECELERATION
+                };
+                let new_speed = (speed - deceleration * dt).max(0.);
+                if speed > 0. {
+                    let scale = new_speed / speed;
+                    position += (velocity + velocity * scale) / 2. * dt;
+                    velocity.x *= scale;
+                    velocity.y *= scale;
+                }
+            }
+
+            // Inside the goal, the ball is stopped by the net
+            let half_goal_width = if position.x > 0. {
+                geometry.enemy_goal.width / 2.
+            } else {
+                geometry.ally_goal.width / 2.
+            };
+            if position.x.abs() > goal_x && position.y.abs() < half_goal_width {
+                velocity = Vector3::zeros();
+            } else {
+                if position.x.abs() > wall_x {
+                    position.x = position.x.signum() * wall_x;
+                    velocity.x = -velocity.x;
+                    velocity *= WALL_DAMPING;
+                }
+                if position.y.abs() > wall_y {
+                    position.y = position.y.signum() * wall_y;
+                    velocity.y = -velocity.y;
+                    velocity *= WALL_DAMPING;
+                }
+            }
+
+            samples.push(BallSample {
+                time: step as f64 * dt,
+                position,
+                velocity,
+            });
+            if velocity.norm() == 0. && position.z == 0. {
+                break;
+            }
+        }
+        Self { samples }
+    }
+
+    /// Returns the predicted samples, from now to the end of the prediction.
+    pub fn samples(&self) -> &Vec<BallSample> {
+        &self.samples
+    }
+
+    /// Returns the predicted state of the ball after the given time.
+    /// Past the end of the prediction, the last predicted state is returned.
+    pub fn at(&self, time: f64) -> BallSample {
+        let index = (time.max(0.) / PREDICTION_STEP).round() as usize;
+        let mut sample = self.samples[index.min(self.samples.len() - 1)];
+        sample.time = time;
+        sample
+    }
+
+    /// Returns the position where the ball stops, or its position at the end of the prediction.
+    pub fn rest_position(&self) -> Point2<f64> {
+        self.samples[self.samples.len() - 1].position_2d()
+    }
+
+    /// Computes the earliest time and point at which a robot can reach the ball.
+    /// If the robot can't catch the ball during the prediction, it has to go to the rest position.
+    ///
+    /// # Arguments
+    ///
+    /// * `robot`: The robot going to the ball.
+    /// * `reach`: The distance from the center of the robot at which it touches the ball.
+    pub fn interception<T>(&self, robot: &Robot<T>, reach: f64) -> Interception {
+        for sample in self.samples.iter().filter(|sample| sample.reachable()) {
+            let time = robot_time_to_point(robot, &sample.position_2d(), reach);
+            if time <= sample.time {
+                return Interception {
+                    id: robot.id,
+                    time: sample.time,
+                    point: sample.position_2d(),
+                };
+            }
+        }
+        let point = self.rest_position();
+        let last = self.samples[self.samples.len() - 1].time;
+        Interception {
+            id: robot.id,
+            time: robot_time_to_point(robot, &point, reach).max(last),
+            point,
+        }
+    }
+
+    /// Computes the interceptions of multiple robots, sorted from the earliest to the latest.
+    ///
+    /// # Arguments
+    ///
+    /// * `robots`: The robots going to the ball.
+    /// * `reach`: The distance from the center of the robots at which they touch the ball.
+    pub fn interceptions<T>(&self, robots: Vec<&Robot<T>>, reach: f64) -> Vec<Interception> {
+        let mut interceptions: Vec<Interception> = robots
+            .iter()
+            .map(|robot| self.interception(*robot, reach))
+            .collect();
+        interceptions.sort_by(|a, b| a.time.total_cmp(&b.time));
+        interceptions
+    }
+}
+
+/// Estimates the time needed by a robot to reach a point, accelerating from its current
+/// velocity up to its maximum speed.
+///
+/// # Arguments
+///
+/// * `robot`: The robot.
+/// * `point`: The point to reach.
+/// * `reach`: The distance to the point at which the robot is considered to have reached it.
+///
+/// # Returns
+///
+/// The estimated time in seconds.
+///
+/// # Example
+/// ```
+/// use crabe_decision::utils::prediction::robot_time_to_point;
+/// use crabe_framework::data::world::{AllyInfo, Robot};
+/// use nalgebra::Point2;
+///
+/// let robot = Robot::<AllyInfo>::default();
+/// assert_eq!(robot_time_to_point(&robot, &Point2::new(0.1, 0.), 0.2), 0.);
+/// // 1s to accelerate to 3m/s over 1.5m, then 0.5s at full speed
+/// assert!((robot_time_to_point(&robot, &Point2::new(3., 0.), 0.) - 1.5).abs() < 1e-9);
+/// ```
+pub fn robot_time_to_point<T>(robot: &Robot<T>, point: &Point2<f64>, reach: f64) -> f64 {
+    let to_point: Vector2<f64> = point - robot.pose.position;
+    let mut distance = to_point.norm() - reach;
+    if distance <= 0. {
+        return 0.;
+    }
+    let mut initial_speed = robot.velocity.linear.dot(&(to_point / to_point.norm()));
+    let mut time = 0.;
+    if initial_speed < 0. {
+        // The robot has to brake before going towards the point
+        time += -initial_speed / ROBOT_MAX_ACCELERATION;
+        distance += initial_speed.powi(2) / (2. * ROBOT_MAX_ACCELERATION);
+        initial_speed = 0.;
+    }
+    let initial_speed = initial_speed.min(ROBOT_MAX_SPEED);
+    let acceleration_time = (ROBOT_MAX_SPEED - initial_speed) / ROBOT_MAX_ACCELERATION;
+    let acceleration_distance = (initial_speed + ROBOT_MAX_SPEED) / 2. * acceleration_time;
+    if distance <= acceleration_distance {
+        time + (-initial_speed
+            + (initial_speed.powi(2) + 2. * ROBOT_MAX_ACCELERATION * distance).sqrt())
+            / ROBOT_MAX_ACCELERATION
+    } else {
+        time + acceleration_time + (distance - acceleration_distance) / ROBOT_MAX_SPEED
+    }
+}
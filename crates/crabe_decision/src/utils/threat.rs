@@ -0,0 +1,90 @@
+use crabe_framework::data::world::{EnemyInfo, Robot, World};
+use crabe_math::shape::Line;
+use crabe_math::vectors;
+use std::f64::consts::PI;
+
+use super::{closest_bot_to_point, get_enemy_keeper_id};
+
+/// Threat over which an enemy starts being marked.
+pub const MARK_THREAT_THRESHOLD: f64 = 0.45;
+/// Threat under which a marked enemy is released, lower than `MARK_THREAT_THRESHOLD` to avoid flickering.
+pub const RELEASE_THREAT_THRESHOLD: f64 = 0.35;
+/// Distance from the ball under which an enemy is considered to carry the ball (m).
+const BALL_CARRIER_DISTANCE: f64 = 0.3;
+/// Distance from the pass lane over which an ally doesn't block the pass (m).
+const OPEN_LANE_DISTANCE: f64 = 1.;
+
+/// Weight of the proximity to our goal in the threat of an enemy.
+const GOAL_PROXIMITY_WEIGHT: f64 = 0.4;
+/// Weight of the pass lane openness from the ball in the threat of an enemy.
+const PASS_LANE_WEIGHT: f64 = 0.3;
+/// Weight of the shot angle on our goal in the threat of an enemy.
+const SHOT_ANGLE_WEIGHT: f64 = 0.3;
+
+/// The threat of an enemy robot not carrying the ball.
+#[derive(Clone, Copy, Debug)]
+pub struct EnemyThreat {
+    /// The id of the enemy.
+    pub id: u8,
+    /// The threat between 0 and 1, the higher the more dangerous.
+    pub score: f64,
+}
+
+/// Computes the threat of an enemy, from its proximity to our goal,
+/// the openness of the pass lane from the ball and its shot angle on our goal.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+/// - `enemy`: The enemy robot.
+///
+/// # Returns
+/// The threat between 0 and 1.
+pub fn enemy_threat(world: &World, enemy: &Robot<EnemyInfo>) -> f64 {
+    let goal = &world.geometry.ally_goal.line;
+    let position = enemy.pose.position;
+    let goal_distance = (goal.center() - position).norm();
+    let goal_proximity = 1. - (goal_distance / world.geometry.field.length).clamp(0., 1.);
+
+    let pass_lane = match &world.ball {
+        Some(ball) => {
+            let lane = Line::new(ball.position_2d(), position);
+            let lane_distance = world.allies_bot.values()
+                .map(|ally| (lane.closest_point_on_segment(&ally.pose.position) - ally.pose.position).norm())
+                .fold(OPEN_LANE_DISTANCE, f64::min);
+            lane_distance / OPEN_LANE_DISTANCE
+        }
+        None => 0.,
+    };
+
+    let post_angle = |post| vectors::angle_to_point(position, post);
+    let mut shot_angle = (post_angle(goal.start) - post_angle(goal.end)).abs();
+    if shot_angle > PI {
+        shot_angle = 2. * PI - shot_angle;
+    }
+    let shot = (shot_angle / (PI / 2.)).clamp(0., 1.);
+
+    GOAL_PROXIMITY_WEIGHT * goal_proximity + PASS_LANE_WEIGHT * pass_lane + SHOT_ANGLE_WEIGHT * shot
+}
+
+/// Ranks the enemies that can receive the ball by threat, from the most to the least dangerous.
+/// The enemy keeper and the enemy carrying the ball are not ranked, the attacker and the wall deal with them.
+///
+/// # Arguments
+/// - `world`: The current state of the game world.
+///
+/// # Returns
+/// The threats sorted by decreasing score.
+pub fn enemy_threats(world: &World) -> Vec<EnemyThreat> {
+    let keeper_id = get_enemy_keeper_id(world);
+    let ball_carrier_id = world.ball.as_ref().and_then(|ball| {
+        closest_bot_to_point(world.enemies_bot.values().collect(), ball.position_2d())
+            .filter(|enemy| enemy.distance(&ball.position_2d()) < BALL_CARRIER_DISTANCE)
+            .map(|enemy| enemy.id)
+    });
+    let mut threats: Vec<EnemyThreat> = world.enemies_bot.values()
+        .filter(|enemy| enemy.id != keeper_id && Some(enemy.id) != ball_carrier_id)
+        .map(|enemy| EnemyThreat { id: enemy.id, score: enemy_threat(world, enemy) })
+        .collect();
+    threats.sort_by(|a, b| b.score.total_cmp(&a.score));
+    threats
+}
@@ -13,6 +13,7 @@ use crate::filter::Filter;
 use crate::post_filter::ball::BallFilter;
 use crate::post_filter::game_controller::GameControllerPostFilter;
 use crate::post_filter::geometry::GeometryFilter;
+use crate::post_filter::opponent::OpponentFilter;
 use crate::post_filter::robot::RobotFilter;
 use crate::post_filter::PostFilter;
 use crate::pre_filter::game_controller::GameControllerPreFilter;
@@ -25,12 +26,17 @@ use crabe_framework::data::input::InboundData;
 use crabe_framework::data::world::{TeamColor, World};
 use filter::team_side::TeamSideFilter;
 use post_filter::field_mask::FieldMaskFilter;
+use std::path::PathBuf;
 
 
 #[derive(Args)]
 pub struct FilterConfig {
     #[arg(long)]
-    field_mask: Option<FieldMask>
+    field_mask: Option<FieldMask>,
+
+    /// Path of the JSON report on the enemy team, written when the program stops.
+    #[arg(long)]
+    opponent_report: Option<PathBuf>,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -42,6 +48,7 @@ pub struct FilterPipeline {
     pub pre_filters: Vec<Box<dyn PreFilter>>,
     pub filters: Vec<Box<dyn Filter>>,
     pub post_filters: Vec<Box<dyn PostFilter>>,
+    pub opponent_filter: OpponentFilter,
     pub filter_data: FilterData,
     pub team_color: TeamColor,
 }
@@ -74,6 +81,7 @@ impl FilterPipeline {
             pre_filters,
             filters,
             post_filters,
+            opponent_filter: OpponentFilter::new(config.opponent_report),
             filter_data: FilterData::default(),
             team_color: if common_config.yellow {
                 TeamColor::Yellow
@@ -85,7 +93,9 @@ impl FilterPipeline {
 }
 
 impl Component for FilterPipeline {
-    fn close(self) {}
+    fn close(self) {
+        self.opponent_filter.close();
+    }
 }
 
 impl FilterComponent for FilterPipeline {
@@ -101,5 +111,7 @@ impl FilterComponent for FilterPipeline {
         self.post_filters
             .iter_mut()
             .for_each(|f| f.step(&self.filter_data, world));
+
+        self.opponent_filter.step(&self.filter_data, world);
     }
 }
@@ -3,6 +3,7 @@ pub mod geometry;
 pub mod robot;
 pub mod game_controller;
 pub mod field_mask;
+pub mod opponent;
 
 use crate::data::FilterData;
 use crabe_framework::data::world::World;
@@ -117,6 +117,9 @@ impl PostFilter for GameControllerPostFilter {
 
             self.update_state(world, referee);
 
+            // the remaining time changes at every packet, not only with the state
+            world.data.ref_orders.current_action_time_remaining = referee.current_action_time_remaining;
+
             // update positive half, to see which team resides on the positive
             // side of the field
             if let Some(team_on_positive_half) = referee.positive_half {
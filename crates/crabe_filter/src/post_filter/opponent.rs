@@ -0,0 +1,207 @@
+use crate::data::FilterData;
+use crate::post_filter::PostFilter;
+use crabe_framework::data::world::game_state::{GameState, RunningState};
+use crabe_framework::data::world::{FormationSnapshot, OpponentModel, World};
+use log::{error, info};
+use nalgebra::Point2;
+use std::fs;
+use std::path::PathBuf;
+
+/// Increase of the ball speed in one frame over which the ball is considered kicked (m/s).
+const KICK_SPEED_JUMP: f64 = 1.;
+/// Distance between the ball and an enemy under which the enemy is considered the kicker (m).
+const KICKER_DISTANCE: f64 = 0.25;
+/// Height over which a kicked ball is considered chipped (m).
+const CHIP_HEIGHT: f64 = 0.05;
+/// Margin added to the goal width to count a kick as a shot (m).
+const SHOT_MARGIN: f64 = 0.1;
+/// Speed under which an enemy robot is not counted in the speed statistics (m/s).
+const MIN_MOVING_SPEED: f64 = 0.1;
+/// Distance from the ball on the y axis over which the keeper lateral ratio is measured (m).
+const MIN_BALL_LATERAL_OFFSET: f64 = 0.2;
+/// Number of formations kept in the model.
+const MAX_FORMATIONS: usize = 50;
+
+/// A kick seen at the previous frames, waiting for the ball to reach its top speed.
+struct PendingKick {
+    peak_speed: f64,
+    chip: bool,
+    shot_y: Option<f64>,
+}
+
+/// The `OpponentFilter` struct builds the model of the enemy team from every world snapshot
+/// and shares it with the strategies through the world.
+/// The model can be written as a JSON report when the program stops.
+#[derive(Default)]
+pub struct OpponentFilter {
+    model: OpponentModel,
+    previous_ball: Option<(Point2<f64>, f64)>,
+    previous_state: Option<GameState>,
+    pending_kick: Option<PendingKick>,
+    report_path: Option<PathBuf>,
+}
+
+impl OpponentFilter {
+    /// Creates a new `OpponentFilter`, writing its report to the given path when closed.
+    pub fn new(report_path: Option<PathBuf>) -> Self {
+        Self {
+            report_path,
+            ..Default::default()
+        }
+    }
+
+    fn update_robots(&mut self, world: &World) {
+        let enemy_penalty = &world.geometry.enemy_penalty;
+        for enemy in world.enemies_bot.values() {
+            let speed = enemy.velocity.linear.norm();
+            if speed > MIN_MOVING_SPEED {
+                self.model.robot_speeds.push(speed);
+            }
+            if enemy_penalty.is_inside(&enemy.pose.position) {
+                *self.model.keeper_frames.entry(enemy.id).or_default() += 1;
+            }
+        }
+
+        self.model.keeper_id = match &world.data.enemy.info {
+            Some(info) => Some(info.goalkeeper as u8),
+            None => self
+                .model
+                .keeper_frames
+                .iter()
+                .max_by_key(|(_, frames)| **frames)
+                .map(|(id, _)| *id),
+        };
+
+        let keeper = self
+            .model
+            .keeper_id
+            .and_then(|id| world.enemies_bot.get(&id));
+        if let Some(keeper) = keeper.filter(|keeper| enemy_penalty.is_inside(&keeper.pose.position))
+        {
+            let goal_line_x = world.geometry.enemy_goal.line.start.x;
+            self.model
+                .keeper_depth
+                .push((goal_line_x - keeper.pose.position.x).abs());
+            if let Some(ball) = &world.ball {
+                if ball.position.y.abs() > MIN_BALL_LATERAL_OFFSET {
+                    self.model
+                        .keeper_lateral_ratio
+                        .push(keeper.pose.position.y / ball.position.y);
+                }
+            }
+        }
+    }
+
+    fn update_kicks(&mut self, world: &World) {
+        let Some(ball) = &world.ball else {
+            self.pending_kick = None;
+            self.previous_ball = None;
+            return;
+        };
+        let speed = ball.velocity.xy().norm();
+
+        if let Some(mut kick) = self.pending_kick.take() {
+            kick.chip |= ball.position.z > CHIP_HEIGHT;
+            if speed > kick.peak_speed {
+                kick.peak_speed = speed;
+                self.pending_kick = Some(kick);
+            } else {
+                self.model.kick_speeds.push(kick.peak_speed);
+                if kick.chip {
+                    self.model.chip_kicks += 1;
+                } else {
+                    self.model.straight_kicks += 1;
+                }
+                if let Some(shot_y) = kick.shot_y {
+                    self.model.shot_targets.push(shot_y);
+                }
+            }
+        } else if let Some((previous_position, previous_speed)) = self.previous_ball {
+            let kicked_by_enemy = world.enemies_bot.values().any(|enemy| {
+                enemy.distance(&previous_position) < KICKER_DISTANCE
+                    && world.allies_bot.values().all(|ally| {
+                        ally.distance(&previous_position) > enemy.distance(&previous_position)
+                    })
+            });
+            if kicked_by_enemy && speed - previous_speed > KICK_SPEED_JUMP {
+                self.pending_kick = Some(PendingKick {
+                    peak_speed: speed,
+                    chip: ball.position.z > CHIP_HEIGHT,
+                    shot_y: Self::shot_y(world, previous_position, ball.velocity.xy()),
+                });
+            }
+        }
+        self.previous_ball = Some((ball.position_2d(), speed));
+    }
+
+    /// Returns the y coordinate where a kick crosses our goal line, if it goes to our goal.
+    fn shot_y(
+        world: &World,
+        position: Point2<f64>,
+        direction: nalgebra::Vector2<f64>,
+    ) -> Option<f64> {
+        let goal_line_x = world.geometry.ally_goal.line.start.x;
+        if direction.x.abs() < f64::EPSILON || (goal_line_x - position.x) / direction.x < 0. {
+            return None;
+        }
+        let y = position.y + direction.y * (goal_line_x - position.x) / direction.x;
+        (y.abs() <= world.geometry.ally_goal.width / 2. + SHOT_MARGIN).then_some(y)
+    }
+
+    fn update_formations(&mut self, world: &World) {
+        let state = world.data.ref_orders.state;
+        let enemy_color = world.data.enemy.color;
+        let enemy_set_piece = matches!(
+            state,
+            GameState::Running(RunningState::KickOff(color))
+            | GameState::Running(RunningState::FreeKick(color))
+            | GameState::Running(RunningState::Penalty(color))
+            if color == enemy_color
+        );
+        if enemy_set_piece && self.previous_state != Some(state) {
+            self.model.formations.push(FormationSnapshot {
+                state,
+                positions: world
+                    .enemies_bot
+                    .values()
+                    .map(|enemy| enemy.pose.position)
+                    .collect(),
+            });
+            if self.model.formations.len() > MAX_FORMATIONS {
+                self.model.formations.remove(0);
+            }
+        }
+        self.previous_state = Some(state);
+    }
+
+    /// Writes the report on the enemy team, if a path was given.
+    pub fn close(self) {
+        let Some(path) = self.report_path else {
+            return;
+        };
+        let report = match serde_json::to_string_pretty(&self.model) {
+            Ok(report) => report,
+            Err(e) => {
+                error!("failed to serialize the opponent report: {}", e);
+                return;
+            }
+        };
+        match fs::write(&path, report) {
+            Ok(_) => info!("opponent report written to {}", path.display()),
+            Err(e) => error!(
+                "failed to write the opponent report to {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+}
+
+impl PostFilter for OpponentFilter {
+    fn step(&mut self, _filter_data: &FilterData, world: &mut World) {
+        self.update_robots(world);
+        self.update_kicks(world);
+        self.update_formations(world);
+        world.opponent = self.model.clone();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::data::input::InboundData;
 use crate::data::output::{CommandMap, FeedbackMap};
-use crate::data::tool::{ToolCommands, ToolData};
+use crate::data::tool::{GuardData, ToolCommands, ToolData};
 use crate::data::world::World;
 
 /// The Component trait defines the methods shared for a component that is a part
@@ -50,6 +50,12 @@ pub trait FilterComponent: Component {
 /// for a SSL robot fleet based on the filtered input data.
 pub trait DecisionComponent: Component {
     fn step(&mut self, data: &World) -> (CommandMap, ToolData);
+    /// Takes the commands sent by the tools to the decision, such as the strategies to start.
+    /// They are applied on the next step.
+    fn handle_tool_commands(&mut self, _tool_commands: &mut ToolCommands) {}
+    /// Takes what the guards reported, such as the robots they pulled off the field.
+    /// It is used on the next step.
+    fn handle_guard_data(&mut self, _guard_data: &GuardData) {}
 }
 
 /// The `ToolComponent` trait defines the methods required for a component that manages and
@@ -67,7 +73,14 @@ pub trait ToolComponent: Component {
 /// The `GuardComponent` trait defines the methods required for a component that guards the robot
 /// from potentially dangerous or unwanted actions.
 pub trait GuardComponent: Component {
-    fn step(&mut self, world: &World, command: &mut CommandMap, tools_commands: &mut ToolCommands);
+    /// Checks the commands, reporting to the tools in `guard_data`.
+    fn step(
+        &mut self,
+        world: &World,
+        command: &mut CommandMap,
+        guard_data: &mut GuardData,
+        tools_commands: &mut ToolCommands,
+    );
 }
 
 /// The `OutputComponent` trait defines the methods required for a component that sends output
@@ -1,3 +1,4 @@
+use chrono::Duration;
 use nalgebra::Point2;
 use serde::Serialize;
 use crate::data::referee::event::GameEvent;
@@ -24,6 +25,11 @@ pub struct RefereeOrders {
     /// The last designated position for a ball placement event
     /// If no ball placement is required, this field is set to None
     pub designated_position: Option<Point2<f64>>,
+    /// Time remaining until the current action times out,
+    /// such as the time left to take a free kick.
+    /// It can get negative
+    #[serde(skip)]
+    pub current_action_time_remaining: Option<Duration>,
 }
 
 const MAX_SPEED_HALTED: f32 = 0.;
@@ -31,6 +37,8 @@ const MAX_SPEED_STOPPED: f32 = 1.5;
 //TODO: use MAX_LINEAR constant ? (can't because of circular dependency
 // between crabe_framework and crabe_guard)
 const MAX_SPEED_RUNNING: f32 = 6.; // Arbitrary value, not defined by the rulebook
+/// Distance to keep from the ball during a stop, defined by the rulebook (m)
+const STOP_BALL_DISTANCE: f32 = 0.5;
 
 impl RefereeOrders {
     /// Get the maximum speed authorized during a given game state
@@ -50,7 +58,7 @@ impl RefereeOrders {
     pub fn get_min_dist_from_ball_during(game_state: GameState) -> Option<f32> {
         match game_state {
             GameState::Halted(_) => None,
-            GameState::Stopped(_) => Some(1.5),
+            GameState::Stopped(_) => Some(STOP_BALL_DISTANCE),
             GameState::Running(_) => None,
         }
     }
@@ -64,6 +72,7 @@ impl RefereeOrders {
             speed_limit: Self::get_speed_limit_during(game_state),
             min_dist_from_ball: None,
             designated_position: None,
+            current_action_time_remaining: None,
         }
     }
 
@@ -81,6 +90,7 @@ impl RefereeOrders {
         };
 
         self.designated_position = referee.designated_position;
+        self.current_action_time_remaining = referee.current_action_time_remaining;
     }
 }
 
@@ -92,6 +102,7 @@ impl Default for RefereeOrders {
             speed_limit: MAX_SPEED_HALTED,
             min_dist_from_ball: None,
             designated_position: None,
+            current_action_time_remaining: None,
         }
     }
 }
\ No newline at end of file
@@ -1,14 +1,193 @@
 use crate::data::annotation::AnnotationStore;
-use serde::Serialize;
+use crate::data::output::{Command, Kick};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::collections::HashMap;
 
 /// The `ToolData` struct is a container for storing additional data that can be sent to
 /// external tools, such as a viewer or joystick handler.
+#[serde_as]
 #[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolData {
     #[serde(flatten)]
     pub annotations: AnnotationStore,
+    /// The running branches of the behavior tree of each robot, each one from the root to a running leaf.
+    /// There are several branches when the children of a parallel node are running.
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub behavior_trees: HashMap<u8, Vec<Vec<String>>>,
+    /// What the guards reported at the previous step.
+    pub guards: GuardData,
 }
 
-/// The `ToolCommands` struct is a container for storing commands that are sent to external
-/// tools.
-pub struct ToolCommands;
+/// The `GuardData` struct contains what the guards report to the tools,
+/// such as the robots they stopped, so that the operators see it.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardData {
+    /// Whether the vision stopped sending data, all the robots being stopped.
+    pub vision_lost: bool,
+    /// The robots stopped because their data is too old.
+    pub stale_robots: Vec<u8>,
+    /// Whether the emergency stop is engaged, all the robots being stopped.
+    pub emergency_stop: bool,
+    /// The robots disabled by the operators, which are stopped.
+    pub disabled_robots: Vec<u8>,
+    /// The robots pulled off the field because we have more robots than allowed by the referee,
+    /// left out of the decisions.
+    pub pulled_robots: Vec<u8>,
+    /// The changes of the commands by the guards.
+    pub interventions: Vec<GuardIntervention>,
+    /// The number of steps each guard changed the command of each robot since the start.
+    pub intervention_counts: Vec<InterventionCount>,
+    /// The number of steps guarded since the start, to compare with the counts.
+    pub steps: u64,
+}
+
+impl GuardData {
+    /// Reports the fields of the command of a robot changed by a guard.
+    ///
+    /// # Arguments
+    ///
+    /// * `guard`: The name of the guard, like `"SpeedGuard"`.
+    /// * `robot_id`: The id of the robot.
+    /// * `before`: The command before the guard changed it.
+    /// * `after`: The command after the guard changed it.
+    /// * `reason`: Why the guard changed the command.
+    pub fn report(
+        &mut self,
+        guard: &str,
+        robot_id: u8,
+        before: &Command,
+        after: &Command,
+        reason: &str,
+    ) {
+        let kick_power = |kick: &Option<Kick>| match kick {
+            Some(Kick::StraightKick { power }) | Some(Kick::ChipKick { power }) => *power,
+            None => 0.,
+        };
+        let fields = [
+            (
+                CommandField::ForwardVelocity,
+                before.forward_velocity,
+                after.forward_velocity,
+            ),
+            (
+                CommandField::LeftVelocity,
+                before.left_velocity,
+                after.left_velocity,
+            ),
+            (
+                CommandField::AngularVelocity,
+                before.angular_velocity,
+                after.angular_velocity,
+            ),
+            (CommandField::Dribbler, before.dribbler, after.dribbler),
+            (
+                CommandField::Charge,
+                before.charge as u8 as f32,
+                after.charge as u8 as f32,
+            ),
+            (
+                CommandField::Kick,
+                kick_power(&before.kick),
+                kick_power(&after.kick),
+            ),
+        ];
+        for (field, original, clamped) in fields {
+            // the bits are compared so that a NaN replaced by a value is reported
+            if original.to_bits() != clamped.to_bits() {
+                self.interventions.push(GuardIntervention {
+                    guard: guard.to_string(),
+                    robot_id,
+                    field,
+                    original,
+                    clamped,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// A field of a command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandField {
+    ForwardVelocity,
+    LeftVelocity,
+    AngularVelocity,
+    Dribbler,
+    /// Whether the robot charges its capacitor, 1 when it does.
+    Charge,
+    /// The power of the kick, 0 without a kick.
+    Kick,
+}
+
+/// A change of a field of the command of a robot by a guard.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardIntervention {
+    /// The name of the guard, like `"SpeedGuard"`.
+    pub guard: String,
+    pub robot_id: u8,
+    pub field: CommandField,
+    /// The value asked by the strategy, or by the previous guards.
+    pub original: f32,
+    /// The value sent after the guard.
+    pub clamped: f32,
+    /// Why the guard changed the value.
+    pub reason: String,
+}
+
+/// The number of steps a guard changed the command of a robot.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterventionCount {
+    pub guard: String,
+    pub robot_id: u8,
+    pub count: u64,
+}
+
+/// The value of a strategy parameter, as written in a tool request, a config file or on the command line.
+/// It is checked against the parameters expected by the strategy when the strategy is created.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+/// A request to run a strategy on some robots, taking them from their current strategies.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyRequest {
+    /// The name of the strategy, like `"Attacker"`.
+    pub name: String,
+    /// The ids of the robots running the strategy.
+    pub ids: Vec<u8>,
+    /// The parameters of the strategy, by name.
+    #[serde(default)]
+    pub parameters: HashMap<String, ParameterValue>,
+}
+
+/// A request of the operators to the guards, to take robots out of the game without stopping the program.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum GuardRequest {
+    /// Enables or disables a robot, a disabled robot is stopped and can't kick.
+    SetRobotEnabled { id: u8, enabled: bool },
+    /// Engages or releases the emergency stop of all the robots.
+    EmergencyStop { engaged: bool },
+}
+
+/// The `ToolCommands` struct is a container for storing commands that are sent by external
+/// tools to the other components.
+#[derive(Clone, Debug, Default)]
+pub struct ToolCommands {
+    /// The strategies requested by the tools, started by the decision component.
+    pub strategies: Vec<StrategyRequest>,
+    /// The requests to the guards, applied by the guard component.
+    pub guard_requests: Vec<GuardRequest>,
+}
@@ -11,6 +11,9 @@ pub use self::ball::{Ball, BallTouchInfo};
 mod team;
 pub use self::team::{Team, TeamColor};
 
+mod opponent;
+pub use self::opponent::{FormationSnapshot, OpponentModel, RunningStat};
+
 pub mod game_data;
 pub mod game_state;
 pub mod stage_info;
@@ -42,6 +45,8 @@ pub struct World {
     pub ball: Option<Ball>,
     /// The team color of our team.
     pub team_color: TeamColor,
+    /// What we learned about the enemy team during the match.
+    pub opponent: OpponentModel,
 }
 
 impl World {
@@ -59,6 +64,7 @@ impl World {
             enemies_bot: Default::default(),
             ball: None,
             team_color,
+            opponent: Default::default(),
         }
     }
 }
@@ -0,0 +1,146 @@
+use crate::data::world::game_state::GameState;
+use nalgebra::Point2;
+use serde::Serialize;
+use serde_with::serde_as;
+use std::collections::HashMap;
+
+/// Number of samples needed before a statistic is considered reliable.
+pub const MIN_RELIABLE_SAMPLES: u64 = 5;
+
+/// The `RunningStat` struct accumulates the mean, the variance and the bounds of a series of values
+/// without storing them.
+///
+/// # Example
+/// ```
+/// use crabe_framework::data::world::RunningStat;
+///
+/// let mut stat = RunningStat::default();
+/// assert_eq!(stat.mean(), None);
+/// for value in [2., 4., 4., 4., 5., 5., 7., 9.] {
+///     stat.push(value);
+/// }
+/// assert_eq!(stat.count(), 8);
+/// assert_eq!(stat.mean(), Some(5.));
+/// assert_eq!(stat.std_dev(), Some(2.));
+/// assert_eq!(stat.max(), Some(9.));
+/// ```
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningStat {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RunningStat {
+    /// Adds a value to the statistic.
+    pub fn push(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the number of values added.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the mean of the values, if any.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Returns the standard deviation of the values, if any.
+    pub fn std_dev(&self) -> Option<f64> {
+        (self.count > 0).then(|| (self.m2 / self.count as f64).sqrt())
+    }
+
+    /// Returns the smallest value, if any.
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the largest value, if any.
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Returns true if enough values were added to rely on the statistic.
+    pub fn is_reliable(&self) -> bool {
+        self.count >= MIN_RELIABLE_SAMPLES
+    }
+}
+
+/// The positions of the enemy robots when one of their set pieces starts.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FormationSnapshot {
+    /// The game state of the set piece.
+    pub state: GameState,
+    /// The positions of the enemy robots.
+    pub positions: Vec<Point2<f64>>,
+}
+
+/// The `OpponentModel` struct gathers what we learned about the enemy team during the match.
+/// All positions use the same frame as the `World`, our goal being on the negative side.
+#[serde_as]
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OpponentModel {
+    /// The id of the enemy keeper, given by the game controller or guessed from the time spent in their penalty area.
+    pub keeper_id: Option<u8>,
+    /// The number of frames each enemy robot spent in their penalty area.
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub keeper_frames: HashMap<u8, u64>,
+    /// The distance between the enemy keeper and their goal line (m).
+    pub keeper_depth: RunningStat,
+    /// How much the enemy keeper follows the ball sideways, as the ratio between its y and the ball y.
+    pub keeper_lateral_ratio: RunningStat,
+    /// The speed of the ball after an enemy kick (m/s).
+    pub kick_speeds: RunningStat,
+    /// The number of straight kicks by the enemies.
+    pub straight_kicks: u32,
+    /// The number of chip kicks by the enemies.
+    pub chip_kicks: u32,
+    /// The speed of the enemy robots (m/s).
+    pub robot_speeds: RunningStat,
+    /// The y coordinate where the enemy shots cross our goal line (m).
+    pub shot_targets: RunningStat,
+    /// The formations of the enemies at the start of their set pieces, from the oldest to the latest.
+    pub formations: Vec<FormationSnapshot>,
+}
+
+impl OpponentModel {
+    /// Returns the y coordinate where the enemies usually shoot on our goal, once enough shots were seen.
+    pub fn preferred_shot_y(&self) -> Option<f64> {
+        self.shot_targets
+            .is_reliable()
+            .then(|| self.shot_targets.mean())
+            .flatten()
+    }
+
+    /// Returns the usual speed of the enemy kicks, once enough kicks were seen.
+    pub fn typical_kick_speed(&self) -> Option<f64> {
+        self.kick_speeds
+            .is_reliable()
+            .then(|| self.kick_speeds.mean())
+            .flatten()
+    }
+
+    /// Returns the latest formation seen for a set piece.
+    pub fn last_formation(&self, state: GameState) -> Option<&FormationSnapshot> {
+        self.formations
+            .iter()
+            .rev()
+            .find(|formation| formation.state == state)
+    }
+}
@@ -0,0 +1,450 @@
+use crate::constant::{
+    EMERGENCY_ANGULAR_DECELERATION, EMERGENCY_DECELERATION, FRAME_PERIOD, MAX_ANGULAR_ACCELERATION,
+    MAX_LINEAR_ACCELERATION,
+};
+use crate::pipeline::Guard;
+use crabe_framework::data::output::{Command, CommandMap};
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::World;
+use nalgebra::{SVector, Vector1, Vector2};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Longest time between two frames taken into account, so that a late frame doesn't allow a jump (s).
+const MAX_PERIOD: f32 = 2. * FRAME_PERIOD;
+
+/// The acceleration limits of a robot.
+#[derive(Clone, Copy, Debug)]
+pub struct AccelerationLimits {
+    /// Maximum linear acceleration (m.s-2).
+    pub max_linear: f32,
+    /// Maximum angular acceleration (rad.s-2).
+    pub max_angular: f32,
+    /// Maximum linear jerk (m.s-3), None to change the acceleration instantly.
+    pub max_linear_jerk: Option<f32>,
+    /// Maximum angular jerk (rad.s-3), None to change the acceleration instantly.
+    pub max_angular_jerk: Option<f32>,
+    /// Linear deceleration used when the robot is asked to stop (m.s-2).
+    pub emergency_linear: f32,
+    /// Angular deceleration used when the robot is asked to stop turning (rad.s-2).
+    pub emergency_angular: f32,
+}
+
+impl Default for AccelerationLimits {
+    fn default() -> Self {
+        Self {
+            max_linear: MAX_LINEAR_ACCELERATION,
+            max_angular: MAX_ANGULAR_ACCELERATION,
+            max_linear_jerk: None,
+            max_angular_jerk: None,
+            emergency_linear: EMERGENCY_DECELERATION,
+            emergency_angular: EMERGENCY_ANGULAR_DECELERATION,
+        }
+    }
+}
+
+/// The acceleration limits of a robot given on the command line, the missing ones being the limits of all the robots.
+#[derive(Clone, Copy, Debug)]
+pub struct RobotAcceleration {
+    /// The id of the robot.
+    pub id: u8,
+    /// Maximum linear acceleration (m.s-2).
+    pub linear: Option<f32>,
+    /// Maximum angular acceleration (rad.s-2).
+    pub angular: Option<f32>,
+}
+
+impl RobotAcceleration {
+    /// Returns the limits of the robot, the ones it doesn't set being taken from the limits of all the robots.
+    pub fn limits(&self, limits: AccelerationLimits) -> AccelerationLimits {
+        AccelerationLimits {
+            max_linear: self.linear.unwrap_or(limits.max_linear),
+            max_angular: self.angular.unwrap_or(limits.max_angular),
+            ..limits
+        }
+    }
+}
+
+/// Parses the acceleration limits of a robot written on the command line as `id=3,linear=2.5,angular=20`,
+/// the limits being optional.
+///
+/// # Example
+/// ```
+/// use crabe_guard::acceleration::{parse_robot_acceleration, AccelerationLimits};
+///
+/// let robot = parse_robot_acceleration("id=3,linear=2.5").unwrap();
+/// assert_eq!(robot.id, 3);
+/// let limits = robot.limits(AccelerationLimits::default());
+/// assert_eq!(limits.max_linear, 2.5);
+/// assert_eq!(limits.max_angular, AccelerationLimits::default().max_angular);
+/// assert!(parse_robot_acceleration("linear=2.5").is_err());
+/// assert!(parse_robot_acceleration("id=3,linear=-1").is_err());
+/// assert!(parse_robot_acceleration("id=3,speed=2").is_err());
+/// ```
+pub fn parse_robot_acceleration(s: &str) -> Result<RobotAcceleration, String> {
+    let mut id = None;
+    let mut linear = None;
+    let mut angular = None;
+    for field in s.split(',') {
+        let (name, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("expected id=id,linear=value,angular=value, got {}", s))?;
+        let value = value.trim();
+        let limit = || match value.parse::<f32>() {
+            Ok(limit) if limit.is_finite() && limit > 0. => Ok(limit),
+            _ => Err(format!(
+                "invalid limit {}, expected a positive number",
+                value
+            )),
+        };
+        match name.trim() {
+            "id" => {
+                id = Some(
+                    value
+                        .parse::<u8>()
+                        .map_err(|e| format!("invalid robot id {}: {}", value, e))?,
+                )
+            }
+            "linear" => linear = Some(limit()?),
+            "angular" => angular = Some(limit()?),
+            name => {
+                return Err(format!(
+                    "unknown field {}, expected one of: id, linear, angular",
+                    name
+                ))
+            }
+        }
+    }
+    Ok(RobotAcceleration {
+        id: id.ok_or_else(|| format!("missing robot id in {}", s))?,
+        linear,
+        angular,
+    })
+}
+
+/// The velocity sent to a robot at the previous frame, and the acceleration used to reach it.
+#[derive(Clone, Copy)]
+struct Motion<const D: usize> {
+    velocity: SVector<f32, D>,
+    acceleration: SVector<f32, D>,
+}
+
+impl<const D: usize> Default for Motion<D> {
+    fn default() -> Self {
+        Self {
+            velocity: SVector::zeros(),
+            acceleration: SVector::zeros(),
+        }
+    }
+}
+
+impl<const D: usize> Motion<D> {
+    /// Moves the velocity toward the target, limiting the acceleration and the jerk.
+    /// With a jerk limit, the acceleration is reduced while getting close to the target,
+    /// so that the target is reached with no acceleration left.
+    fn toward(
+        &self,
+        target: SVector<f32, D>,
+        max_acceleration: f32,
+        max_jerk: Option<f32>,
+        dt: f32,
+    ) -> Self {
+        let delta = target - self.velocity;
+        if delta.norm() <= f32::EPSILON {
+            return Self {
+                velocity: target,
+                acceleration: SVector::zeros(),
+            };
+        }
+
+        let mut max_acceleration = max_acceleration;
+        if let Some(jerk) = max_jerk {
+            max_acceleration = max_acceleration.min((2. * jerk * delta.norm()).sqrt());
+        }
+        let mut acceleration = (delta / dt).cap_magnitude(max_acceleration);
+        if let Some(jerk) = max_jerk {
+            acceleration =
+                self.acceleration + (acceleration - self.acceleration).cap_magnitude(jerk * dt);
+        }
+
+        let step = acceleration * dt;
+        if step.norm() >= delta.norm() {
+            return Self {
+                velocity: target,
+                acceleration: delta / dt,
+            };
+        }
+        Self {
+            velocity: self.velocity + step,
+            acceleration,
+        }
+    }
+}
+
+/// The motion of a robot at the previous frame.
+#[derive(Clone, Copy, Default)]
+struct RobotMotion {
+    linear: Motion<2>,
+    angular: Motion<1>,
+}
+
+impl RobotMotion {
+    fn is_stopped(&self) -> bool {
+        self.linear.velocity == Vector2::zeros() && self.angular.velocity == Vector1::zeros()
+    }
+}
+
+/// The `AccelerationGuard` struct limits the linear and angular acceleration of the robots,
+/// and optionally their jerk, from the command sent to each robot at the previous frame,
+/// so that the robots don't slip or tip over.
+///
+/// A robot asked to stop, no longer receiving commands or stopped by the operators, decelerates
+/// with the emergency deceleration until it stops. Only the kick and the dribbler of the robots
+/// stopped by the operators are cut at once.
+///
+/// The velocities are limited from the commands actually sent, so a robot slowed down
+/// by the next guards accelerates again smoothly.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::World;
+/// use crabe_guard::acceleration::{AccelerationGuard, AccelerationLimits};
+/// use crabe_guard::pipeline::Guard;
+///
+/// let world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// let limits = AccelerationLimits { max_linear: 4., ..Default::default() };
+/// let mut guard = AccelerationGuard::new(limits);
+///
+/// // The robot can't go from 0 to 2 m/s in one frame of 16 ms
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 2., ..Default::default() });
+/// guard.guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!((commands[&0].forward_velocity - 4. * 0.016).abs() < 1e-4);
+/// ```
+///
+/// A robot stopped by the `CollisionGuard` accelerates again from its stop once the path is clear:
+/// ```
+/// use crabe_framework::component::GuardComponent;
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::{AllyInfo, EnemyInfo, Pose, Robot, World};
+/// use crabe_guard::acceleration::{AccelerationGuard, AccelerationLimits};
+/// use crabe_guard::collision::CollisionGuard;
+/// use crabe_guard::pipeline::GuardPipeline;
+/// use nalgebra::Point2;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, pose: Pose::new(Point2::new(0., 0.), 0.), ..Default::default() });
+/// world.enemies_bot.insert(0, Robot::<EnemyInfo> { id: 0, pose: Pose::new(Point2::new(0.3, 0.), 0.), ..Default::default() });
+/// // the robot can reach 2 m/s in the first frame
+/// let limits = AccelerationLimits { max_linear: 200., ..Default::default() };
+/// let mut pipeline = GuardPipeline::new(vec![
+///     Box::new(AccelerationGuard::new(limits)),
+///     Box::<CollisionGuard>::default(),
+/// ]);
+/// let mut step = |world: &World| {
+///     let mut commands = CommandMap::new();
+///     commands.insert(0, Command { forward_velocity: 2., ..Default::default() });
+///     pipeline.step(world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+///     commands[&0].forward_velocity
+/// };
+///
+/// // The enemy is in the way
+/// let stopped = step(&world);
+/// assert!(stopped < 1.);
+/// // The path is clear, the robot doesn't jump back to 2 m/s
+/// world.enemies_bot.clear();
+/// let cleared = step(&world);
+/// assert!(cleared >= stopped && cleared < 2.);
+/// ```
+///
+/// The emergency stop slows the robots down with the emergency deceleration instead of stopping them in one frame:
+/// ```
+/// use crabe_framework::component::GuardComponent;
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap, Kick};
+/// use crabe_framework::data::tool::{GuardData, GuardRequest, ToolCommands};
+/// use crabe_framework::data::world::World;
+/// use crabe_guard::acceleration::{AccelerationGuard, AccelerationLimits};
+/// use crabe_guard::emergency::EmergencyGuard;
+/// use crabe_guard::pipeline::GuardPipeline;
+/// use std::time::Duration;
+///
+/// let world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// // the robot can reach 2 m/s in the first frame
+/// let limits = AccelerationLimits { max_linear: 200., emergency_linear: 6., ..Default::default() };
+/// let mut pipeline = GuardPipeline::new(vec![
+///     Box::<EmergencyGuard>::default(),
+///     Box::new(AccelerationGuard::new(limits)),
+/// ]);
+/// let mut step = |tool_commands: &mut ToolCommands| {
+///     let mut commands = CommandMap::new();
+///     let kick = Some(Kick::StraightKick { power: 1. });
+///     commands.insert(0, Command { forward_velocity: 2., kick, ..Default::default() });
+///     pipeline.step(&world, &mut commands, &mut GuardData::default(), tool_commands);
+///     commands[&0]
+/// };
+///
+/// assert_eq!(step(&mut ToolCommands::default()).forward_velocity, 2.);
+/// std::thread::sleep(Duration::from_millis(16));
+/// let mut tool_commands = ToolCommands::default();
+/// tool_commands.guard_requests.push(GuardRequest::EmergencyStop { engaged: true });
+/// let stopping = step(&mut tool_commands);
+/// // at most two frames of 16 ms are taken into account
+/// assert!(stopping.forward_velocity <= 2. - 6. * 0.016);
+/// assert!(stopping.forward_velocity >= 2. - 6. * 0.032);
+/// assert!(stopping.kick.is_none());
+/// ```
+pub struct AccelerationGuard {
+    /// The limits of the robots without their own limits.
+    limits: AccelerationLimits,
+    robot_limits: HashMap<u8, AccelerationLimits>,
+    motions: HashMap<u8, RobotMotion>,
+    last_guard: Option<Instant>,
+}
+
+impl AccelerationGuard {
+    /// Creates a new `AccelerationGuard` with the same limits for all the robots.
+    pub fn new(limits: AccelerationLimits) -> Self {
+        Self {
+            limits,
+            robot_limits: HashMap::new(),
+            motions: HashMap::new(),
+            last_guard: None,
+        }
+    }
+
+    /// Uses specific limits for a robot, like one with a worn wheel.
+    pub fn with_robot_limits(mut self, id: u8, limits: AccelerationLimits) -> Self {
+        self.robot_limits.insert(id, limits);
+        self
+    }
+
+    /// Returns the limits of a robot.
+    pub fn limits(&self, id: u8) -> &AccelerationLimits {
+        self.robot_limits.get(&id).unwrap_or(&self.limits)
+    }
+
+    /// Returns the time since the previous frame, the frame period on the first one.
+    fn period(&mut self) -> f32 {
+        let now = Instant::now();
+        let dt = self.last_guard.map_or(FRAME_PERIOD, |last_guard| {
+            now.duration_since(last_guard).as_secs_f32()
+        });
+        self.last_guard = Some(now);
+        dt.clamp(f32::EPSILON, MAX_PERIOD)
+    }
+}
+
+impl Default for AccelerationGuard {
+    fn default() -> Self {
+        Self::new(AccelerationLimits::default())
+    }
+}
+
+impl Guard for AccelerationGuard {
+    fn guard(
+        &mut self,
+        _world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        let dt = self.period();
+
+        // The robots which no longer receive commands are stopped smoothly
+        for (id, motion) in &self.motions {
+            if !motion.is_stopped() {
+                commands.entry(*id).or_insert_with(Command::default);
+            }
+        }
+
+        for (id, command) in commands.iter_mut() {
+            let before = *command;
+            let limits = *self.limits(*id);
+            let previous = self.motions.get(id).copied().unwrap_or_default();
+
+            // The robots stopped by the operators stop as fast as they can, without kicking nor dribbling
+            let stopped = guard_data.emergency_stop || guard_data.disabled_robots.contains(id);
+            if stopped {
+                command.kick = None;
+                command.dribbler = 0.;
+            }
+            let (max_linear_jerk, max_angular_jerk) = if stopped {
+                (None, None)
+            } else {
+                (limits.max_linear_jerk, limits.max_angular_jerk)
+            };
+
+            let linear_target = Vector2::new(command.forward_velocity, command.left_velocity);
+            let linear_acceleration = if stopped || linear_target == Vector2::zeros() {
+                limits.emergency_linear
+            } else {
+                limits.max_linear
+            };
+            let angular_target = Vector1::new(command.angular_velocity);
+            let angular_acceleration = if stopped || angular_target == Vector1::zeros() {
+                limits.emergency_angular
+            } else {
+                limits.max_angular
+            };
+
+            let motion = RobotMotion {
+                linear: previous.linear.toward(
+                    linear_target,
+                    linear_acceleration,
+                    max_linear_jerk,
+                    dt,
+                ),
+                angular: previous.angular.toward(
+                    angular_target,
+                    angular_acceleration,
+                    max_angular_jerk,
+                    dt,
+                ),
+            };
+            command.forward_velocity = motion.linear.velocity.x;
+            command.left_velocity = motion.linear.velocity.y;
+            command.angular_velocity = motion.angular.velocity.x;
+            guard_data.report(
+                "AccelerationGuard",
+                *id,
+                &before,
+                command,
+                if stopped {
+                    "emergency deceleration"
+                } else {
+                    "acceleration limit"
+                },
+            );
+            self.motions.insert(*id, motion);
+        }
+        self.motions.retain(|_, motion| !motion.is_stopped());
+    }
+
+    fn commands_sent(&mut self, commands: &CommandMap) {
+        // the next guards may have slowed the robots down, the next limits start from the commands sent
+        for (id, command) in commands {
+            let linear = Vector2::new(command.forward_velocity, command.left_velocity);
+            let angular = Vector1::new(command.angular_velocity);
+            let motion = self.motions.entry(*id).or_default();
+            if motion.linear.velocity != linear {
+                motion.linear = Motion {
+                    velocity: linear,
+                    acceleration: Vector2::zeros(),
+                };
+            }
+            if motion.angular.velocity != angular {
+                motion.angular = Motion {
+                    velocity: angular,
+                    acceleration: Vector1::zeros(),
+                };
+            }
+        }
+        self.motions.retain(|_, motion| !motion.is_stopped());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::constant::{KEEPER_ID, LOOKAHEAD};
+use crate::pipeline::Guard;
+use crabe_framework::data::geometry::{Geometry, Goal, Penalty};
+use crabe_framework::data::output::CommandMap;
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::World;
+use crabe_math::shape::Rectangle;
+use crabe_math::vectors::rotate_vector;
+use log::debug;
+use nalgebra::{Point2, Vector2};
+
+/// Distance kept between a robot and the walls around the field (m).
+const WALL_MARGIN: f64 = 0.05;
+
+/// The `BoundaryGuard` struct keeps the robots in the legal area: on the field or on its boundary,
+/// far enough from the walls, and out of the goals.
+/// The velocity moving a robot out of the legal area within a short time is projected on its border,
+/// a robot already out of it can only come back.
+///
+/// Only the keeper is allowed in our defense area, the other robots are kept from touching it.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::{AllyInfo, Pose, Robot, World};
+/// use crabe_guard::boundary::BoundaryGuard;
+/// use crabe_guard::pipeline::Guard;
+/// use nalgebra::Point2;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, pose: Pose::new(Point2::new(4.5, 2.), 0.), ..Default::default() });
+///
+/// // The robot on the field line can go along it but not further out
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 1., left_velocity: 1., ..Default::default() });
+/// BoundaryGuard::default().guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(commands[&0].forward_velocity < 1.);
+/// assert_eq!(commands[&0].left_velocity, 1.);
+///
+/// // Only the keeper can enter our defense area
+/// for id in [1, 3] {
+///     world.allies_bot.insert(id, Robot::<AllyInfo> { id, pose: Pose::new(Point2::new(-3.3, 0.), 0.), ..Default::default() });
+///     commands.insert(id, Command { forward_velocity: -1., ..Default::default() });
+/// }
+/// BoundaryGuard::default().guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(commands[&1].forward_velocity > -1.);
+/// assert_eq!(commands[&3].forward_velocity, -1.);
+/// ```
+#[derive(Default)]
+pub struct BoundaryGuard;
+
+impl BoundaryGuard {
+    /// Returns the area where the center of a robot can be.
+    fn legal_area(geometry: &Geometry) -> Rectangle {
+        let margin = geometry.boundary_width - geometry.robot_radius - WALL_MARGIN;
+        let length = geometry.field.length + 2. * margin;
+        let width = geometry.field.width + 2. * margin;
+        Rectangle::new(length, width, Point2::new(-length / 2., -width / 2.))
+    }
+
+    /// Returns the area inside a goal where the center of a robot can't be, the robot touching the goal frame.
+    fn goal_area(goal: &Goal, robot_radius: f64) -> Rectangle {
+        let goal_x = goal.line.start.x;
+        let (front, back) = (
+            goal_x - goal_x.signum() * robot_radius,
+            goal_x + goal_x.signum() * goal.depth,
+        );
+        let width = goal.width + 2. * robot_radius;
+        Rectangle::new(
+            (back - front).abs(),
+            width,
+            Point2::new(front.min(back), -width / 2.),
+        )
+    }
+
+    /// Returns the area where the center of a robot other than the keeper can't be, the robot touching our defense area.
+    fn defense_area(penalty: &Penalty, robot_radius: f64) -> Rectangle {
+        let area = penalty.enlarged_penalty(robot_radius);
+        let (front, back) = (area.front_line.start.x, area.back_line.start.x);
+        Rectangle::new(
+            (back - front).abs(),
+            area.width,
+            Point2::new(front.min(back), -area.width / 2.),
+        )
+    }
+
+    /// Limits a velocity so that the robot stays in an area.
+    fn stay_inside(area: &Rectangle, position: &Point2<f64>, velocity: &mut Vector2<f64>) {
+        let (min, max) = (
+            area.position,
+            area.position + Vector2::new(area.width, area.height),
+        );
+        // a robot out of the area can stay still or come back
+        velocity.x = velocity.x.clamp(
+            ((min.x - position.x) / LOOKAHEAD).min(0.),
+            ((max.x - position.x) / LOOKAHEAD).max(0.),
+        );
+        velocity.y = velocity.y.clamp(
+            ((min.y - position.y) / LOOKAHEAD).min(0.),
+            ((max.y - position.y) / LOOKAHEAD).max(0.),
+        );
+    }
+
+    /// Limits a velocity so that the robot doesn't enter an area, stopping it on the side it comes from.
+    /// A robot already in the area can't go deeper toward the goal line at `goal_x`.
+    fn stay_outside(
+        area: &Rectangle,
+        position: &Point2<f64>,
+        velocity: &mut Vector2<f64>,
+        goal_x: f64,
+    ) {
+        if !area.is_inside(position + *velocity * LOOKAHEAD) {
+            return;
+        }
+        let (min, max) = (
+            area.position,
+            area.position + Vector2::new(area.width, area.height),
+        );
+        // the distance of the robot to each side of the area, the robot being out of the area on the positive ones
+        let sides = [
+            (min.x - position.x, Vector2::x()),
+            (position.x - max.x, -Vector2::x()),
+            (min.y - position.y, Vector2::y()),
+            (position.y - max.y, -Vector2::y()),
+        ];
+        let (distance, inward) = sides
+            .into_iter()
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or((0., Vector2::zeros()));
+        if distance < 0. {
+            // already in the area, the robot can only go out of it
+            if velocity.x * goal_x > 0. {
+                velocity.x = 0.;
+            }
+            return;
+        }
+        let speed_in = velocity.dot(&inward);
+        let allowed = distance / LOOKAHEAD;
+        if speed_in > allowed {
+            *velocity -= inward * (speed_in - allowed);
+        }
+    }
+}
+
+impl Guard for BoundaryGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        let geometry = &world.geometry;
+        let legal_area = Self::legal_area(geometry);
+        let defense_area = Self::defense_area(&geometry.ally_penalty, geometry.robot_radius);
+        let keeper_id = world
+            .data
+            .ally
+            .info
+            .as_ref()
+            .map_or(KEEPER_ID, |info| info.goalkeeper as u8);
+
+        for (id, command) in commands.iter_mut() {
+            let Some(robot) = world.allies_bot.get(id) else {
+                continue;
+            };
+            let position = robot.pose.position;
+            let local = Vector2::new(
+                command.forward_velocity as f64,
+                command.left_velocity as f64,
+            );
+            let requested = rotate_vector(local, robot.pose.orientation);
+
+            let mut velocity = requested;
+            Self::stay_inside(&legal_area, &position, &mut velocity);
+            for goal in [&geometry.ally_goal, &geometry.enemy_goal] {
+                let goal_area = Self::goal_area(goal, geometry.robot_radius);
+                Self::stay_outside(&goal_area, &position, &mut velocity, goal.line.start.x);
+            }
+            if *id != keeper_id {
+                Self::stay_outside(
+                    &defense_area,
+                    &position,
+                    &mut velocity,
+                    geometry.ally_goal.line.start.x,
+                );
+            }
+
+            if velocity != requested {
+                debug!(
+                    "robot {} would leave the legal area at {:?}, its velocity is projected on the border",
+                    id,
+                    position + requested * LOOKAHEAD
+                );
+                let before = *command;
+                let local = rotate_vector(velocity, -robot.pose.orientation);
+                command.forward_velocity = local.x as f32;
+                command.left_velocity = local.y as f32;
+                guard_data.report("BoundaryGuard", *id, &before, command, "legal area border");
+            }
+        }
+    }
+}
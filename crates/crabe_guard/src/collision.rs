@@ -0,0 +1,281 @@
+use crate::pipeline::Guard;
+use crabe_framework::data::output::CommandMap;
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::World;
+use crabe_math::shape::Line;
+use crabe_math::vectors::rotate_vector;
+use log::debug;
+use nalgebra::{Point2, Vector2};
+use std::collections::HashMap;
+
+/// Time during which the commanded velocities are simulated (s).
+const HORIZON: f64 = 0.3;
+/// Number of positions checked along the horizon.
+const STEPS: usize = 15;
+/// Distance kept between a robot and the obstacles (m).
+const MARGIN: f64 = 0.02;
+
+/// Something a robot can collide with, moving at a constant velocity.
+struct Obstacle {
+    /// The name of the obstacle in the logs.
+    label: String,
+    shape: ObstacleShape,
+}
+
+enum ObstacleShape {
+    /// A robot, with the id of the ally robot to ignore it when checking itself.
+    Robot {
+        ally: Option<u8>,
+        position: Point2<f64>,
+        velocity: Vector2<f64>,
+    },
+    /// A goal frame or a wall.
+    Segment(Line),
+}
+
+impl Obstacle {
+    /// Returns the distance kept from the obstacle by a robot at a position, negative if they collide,
+    /// and the direction from the robot to the obstacle.
+    fn clearance(&self, point: &Point2<f64>, t: f64, robot_radius: f64) -> (f64, Vector2<f64>) {
+        let (closest, radius) = match &self.shape {
+            ObstacleShape::Robot {
+                position, velocity, ..
+            } => (position + velocity * t, 2. * robot_radius),
+            ObstacleShape::Segment(line) => (line.closest_point_on_segment(point), robot_radius),
+        };
+        let delta = closest - point;
+        let direction = delta.try_normalize(f64::EPSILON).unwrap_or_default();
+        (delta.norm() - radius - MARGIN, direction)
+    }
+}
+
+/// A predicted collision.
+struct Collision<'a> {
+    obstacle: &'a Obstacle,
+    /// The time of the collision from now (s).
+    time: f64,
+    /// The direction from the robot to the obstacle.
+    direction: Vector2<f64>,
+}
+
+/// The `CollisionGuard` struct is the last line of defense against collisions, whatever the strategies do.
+/// It simulates the velocity commanded to each robot during a short horizon, against the other robots,
+/// the goal frames and the walls around the field, and changes the velocities that would collide:
+/// the velocity toward the obstacle is removed when the robot can slide along it, otherwise the robot slows down.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::{AllyInfo, EnemyInfo, Pose, Robot, World};
+/// use crabe_guard::collision::CollisionGuard;
+/// use crabe_guard::pipeline::Guard;
+/// use nalgebra::Point2;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, pose: Pose::new(Point2::new(0., 0.), 0.), ..Default::default() });
+/// world.enemies_bot.insert(0, Robot::<EnemyInfo> { id: 0, pose: Pose::new(Point2::new(0.4, 0.), 0.), ..Default::default() });
+///
+/// // The robot faces the enemy, it can't go straight to it
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 2., ..Default::default() });
+/// CollisionGuard::default().guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(commands[&0].forward_velocity < 1.);
+///
+/// // But it can go away from it
+/// commands.insert(0, Command { forward_velocity: -2., ..Default::default() });
+/// CollisionGuard::default().guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert_eq!(commands[&0].forward_velocity, -2.);
+/// ```
+#[derive(Default)]
+pub struct CollisionGuard;
+
+impl CollisionGuard {
+    /// Returns the goal frames and the walls around the field.
+    fn static_obstacles(world: &World) -> Vec<Obstacle> {
+        let geometry = &world.geometry;
+        let mut obstacles = vec![];
+        for (label, goal) in [
+            ("ally goal", &geometry.ally_goal),
+            ("enemy goal", &geometry.enemy_goal),
+        ] {
+            let depth = Vector2::new(goal.line.start.x.signum() * goal.depth, 0.);
+            let (start, end) = (goal.line.start, goal.line.end);
+            for segment in [
+                Line::new(start, start + depth),
+                Line::new(end, end + depth),
+                Line::new(start + depth, end + depth),
+            ] {
+                obstacles.push(Obstacle {
+                    label: label.to_string(),
+                    shape: ObstacleShape::Segment(segment),
+                });
+            }
+        }
+
+        let half_length = geometry.field.length / 2. + geometry.boundary_width;
+        let half_width = geometry.field.width / 2. + geometry.boundary_width;
+        let corners = [
+            Point2::new(-half_length, -half_width),
+            Point2::new(half_length, -half_width),
+            Point2::new(half_length, half_width),
+            Point2::new(-half_length, half_width),
+        ];
+        for (i, corner) in corners.iter().enumerate() {
+            obstacles.push(Obstacle {
+                label: "wall".to_string(),
+                shape: ObstacleShape::Segment(Line::new(*corner, corners[(i + 1) % corners.len()])),
+            });
+        }
+        obstacles
+    }
+
+    /// Returns the robots on the field, the ally robots moving with their commanded velocity.
+    fn robot_obstacles(world: &World, velocities: &HashMap<u8, Vector2<f64>>) -> Vec<Obstacle> {
+        let allies = world.allies_bot.values().map(|robot| Obstacle {
+            label: format!("ally robot {}", robot.id),
+            shape: ObstacleShape::Robot {
+                ally: Some(robot.id),
+                position: robot.pose.position,
+                velocity: velocities
+                    .get(&robot.id)
+                    .copied()
+                    .unwrap_or(robot.velocity.linear),
+            },
+        });
+        let enemies = world.enemies_bot.values().map(|robot| Obstacle {
+            label: format!("enemy robot {}", robot.id),
+            shape: ObstacleShape::Robot {
+                ally: None,
+                position: robot.pose.position,
+                velocity: robot.velocity.linear,
+            },
+        });
+        allies.chain(enemies).collect()
+    }
+
+    /// Returns the first collision of a robot moving at a velocity during the horizon.
+    /// The obstacles the robot already touches are ignored if it moves away from them.
+    fn first_collision<'a>(
+        id: u8,
+        position: &Point2<f64>,
+        velocity: &Vector2<f64>,
+        obstacles: &'a [Obstacle],
+        robot_radius: f64,
+    ) -> Option<Collision<'a>> {
+        let obstacles: Vec<&Obstacle> = obstacles
+            .iter()
+            .filter(|obstacle| !matches!(obstacle.shape, ObstacleShape::Robot { ally: Some(ally), .. } if ally == id))
+            .filter(|obstacle| {
+                let (clearance, direction) = obstacle.clearance(position, 0., robot_radius);
+                clearance >= 0. || velocity.dot(&direction) > 0.
+            })
+            .collect();
+        (0..=STEPS).find_map(|step| {
+            let time = HORIZON * step as f64 / STEPS as f64;
+            let point = position + velocity * time;
+            obstacles.iter().find_map(|obstacle| {
+                let (clearance, direction) = obstacle.clearance(&point, time, robot_radius);
+                (clearance < 0.).then_some(Collision {
+                    obstacle,
+                    time,
+                    direction,
+                })
+            })
+        })
+    }
+
+    /// Returns a velocity avoiding the predicted collision, sliding along the obstacle if possible, otherwise slower.
+    fn avoid(
+        id: u8,
+        position: &Point2<f64>,
+        velocity: Vector2<f64>,
+        collision: &Collision,
+        obstacles: &[Obstacle],
+        robot_radius: f64,
+    ) -> Vector2<f64> {
+        let toward = velocity.dot(&collision.direction);
+        if toward > 0. {
+            let slide = velocity - collision.direction * toward;
+            if slide.norm() > f64::EPSILON
+                && Self::first_collision(id, position, &slide, obstacles, robot_radius).is_none()
+            {
+                debug!(
+                    "robot {} would collide with the {} in {:.2} s, its velocity is redirected",
+                    id, collision.obstacle.label, collision.time
+                );
+                return slide;
+            }
+        }
+
+        // the robot goes during the whole horizon as far as it could go safely at full speed
+        let safe_time = (collision.time - HORIZON / STEPS as f64).max(0.);
+        let slower = velocity * (safe_time / HORIZON);
+        if Self::first_collision(id, position, &slower, obstacles, robot_radius).is_none() {
+            debug!(
+                "robot {} would collide with the {} in {:.2} s, its velocity is reduced",
+                id, collision.obstacle.label, collision.time
+            );
+            return slower;
+        }
+        debug!(
+            "robot {} would collide with the {} in {:.2} s, it is stopped",
+            id, collision.obstacle.label, collision.time
+        );
+        Vector2::zeros()
+    }
+}
+
+impl Guard for CollisionGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        // The commands are in the frame of the robots, the obstacles in the frame of the field
+        let velocities: HashMap<u8, Vector2<f64>> = commands
+            .iter()
+            .filter_map(|(id, command)| {
+                let robot = world.allies_bot.get(id)?;
+                let local = Vector2::new(
+                    command.forward_velocity as f64,
+                    command.left_velocity as f64,
+                );
+                Some((*id, rotate_vector(local, robot.pose.orientation)))
+            })
+            .collect();
+        let mut obstacles = Self::static_obstacles(world);
+        obstacles.extend(Self::robot_obstacles(world, &velocities));
+        let robot_radius = world.geometry.robot_radius;
+
+        for (id, velocity) in velocities {
+            let (Some(robot), Some(command)) = (world.allies_bot.get(&id), commands.get_mut(&id))
+            else {
+                continue;
+            };
+            let position = robot.pose.position;
+            let Some(collision) =
+                Self::first_collision(id, &position, &velocity, &obstacles, robot_radius)
+            else {
+                continue;
+            };
+            let safe = Self::avoid(
+                id,
+                &position,
+                velocity,
+                &collision,
+                &obstacles,
+                robot_radius,
+            );
+            let before = *command;
+            let local = rotate_vector(safe, -robot.pose.orientation);
+            command.forward_velocity = local.x as f32;
+            command.left_velocity = local.y as f32;
+            let reason = format!("collision with the {}", collision.obstacle.label);
+            guard_data.report("CollisionGuard", id, &before, command, &reason);
+        }
+    }
+}
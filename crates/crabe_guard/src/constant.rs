@@ -1,3 +1,21 @@
 pub const MAX_LINEAR: f32 = 2.;
 pub const MAX_ANGULAR: f32 = std::f32::consts::PI;
 pub const MAX_DRIBBLER: f32 = 400.;
+/// Maximum speed of the ball after a kick allowed by the rules (m.s-1).
+pub const MAX_KICK_SPEED: f32 = 6.5;
+/// Margin kept under the speed limit of the referee during a stop (m.s-1).
+pub const STOP_SPEED_MARGIN: f32 = 0.2;
+pub const MAX_LINEAR_ACCELERATION: f32 = 4.;
+pub const MAX_ANGULAR_ACCELERATION: f32 = 4. * std::f32::consts::PI;
+pub const EMERGENCY_DECELERATION: f32 = 6.;
+pub const EMERGENCY_ANGULAR_DECELERATION: f32 = 6. * std::f32::consts::PI;
+/// Period of the main loop, used before a second command is guarded (s).
+pub const FRAME_PERIOD: f32 = 0.016;
+/// The id of our keeper when the game controller doesn't give it, the same as in the decision.
+pub const KEEPER_ID: u8 = 3;
+/// Time after which the position of a robot is checked, about the time it takes to stop (s).
+pub const LOOKAHEAD: f64 = 0.2;
+/// Age of the data of a robot after which the watchdog stops it (s).
+pub const WATCHDOG_TIMEOUT: f64 = 0.2;
+/// Time taken by the watchdog to ramp the commands of a robot down to zero (s).
+pub const WATCHDOG_STOP_TIME: f64 = 0.5;
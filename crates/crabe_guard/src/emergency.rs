@@ -0,0 +1,126 @@
+use crate::pipeline::Guard;
+use crabe_framework::data::output::{Command, CommandMap};
+use crabe_framework::data::tool::{GuardData, GuardRequest, ToolCommands};
+use crabe_framework::data::world::World;
+use log::{info, warn};
+use std::collections::BTreeSet;
+
+/// The `EmergencyGuard` struct lets the operators take robots out of the game without stopping the program.
+/// The disabled robots, and all the robots while the emergency stop is engaged, receive a zero command:
+/// they stop at once, don't dribble, don't charge their capacitor and can't kick.
+///
+/// The robots are enabled and disabled, and the emergency stop engaged and released,
+/// with the requests of the tools. The state is reported to the tools.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap, Kick};
+/// use crabe_framework::data::tool::{GuardData, GuardRequest, ToolCommands};
+/// use crabe_framework::data::world::World;
+/// use crabe_guard::emergency::EmergencyGuard;
+/// use crabe_guard::pipeline::Guard;
+///
+/// let world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// let mut guard = EmergencyGuard::new(&[1]);
+///
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 1., ..Default::default() });
+/// commands.insert(1, Command { forward_velocity: 1., kick: Some(Kick::StraightKick { power: 3. }), ..Default::default() });
+/// let mut guard_data = GuardData::default();
+/// guard.guard(&world, &mut commands, &mut guard_data, &mut ToolCommands::default());
+/// assert_eq!(commands[&0].forward_velocity, 1.);
+/// assert_eq!(commands[&1].forward_velocity, 0.);
+/// assert!(commands[&1].kick.is_none());
+/// assert_eq!(guard_data.disabled_robots, vec![1]);
+///
+/// // The emergency stop stops every robot
+/// let mut tool_commands = ToolCommands::default();
+/// tool_commands.guard_requests.push(GuardRequest::EmergencyStop { engaged: true });
+/// guard.guard(&world, &mut commands, &mut guard_data, &mut tool_commands);
+/// assert_eq!(commands[&0].forward_velocity, 0.);
+/// assert!(guard_data.emergency_stop);
+/// ```
+#[derive(Default)]
+pub struct EmergencyGuard {
+    /// Whether the emergency stop is engaged.
+    emergency_stop: bool,
+    /// The robots disabled by the operators.
+    disabled: BTreeSet<u8>,
+}
+
+impl EmergencyGuard {
+    /// Creates a new `EmergencyGuard` with some robots disabled from the start.
+    pub fn new(disabled: &[u8]) -> Self {
+        Self {
+            emergency_stop: false,
+            disabled: disabled.iter().copied().collect(),
+        }
+    }
+
+    /// Applies a request of the operators.
+    fn handle_request(&mut self, request: GuardRequest) {
+        match request {
+            GuardRequest::SetRobotEnabled { id, enabled: true } => {
+                if self.disabled.remove(&id) {
+                    info!("robot {} is enabled", id);
+                }
+            }
+            GuardRequest::SetRobotEnabled { id, enabled: false } => {
+                if self.disabled.insert(id) {
+                    warn!("robot {} is disabled", id);
+                }
+            }
+            GuardRequest::EmergencyStop { engaged } => {
+                if engaged && !self.emergency_stop {
+                    warn!("emergency stop engaged, all the robots are stopped");
+                } else if !engaged && self.emergency_stop {
+                    info!("emergency stop released");
+                }
+                self.emergency_stop = engaged;
+            }
+        }
+    }
+}
+
+impl Guard for EmergencyGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        tool_commands: &mut ToolCommands,
+    ) {
+        for request in std::mem::take(&mut tool_commands.guard_requests) {
+            self.handle_request(request);
+        }
+
+        // The stopped robots receive a command even when the strategies don't control them
+        let stopped: Vec<u8> = if self.emergency_stop {
+            world
+                .allies_bot
+                .keys()
+                .chain(commands.keys())
+                .copied()
+                .collect()
+        } else {
+            self.disabled
+                .iter()
+                .filter(|id| world.allies_bot.contains_key(id) || commands.contains_key(id))
+                .copied()
+                .collect()
+        };
+        let reason = if self.emergency_stop {
+            "emergency stop"
+        } else {
+            "robot disabled"
+        };
+        for id in stopped {
+            let before = commands.insert(id, Command::default()).unwrap_or_default();
+            guard_data.report("EmergencyGuard", id, &before, &Command::default(), reason);
+        }
+
+        guard_data.emergency_stop = self.emergency_stop;
+        guard_data.disabled_robots = self.disabled.iter().copied().collect();
+    }
+}
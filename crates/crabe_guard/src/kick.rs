@@ -0,0 +1,238 @@
+use crate::constant::MAX_KICK_SPEED;
+use crate::pipeline::Guard;
+use crabe_framework::data::output::{Command, CommandMap, Kick};
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::game_state::{GameState, RunningState};
+use crabe_framework::data::world::World;
+use crabe_math::vectors::rotate_vector;
+use log::{debug, info, warn};
+use nalgebra::Vector2;
+
+/// Distance from the ball under which the robot which can't touch it again can't go toward it (m).
+const DOUBLE_TOUCH_MARGIN: f64 = 0.05;
+
+/// The `KickGuard` struct checks the kicks of the robots: the kick speeds are capped for each kind of kick,
+/// under the ball speed limit of the rules, and with the game controller the robots don't kick
+/// when kicking isn't allowed.
+///
+/// After our free kick or kick off, the robot which took it can't touch the ball again until another robot did:
+/// it can't kick nor dribble, and it can't go toward the ball when it is next to it.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap, Kick};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::game_state::{GameState, RunningState, StoppedState};
+/// use crabe_framework::data::world::World;
+/// use crabe_guard::kick::KickGuard;
+/// use crabe_guard::pipeline::Guard;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// let mut guard = KickGuard::new(6.5, 5.).with_referee(true);
+///
+/// // The kick is too fast
+/// world.data.ref_orders.state = GameState::Running(RunningState::Run);
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { kick: Some(Kick::StraightKick { power: 7. }), ..Default::default() });
+/// guard.guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(matches!(commands[&0].kick, Some(Kick::StraightKick { power }) if power == 6.5));
+///
+/// // The robots can't kick during a stop
+/// world.data.ref_orders.state = GameState::Stopped(StoppedState::Stop);
+/// guard.guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(commands[&0].kick.is_none());
+/// ```
+pub struct KickGuard {
+    /// Maximum speed of the straight kicks (m.s-1).
+    max_straight: f32,
+    /// Maximum speed of the chip kicks (m.s-1).
+    max_chip: f32,
+    /// Whether the game state is used, when we receive the game controller.
+    referee: bool,
+    /// The last robot which touched the ball during our free kick or kick off.
+    kicker: Option<u8>,
+    /// Whether the kicker can't touch the ball again, once the ball is in play.
+    blocked: bool,
+}
+
+impl KickGuard {
+    /// Creates a new `KickGuard` with the maximum speeds of the straight and chip kicks.
+    pub fn new(max_straight: f32, max_chip: f32) -> Self {
+        Self {
+            max_straight,
+            max_chip,
+            referee: false,
+            kicker: None,
+            blocked: false,
+        }
+    }
+
+    /// Uses the game state to refuse the illegal kicks and the double touches.
+    pub fn with_referee(mut self, referee: bool) -> Self {
+        self.referee = referee;
+        self
+    }
+
+    /// Returns true if our robots are allowed to kick the ball in the current game state.
+    fn kick_allowed(world: &World) -> bool {
+        match world.data.ref_orders.state {
+            GameState::Halted(_) | GameState::Stopped(_) => false,
+            GameState::Running(RunningState::FreeKick(team))
+            | GameState::Running(RunningState::KickOff(team))
+            | GameState::Running(RunningState::Penalty(team)) => team == world.team_color,
+            GameState::Running(RunningState::Run) => true,
+        }
+    }
+
+    /// Follows the robot which took our free kick or kick off, until another robot touches the ball.
+    fn update_kicker(&mut self, world: &World) {
+        let last_touch = world
+            .ball
+            .as_ref()
+            .and_then(|ball| ball.last_touch.as_ref())
+            .map(|touch| (touch.team_color == world.team_color, touch.robot_id));
+        match world.data.ref_orders.state {
+            GameState::Running(RunningState::FreeKick(team))
+            | GameState::Running(RunningState::KickOff(team))
+                if team == world.team_color =>
+            {
+                self.blocked = false;
+                if let Some((true, id)) = last_touch {
+                    self.kicker = Some(id);
+                }
+            }
+            GameState::Running(_) => {
+                let Some(kicker) = self.kicker else {
+                    return;
+                };
+                if last_touch.is_some_and(|touch| touch != (true, kicker)) {
+                    if self.blocked {
+                        info!(
+                            "the ball was touched by another robot, robot {} can touch it again",
+                            kicker
+                        );
+                    }
+                    self.kicker = None;
+                    self.blocked = false;
+                } else if !self.blocked {
+                    info!(
+                        "robot {} took the kick, it can't touch the ball again",
+                        kicker
+                    );
+                    self.blocked = true;
+                }
+            }
+            _ => {
+                self.kicker = None;
+                self.blocked = false;
+            }
+        }
+    }
+
+    /// Returns the kick with its speed capped, or None if the speed isn't valid.
+    fn cap(&self, id: u8, kick: Kick) -> Option<Kick> {
+        let (power, max) = match kick {
+            Kick::StraightKick { power } => (power, self.max_straight),
+            Kick::ChipKick { power } => (power, self.max_chip),
+        };
+        if power.is_nan() {
+            warn!("An attempt was made to send NaN as the power of the kick of robot {}. The kick is refused.", id);
+            return None;
+        }
+        let capped = power.clamp(0., max);
+        if capped != power {
+            debug!(
+                "the kick speed {} of robot {} is out of the limits, it is adjusted to {}",
+                power, id, capped
+            );
+        }
+        Some(match kick {
+            Kick::StraightKick { .. } => Kick::StraightKick { power: capped },
+            Kick::ChipKick { .. } => Kick::ChipKick { power: capped },
+        })
+    }
+
+    /// Prevents the robot which took the kick from touching the ball again:
+    /// it can't kick nor dribble, and it can't go toward the ball when it is next to it.
+    fn prevent_double_touch(world: &World, id: u8, command: &mut Command) {
+        if command.kick.is_some() {
+            debug!(
+                "robot {} would touch the ball twice, the kick is refused",
+                id
+            );
+            command.kick = None;
+        }
+        command.dribbler = 0.;
+
+        let (Some(robot), Some(ball)) = (world.allies_bot.get(&id), &world.ball) else {
+            return;
+        };
+        let to_ball = ball.position_2d() - robot.pose.position;
+        let touch_distance =
+            world.geometry.robot_radius + world.geometry.ball_radius + DOUBLE_TOUCH_MARGIN;
+        if to_ball.norm() > touch_distance {
+            return;
+        }
+        let direction = to_ball.try_normalize(f64::EPSILON).unwrap_or_default();
+        let local = Vector2::new(
+            command.forward_velocity as f64,
+            command.left_velocity as f64,
+        );
+        let velocity = rotate_vector(local, robot.pose.orientation);
+        let toward = velocity.dot(&direction);
+        if toward > 0. {
+            debug!(
+                "robot {} would touch the ball twice, it can't go toward it",
+                id
+            );
+            let local = rotate_vector(velocity - direction * toward, -robot.pose.orientation);
+            command.forward_velocity = local.x as f32;
+            command.left_velocity = local.y as f32;
+        }
+    }
+}
+
+impl Default for KickGuard {
+    fn default() -> Self {
+        Self::new(MAX_KICK_SPEED, MAX_KICK_SPEED)
+    }
+}
+
+impl Guard for KickGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        let kick_allowed = !self.referee || Self::kick_allowed(world);
+        if self.referee {
+            self.update_kicker(world);
+        }
+
+        for (id, command) in commands.iter_mut() {
+            if command.kick.is_some() && !kick_allowed {
+                debug!(
+                    "robot {} can't kick during {:?}, the kick is refused",
+                    id, world.data.ref_orders.state
+                );
+                let before = *command;
+                command.kick = None;
+                let reason = format!("kick not allowed during {:?}", world.data.ref_orders.state);
+                guard_data.report("KickGuard", *id, &before, command, &reason);
+            }
+
+            let before = *command;
+            command.kick = command.kick.and_then(|kick| self.cap(*id, kick));
+            guard_data.report("KickGuard", *id, &before, command, "kick speed limit");
+
+            if self.blocked && self.kicker == Some(*id) {
+                let before = *command;
+                Self::prevent_double_touch(world, *id, command);
+                guard_data.report("KickGuard", *id, &before, command, "double touch");
+            }
+        }
+    }
+}
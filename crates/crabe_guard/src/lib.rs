@@ -1,3 +1,11 @@
+pub mod acceleration;
+pub mod boundary;
+pub mod collision;
 pub mod constant;
+pub mod emergency;
+pub mod kick;
 pub mod pipeline;
+pub mod robot_count;
+pub mod rules;
 pub mod speed;
+pub mod watchdog;
@@ -1,33 +1,198 @@
+use crate::acceleration::{
+    parse_robot_acceleration, AccelerationGuard, AccelerationLimits, RobotAcceleration,
+};
+use crate::boundary::BoundaryGuard;
+use crate::collision::CollisionGuard;
+use crate::constant::{
+    EMERGENCY_DECELERATION, MAX_ANGULAR, MAX_ANGULAR_ACCELERATION, MAX_DRIBBLER, MAX_KICK_SPEED,
+    MAX_LINEAR, MAX_LINEAR_ACCELERATION, STOP_SPEED_MARGIN, WATCHDOG_STOP_TIME, WATCHDOG_TIMEOUT,
+};
+use crate::emergency::EmergencyGuard;
+use crate::kick::KickGuard;
+use crate::robot_count::RobotCountGuard;
+use crate::rules::RulesGuard;
 use crate::speed::SpeedGuard;
+use crate::watchdog::WatchdogGuard;
 use clap::Args;
 use crabe_framework::component::{Component, GuardComponent};
 use crabe_framework::config::CommonConfig;
 use crabe_framework::data::output::CommandMap;
-use crabe_framework::data::tool::ToolCommands;
+use crabe_framework::data::tool::{GuardData, InterventionCount, ToolCommands};
 use crabe_framework::data::world::World;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
 
+/// The `GuardConfig` struct is used to hold the limits applied by the guards to the commands.
 #[derive(Args)]
-pub struct GuardConfig {}
+pub struct GuardConfig {
+    /// Maximum linear speed of the robots (m.s-1).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_LINEAR)]
+    max_linear: f32,
+    /// Maximum angular speed of the robots (rad.s-1).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_ANGULAR)]
+    max_angular: f32,
+    /// Maximum dribbler speed of the robots (rpm).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_DRIBBLER)]
+    max_dribbler: f32,
+    /// Maximum speed of the straight kicks (m.s-1).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_KICK_SPEED)]
+    max_straight_kick: f32,
+    /// Maximum speed of the chip kicks (m.s-1).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_KICK_SPEED)]
+    max_chip_kick: f32,
+    /// Margin kept under the speed limit of the referee during a stop (m.s-1).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = STOP_SPEED_MARGIN)]
+    stop_speed_margin: f32,
+    /// Maximum linear acceleration of the robots (m.s-2).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_LINEAR_ACCELERATION)]
+    max_linear_acceleration: f32,
+    /// Maximum angular acceleration of the robots (rad.s-2).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = MAX_ANGULAR_ACCELERATION)]
+    max_angular_acceleration: f32,
+    /// Maximum linear jerk of the robots (m.s-3), not limited by default.
+    #[arg(long, value_parser = parse_limit::<f32>)]
+    max_linear_jerk: Option<f32>,
+    /// Maximum angular jerk of the robots (rad.s-3), not limited by default.
+    #[arg(long, value_parser = parse_limit::<f32>)]
+    max_angular_jerk: Option<f32>,
+    /// Acceleration limits of a robot, written as `id=3,linear=2.5,angular=20`, for example for a robot with a worn wheel.
+    /// The limits not given are the ones of all the robots. It can be repeated for several robots.
+    #[arg(long = "robot-acceleration", value_parser = parse_robot_acceleration)]
+    robot_accelerations: Vec<RobotAcceleration>,
+    /// Linear deceleration of the robots asked to stop (m.s-2).
+    #[arg(long, value_parser = parse_limit::<f32>, default_value_t = EMERGENCY_DECELERATION)]
+    emergency_deceleration: f32,
+    /// Age of the vision data of a robot after which it is stopped (s).
+    #[arg(long, value_parser = parse_limit::<f64>, default_value_t = WATCHDOG_TIMEOUT)]
+    watchdog_timeout: f64,
+    /// Time to ramp the commands of a robot without vision data down to zero (s).
+    #[arg(long, value_parser = parse_limit::<f64>, default_value_t = WATCHDOG_STOP_TIME)]
+    watchdog_stop_time: f64,
+    /// Robots disabled from the start, which are stopped until the tools enable them (for example `1,4`).
+    #[arg(long, value_delimiter = ',')]
+    disabled_robots: Vec<u8>,
+}
+
+/// Parses a limit of the guards, which has to be a finite and non-negative number,
+/// so that the guards never clamp the commands between inverted or NaN bounds.
+///
+/// # Example
+/// ```
+/// use crabe_guard::pipeline::parse_limit;
+///
+/// assert_eq!(parse_limit::<f32>("2.5"), Ok(2.5));
+/// assert!(parse_limit::<f32>("-1").is_err());
+/// assert!(parse_limit::<f32>("NaN").is_err());
+/// assert!(parse_limit::<f64>("inf").is_err());
+/// ```
+pub fn parse_limit<T: FromStr + Into<f64> + Copy>(s: &str) -> Result<T, String> {
+    let limit: T = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number {}", s))?;
+    let value: f64 = limit.into();
+    if value.is_finite() && value >= 0. {
+        Ok(limit)
+    } else {
+        Err(format!(
+            "invalid limit {}, expected a finite and non-negative number",
+            s
+        ))
+    }
+}
 
 pub trait Guard {
+    /// Checks and corrects the commands sent to the robots.
+    ///
+    /// # Arguments
+    ///
+    /// * `world`: The current state of the world.
+    /// * `commands`: The commands of the robots, corrected in place.
+    /// * `guard_data`: What the guard reports to the tools.
+    /// * `tools_commands`: The commands sent by the tools.
     fn guard(
         &mut self,
         world: &World,
         commands: &mut CommandMap,
+        guard_data: &mut GuardData,
         tools_commands: &mut ToolCommands,
     );
+
+    /// Called once all the guards checked the commands, with the commands sent to the robots,
+    /// for the guards depending on the commands sent at the previous step.
+    ///
+    /// # Arguments
+    ///
+    /// * `commands`: The commands sent to the robots.
+    fn commands_sent(&mut self, _commands: &CommandMap) {}
 }
 
 pub struct GuardPipeline {
     guards: Vec<Box<dyn Guard>>,
+    /// The number of steps each guard changed the command of each robot, by guard and robot.
+    intervention_counts: BTreeMap<(String, u8), u64>,
+    /// The number of steps guarded since the start.
+    steps: u64,
 }
 
 impl GuardPipeline {
-    pub fn with_config(_guard_cfg: GuardConfig, _common_cfg: &CommonConfig) -> Self {
+    /// Creates a new `GuardPipeline` running the guards in the given order.
+    pub fn new(guards: Vec<Box<dyn Guard>>) -> Self {
         Self {
-            guards: vec![Box::<SpeedGuard>::default()],
+            guards,
+            intervention_counts: BTreeMap::new(),
+            steps: 0,
         }
     }
+
+    pub fn with_config(guard_cfg: GuardConfig, common_cfg: &CommonConfig) -> Self {
+        let speed = SpeedGuard::new(
+            guard_cfg.max_linear,
+            guard_cfg.max_angular,
+            guard_cfg.max_dribbler,
+        )
+        .with_referee(common_cfg.gc)
+        .with_stop_margin(guard_cfg.stop_speed_margin);
+        let limits = AccelerationLimits {
+            max_linear: guard_cfg.max_linear_acceleration,
+            max_angular: guard_cfg.max_angular_acceleration,
+            max_linear_jerk: guard_cfg.max_linear_jerk,
+            max_angular_jerk: guard_cfg.max_angular_jerk,
+            emergency_linear: guard_cfg.emergency_deceleration,
+            ..Default::default()
+        };
+        let acceleration = guard_cfg
+            .robot_accelerations
+            .iter()
+            .fold(AccelerationGuard::new(limits), |acceleration, robot| {
+                acceleration.with_robot_limits(robot.id, robot.limits(limits))
+            });
+
+        // the robots pulled off the field and the robots stopped by the operators are handled first,
+        // the other guards checking their commands like the others
+        let mut guards: Vec<Box<dyn Guard>> = vec![
+            Box::<RobotCountGuard>::default(),
+            Box::new(EmergencyGuard::new(&guard_cfg.disabled_robots)),
+            Box::new(speed),
+            Box::new(WatchdogGuard::new(
+                guard_cfg.watchdog_timeout,
+                guard_cfg.watchdog_stop_time,
+            )),
+        ];
+        // the rules depend on the game state, only known with the game controller
+        if common_cfg.gc {
+            guards.push(Box::<RulesGuard>::default());
+        }
+        guards.push(Box::new(
+            KickGuard::new(guard_cfg.max_straight_kick, guard_cfg.max_chip_kick)
+                .with_referee(common_cfg.gc),
+        ));
+        guards.push(Box::<BoundaryGuard>::default());
+        guards.push(Box::new(acceleration));
+        // the last line of defense, after the commands are final
+        guards.push(Box::<CollisionGuard>::default());
+        Self::new(guards)
+    }
 }
 
 impl Component for GuardPipeline {
@@ -39,10 +204,38 @@ impl GuardComponent for GuardPipeline {
         &mut self,
         world: &World,
         commands: &mut CommandMap,
+        guard_data: &mut GuardData,
         tools_commands: &mut ToolCommands,
     ) {
         self.guards
             .iter_mut()
-            .for_each(|x| x.guard(world, commands, tools_commands));
+            .for_each(|x| x.guard(world, commands, guard_data, tools_commands));
+        self.guards
+            .iter_mut()
+            .for_each(|x| x.commands_sent(commands));
+
+        // a guard changing several fields of a command counts once
+        let intervened: BTreeSet<(&str, u8)> = guard_data
+            .interventions
+            .iter()
+            .map(|intervention| (intervention.guard.as_str(), intervention.robot_id))
+            .collect();
+        for (guard, robot_id) in intervened {
+            *self
+                .intervention_counts
+                .entry((guard.to_string(), robot_id))
+                .or_default() += 1;
+        }
+        self.steps += 1;
+        guard_data.intervention_counts = self
+            .intervention_counts
+            .iter()
+            .map(|((guard, robot_id), count)| InterventionCount {
+                guard: guard.clone(),
+                robot_id: *robot_id,
+                count: *count,
+            })
+            .collect();
+        guard_data.steps = self.steps;
     }
 }
@@ -0,0 +1,158 @@
+use crate::constant::KEEPER_ID;
+use crate::pipeline::Guard;
+use crabe_framework::data::geometry::Geometry;
+use crabe_framework::data::output::{Command, CommandMap};
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::World;
+use crabe_math::vectors::rotate_vector;
+use log::{info, warn};
+use nalgebra::Point2;
+
+/// Speed of the robots driven off the field (m.s-1).
+const PULL_SPEED: f64 = 1.;
+/// Gain of the speed of the robots driven off the field with their distance to the edge (s-1).
+const PULL_GAIN: f64 = 3.;
+/// Distance between a robot driven off the field and the touch line (m).
+const EDGE_MARGIN: f64 = 0.05;
+
+/// The `RobotCountGuard` struct keeps the number of our robots on the field under the maximum allowed
+/// by the referee, which drops with the yellow and red cards, so that we don't commit a too many robots foul.
+///
+/// When we have too many robots, the robots closest to a touch line are pulled off the field, never the keeper:
+/// they are driven beyond the touch line, then they no longer receive commands, until an operator takes them
+/// or the cards expire. The pulled robots are reported so that the decisions leave them out.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::referee::TeamInfo;
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::{AllyInfo, Pose, Robot, World};
+/// use crabe_guard::pipeline::Guard;
+/// use crabe_guard::robot_count::RobotCountGuard;
+/// use nalgebra::Point2;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.data.ally.info = Some(TeamInfo { goalkeeper: 0, max_allowed_bots: Some(1), ..Default::default() });
+/// for (id, y) in [(0, 0.), (1, 2.)] {
+///     world.allies_bot.insert(id, Robot::<AllyInfo> { id, pose: Pose::new(Point2::new(0., y), 0.), ..Default::default() });
+/// }
+///
+/// // The robot 1 is the closest to a touch line, it is driven off the field
+/// let mut commands = CommandMap::new();
+/// commands.insert(1, Command::default());
+/// let mut guard_data = GuardData::default();
+/// RobotCountGuard::default().guard(&world, &mut commands, &mut guard_data, &mut ToolCommands::default());
+/// assert_eq!(guard_data.pulled_robots, vec![1]);
+/// assert!(commands[&1].left_velocity > 0.);
+/// ```
+#[derive(Default)]
+pub struct RobotCountGuard {
+    /// The robots pulled off the field, in the order they were pulled.
+    pulled: Vec<u8>,
+}
+
+impl RobotCountGuard {
+    /// Returns the point out of the field, beyond the closest touch line, where a robot is driven.
+    fn edge(geometry: &Geometry, position: &Point2<f64>) -> Point2<f64> {
+        let y = geometry.field.width / 2. + geometry.robot_radius + EDGE_MARGIN;
+        let half_length = geometry.field.length / 2.;
+        Point2::new(
+            position.x.clamp(-half_length, half_length),
+            y.copysign(position.y),
+        )
+    }
+
+    /// Updates the robots pulled off the field with the number of robots allowed.
+    fn update_pulled(&mut self, world: &World) {
+        let info = world.data.ally.info.as_ref();
+        let allowed = info
+            .and_then(|info| info.max_allowed_bots)
+            .map_or(usize::MAX, |allowed| allowed as usize);
+        let keeper_id = info.map_or(KEEPER_ID, |info| info.goalkeeper as u8);
+
+        // the robots taken off the field by an operator are no longer seen
+        self.pulled.retain(|id| world.allies_bot.contains_key(id));
+        let excess = world.allies_bot.len().saturating_sub(allowed);
+
+        while self.pulled.len() > excess {
+            if let Some(id) = self.pulled.pop() {
+                info!("robot {} can come back on the field", id);
+            }
+        }
+        if self.pulled.len() >= excess {
+            return;
+        }
+        let mut candidates: Vec<(u8, f64)> = world
+            .allies_bot
+            .values()
+            .filter(|robot| robot.id != keeper_id && !self.pulled.contains(&robot.id))
+            .map(|robot| {
+                let position = robot.pose.position;
+                (
+                    robot.id,
+                    (Self::edge(&world.geometry, &position) - position).norm(),
+                )
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        for (id, _) in candidates.into_iter().take(excess - self.pulled.len()) {
+            warn!(
+                "{} robots allowed on the field, robot {} is pulled off the field",
+                allowed, id
+            );
+            self.pulled.push(id);
+        }
+    }
+}
+
+impl Guard for RobotCountGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        self.update_pulled(world);
+
+        for id in &self.pulled {
+            let Some(robot) = world.allies_bot.get(id) else {
+                continue;
+            };
+            let before = commands.get(id).copied().unwrap_or_default();
+            let position = robot.pose.position;
+            if position.y.abs() - world.geometry.robot_radius >= world.geometry.field.width / 2. {
+                // out of the field, the robot no longer receives commands
+                commands.remove(id);
+                guard_data.report(
+                    "RobotCountGuard",
+                    *id,
+                    &before,
+                    &Command::default(),
+                    "pulled off the field",
+                );
+                continue;
+            }
+            let to_edge = Self::edge(&world.geometry, &position) - position;
+            let velocity = to_edge * PULL_GAIN;
+            let velocity = velocity * (PULL_SPEED / velocity.norm()).min(1.);
+            let local = rotate_vector(velocity, -robot.pose.orientation);
+            let command = Command {
+                forward_velocity: local.x as f32,
+                left_velocity: local.y as f32,
+                ..Default::default()
+            };
+            guard_data.report(
+                "RobotCountGuard",
+                *id,
+                &before,
+                &command,
+                "pulled off the field",
+            );
+            commands.insert(*id, command);
+        }
+        guard_data.pulled_robots = self.pulled.clone();
+    }
+}
@@ -0,0 +1,233 @@
+use crate::constant::LOOKAHEAD;
+use crate::pipeline::Guard;
+use crabe_framework::data::geometry::Penalty;
+use crabe_framework::data::output::CommandMap;
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::game_state::{GameState, RunningState, StoppedState};
+use crabe_framework::data::world::World;
+use crabe_math::shape::Line;
+use crabe_math::vectors::rotate_vector;
+use log::debug;
+use nalgebra::{Point2, Vector2};
+
+/// Distance to keep from the ball during the free kicks and kick offs of the enemy team (m).
+const KICK_BALL_DISTANCE: f64 = 0.5;
+/// Distance to keep from the ball placement line, between the ball and the designated position (m).
+const PLACEMENT_DISTANCE: f64 = 0.5;
+/// Distance to keep from the enemy defense area while the game is stopped (m).
+const STOP_DEFENSE_AREA_DISTANCE: f64 = 0.2;
+
+/// An area the center of a robot can't enter.
+#[derive(Debug)]
+enum Zone {
+    /// The points within a distance of a segment, a circle if the segment is a point.
+    Capsule { segment: Line, radius: f64 },
+    /// A rectangle, between its min and max corners.
+    Rectangle { min: Point2<f64>, max: Point2<f64> },
+}
+
+impl Zone {
+    fn circle(center: Point2<f64>, radius: f64) -> Self {
+        Zone::Capsule {
+            segment: Line::new(center, center),
+            radius,
+        }
+    }
+
+    /// Returns the defense area enlarged by a distance.
+    fn defense_area(penalty: &Penalty, offset: f64) -> Self {
+        let area = penalty.enlarged_penalty(offset);
+        let corners = [
+            area.back_line.start,
+            area.back_line.end,
+            area.front_line.start,
+            area.front_line.end,
+        ];
+        Zone::Rectangle {
+            min: Point2::new(
+                corners.iter().map(|p| p.x).fold(f64::INFINITY, f64::min),
+                corners.iter().map(|p| p.y).fold(f64::INFINITY, f64::min),
+            ),
+            max: Point2::new(
+                corners
+                    .iter()
+                    .map(|p| p.x)
+                    .fold(f64::NEG_INFINITY, f64::max),
+                corners
+                    .iter()
+                    .map(|p| p.y)
+                    .fold(f64::NEG_INFINITY, f64::max),
+            ),
+        }
+    }
+
+    /// Returns the signed distance of a point to the border of the zone, negative inside,
+    /// and the direction going out of the zone.
+    fn distance(&self, point: &Point2<f64>) -> (f64, Vector2<f64>) {
+        match self {
+            Zone::Capsule { segment, radius } => {
+                let out = point - segment.closest_point_on_segment(point);
+                let direction = out.try_normalize(f64::EPSILON).unwrap_or(Vector2::x());
+                (out.norm() - radius, direction)
+            }
+            Zone::Rectangle { min, max } => {
+                let sides = [
+                    (min.x - point.x, -Vector2::x()),
+                    (point.x - max.x, Vector2::x()),
+                    (min.y - point.y, -Vector2::y()),
+                    (point.y - max.y, Vector2::y()),
+                ];
+                let (inside, direction) = sides
+                    .into_iter()
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .unwrap_or((0., Vector2::x()));
+                if inside < 0. {
+                    return (inside, direction);
+                }
+                let closest = Point2::new(point.x.clamp(min.x, max.x), point.y.clamp(min.y, max.y));
+                let out = point - closest;
+                (
+                    out.norm(),
+                    out.try_normalize(f64::EPSILON).unwrap_or(direction),
+                )
+            }
+        }
+    }
+
+    /// Limits a velocity so that the robot doesn't enter the zone within the lookahead,
+    /// a robot already in the zone can't go deeper.
+    fn keep_out(&self, position: &Point2<f64>, velocity: &mut Vector2<f64>) {
+        let (distance, out) = self.distance(position);
+        if distance >= 0. && self.distance(&(position + *velocity * LOOKAHEAD)).0 >= 0. {
+            return;
+        }
+        let speed_in = -velocity.dot(&out);
+        let allowed = distance.max(0.) / LOOKAHEAD;
+        if speed_in > allowed {
+            *velocity += out * (speed_in - allowed);
+        }
+    }
+}
+
+/// The `RulesGuard` struct enforces the rules the strategies could break, depending on the game state:
+/// the robots keep their distance to the ball during the stops and the kicks of the enemy team,
+/// they keep away from the ball placement line and out of the enemy defense area.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::game_state::{GameState, StoppedState};
+/// use crabe_framework::data::world::{AllyInfo, Ball, Pose, Robot, World};
+/// use crabe_guard::pipeline::Guard;
+/// use crabe_guard::rules::RulesGuard;
+/// use nalgebra::{Point2, Point3};
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.data.ref_orders.state = GameState::Stopped(StoppedState::Stop);
+/// world.data.ref_orders.min_dist_from_ball = Some(0.5);
+/// world.ball = Some(Ball { position: Point3::new(0.8, 0., 0.), ..Default::default() });
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, pose: Pose::new(Point2::new(0., 0.), 0.), ..Default::default() });
+///
+/// // During a stop, the robot can't go to the ball
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 2., ..Default::default() });
+/// RulesGuard::default().guard(&world, &mut commands, &mut GuardData::default(), &mut ToolCommands::default());
+/// assert!(commands[&0].forward_velocity < 2.);
+/// ```
+#[derive(Default)]
+pub struct RulesGuard;
+
+impl RulesGuard {
+    /// Returns the zones the robots can't enter in the current game state.
+    fn forbidden_zones(world: &World) -> Vec<Zone> {
+        let orders = &world.data.ref_orders;
+        let robot_radius = world.geometry.robot_radius;
+        let ours = |team| team == world.team_color;
+        let mut zones = vec![];
+
+        let ball_distance = match orders.state {
+            GameState::Stopped(StoppedState::PrepareKickoff(team))
+            | GameState::Stopped(StoppedState::PreparePenalty(team))
+            | GameState::Stopped(StoppedState::BallPlacement(team))
+                if ours(team) =>
+            {
+                None
+            }
+            GameState::Stopped(_) => orders.min_dist_from_ball.map(|distance| distance as f64),
+            GameState::Running(RunningState::FreeKick(team))
+            | GameState::Running(RunningState::KickOff(team))
+                if !ours(team) =>
+            {
+                Some(KICK_BALL_DISTANCE)
+            }
+            _ => None,
+        };
+        if let (Some(distance), Some(ball)) = (ball_distance, &world.ball) {
+            zones.push(Zone::circle(ball.position_2d(), distance + robot_radius));
+        }
+
+        if let GameState::Stopped(StoppedState::BallPlacement(team)) = orders.state {
+            if let (false, Some(ball), Some(designated_position)) =
+                (ours(team), &world.ball, orders.designated_position)
+            {
+                zones.push(Zone::Capsule {
+                    segment: Line::new(ball.position_2d(), designated_position),
+                    radius: PLACEMENT_DISTANCE + robot_radius,
+                });
+            }
+        }
+
+        let defense_area_distance = match orders.state {
+            GameState::Stopped(_) => STOP_DEFENSE_AREA_DISTANCE,
+            _ => 0.,
+        };
+        zones.push(Zone::defense_area(
+            &world.geometry.enemy_penalty,
+            defense_area_distance + robot_radius,
+        ));
+        zones
+    }
+}
+
+impl Guard for RulesGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        let zones = Self::forbidden_zones(world);
+
+        for (id, command) in commands.iter_mut() {
+            let Some(robot) = world.allies_bot.get(id) else {
+                continue;
+            };
+            let position = robot.pose.position;
+            let local = Vector2::new(
+                command.forward_velocity as f64,
+                command.left_velocity as f64,
+            );
+            let requested = rotate_vector(local, robot.pose.orientation);
+            let mut velocity = requested;
+            for zone in &zones {
+                zone.keep_out(&position, &mut velocity);
+            }
+
+            if velocity != requested {
+                debug!(
+                    "robot {} would enter a forbidden zone during {:?}, its velocity is changed",
+                    id, world.data.ref_orders.state
+                );
+                let before = *command;
+                let local = rotate_vector(velocity, -robot.pose.orientation);
+                command.forward_velocity = local.x as f32;
+                command.left_velocity = local.y as f32;
+                let reason = format!("forbidden zone during {:?}", world.data.ref_orders.state);
+                guard_data.report("RulesGuard", *id, &before, command, &reason);
+            }
+        }
+    }
+}
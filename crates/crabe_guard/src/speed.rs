@@ -1,16 +1,51 @@
-use crate::constant::{MAX_ANGULAR, MAX_DRIBBLER, MAX_LINEAR};
+use crate::constant::{MAX_ANGULAR, MAX_DRIBBLER, MAX_LINEAR, STOP_SPEED_MARGIN};
 use crate::pipeline::Guard;
 use crabe_framework::data::output::CommandMap;
-use crabe_framework::data::tool::ToolCommands;
+use crabe_framework::data::tool::{GuardData, ToolCommands};
 use crabe_framework::data::world::World;
 use nalgebra::Vector2;
 use log::warn;
 use crabe_framework::data::world::game_state::GameState;
 
+/// The `SpeedGuard` struct limits the velocities and the dribbler speed of the robots,
+/// with the speed limit given by the referee for the current game state:
+/// during a halt the robots are stopped and can't kick, during a stop they move slower than the rule speed.
+/// Without the game controller, the game state is ignored and only the maximum speeds are used.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::referee::referee_orders::RefereeOrders;
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::game_state::{GameState, StoppedState};
+/// use crabe_framework::data::world::World;
+/// use crabe_guard::pipeline::Guard;
+/// use crabe_guard::speed::SpeedGuard;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// world.data.ref_orders = RefereeOrders::new(GameState::Stopped(StoppedState::Stop), None);
+/// let mut guard = SpeedGuard::new(2., 3., 400.).with_referee(true).with_stop_margin(0.2);
+///
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 2., angular_velocity: 10., ..Default::default() });
+/// let mut guard_data = GuardData::default();
+/// guard.guard(&world, &mut commands, &mut guard_data, &mut ToolCommands::default());
+/// // 1.5 m/s during a stop, minus the margin
+/// assert!((commands[&0].forward_velocity - 1.3).abs() < 1e-6);
+/// assert_eq!(commands[&0].angular_velocity, 3.);
+/// // The changes are reported to the tools
+/// assert_eq!(guard_data.interventions.len(), 2);
+/// assert_eq!(guard_data.interventions[0].original, 2.);
+/// ```
 pub struct SpeedGuard {
     max_linear: f32,
     max_angular: f32,
     max_dribbler: f32,
+    /// Margin kept under the speed limit of the referee during a stop (m.s-1).
+    stop_margin: f32,
+    /// Whether the limits of the referee are used, when we receive the game controller.
+    referee: bool,
 }
 
 impl SpeedGuard {
@@ -18,18 +53,41 @@ impl SpeedGuard {
         Self {
             max_linear,
             max_angular,
-            max_dribbler
+            max_dribbler,
+            stop_margin: STOP_SPEED_MARGIN,
+            referee: false,
+        }
+    }
+
+    /// Uses the limits of the referee for the current game state.
+    pub fn with_referee(mut self, referee: bool) -> Self {
+        self.referee = referee;
+        self
+    }
+
+    /// Uses another margin under the speed limit of the referee during a stop.
+    pub fn with_stop_margin(mut self, stop_margin: f32) -> Self {
+        self.stop_margin = stop_margin;
+        self
+    }
+
+    /// Returns the maximum linear speed in the current game state.
+    fn max_speed(&self, world: &World) -> f32 {
+        if !self.referee {
+            return self.max_linear;
+        }
+        let speed_limit = world.data.ref_orders.speed_limit;
+        match world.data.ref_orders.state {
+            GameState::Halted(_) => 0.,
+            GameState::Stopped(_) => (speed_limit - self.stop_margin).clamp(0., self.max_linear),
+            GameState::Running(_) => speed_limit.min(self.max_linear),
         }
     }
 }
 
 impl Default for SpeedGuard {
     fn default() -> Self {
-        Self {
-            max_linear: MAX_LINEAR,
-            max_angular: MAX_ANGULAR,
-            max_dribbler: MAX_DRIBBLER,
-        }
+        Self::new(MAX_LINEAR, MAX_ANGULAR, MAX_DRIBBLER)
     }
 }
 
@@ -38,57 +96,61 @@ impl Guard for SpeedGuard {
         &mut self,
         world: &World,
         commands: &mut CommandMap,
+        guard_data: &mut GuardData,
         _tool_commands: &mut ToolCommands,
     ) {
-        commands.iter_mut().for_each(|(_id, command)| {
+        let halted = self.referee && matches!(world.data.ref_orders.state, GameState::Halted(_));
+        let max_speed = self.max_speed(world);
+        commands.iter_mut().for_each(|(id, command)| {
+            let before = *command;
             // Replacing any NaN values that might be computed to 0.
             // nalgebra docs mention you shouldn't compare with f32::NaN and should use the .is_nan() method instead
             if command.forward_velocity.is_nan() {
                 warn!("An attempt was made to send NaN instead of a valid value in forward_velocity. It has been adjusted to 0.");
                 command.forward_velocity = 0.;
-            } 
+            }
             if command.left_velocity.is_nan() {
                 warn!("An attempt was made to send NaN instead of a valid value in left_velocity. It has been adjusted to 0.");
                 command.left_velocity = 0.;
             }
 
-            let direction = Vector2::new(command.forward_velocity, command.left_velocity);
-            let mut max_speed = self.max_linear;
-            match world.data.ref_orders.state {
-                GameState::Stopped(_) => {
-                    max_speed = 1.;// world.data.ref_orders.speed_limit;
-                }
-                _ => {}
+            if command.angular_velocity.is_nan() {
+                warn!("An attempt was made to send NaN instead of a valid value in angular_velocity. It has been adjusted to 0.");
+                command.angular_velocity = 0.;
             }
+            if command.dribbler.is_nan() {
+                warn!("An attempt was made to send NaN instead of a valid value in dribbler. It has been adjusted to 0.");
+                command.dribbler = 0.;
+            }
+            guard_data.report("SpeedGuard", *id, &before, command, "NaN value");
 
+            let before = *command;
+            let direction = Vector2::new(command.forward_velocity, command.left_velocity);
             if direction.norm() > max_speed {
                 let direction_normalized = direction.normalize() * max_speed;
                 command.forward_velocity = direction_normalized.x;
                 command.left_velocity = direction_normalized.y;
             }
 
-
-            
-
-            if command.angular_velocity.is_nan() {
-                warn!("An attempt was made to send NaN instead of a valid value in angular_velocity. It has been adjusted to 0.");
-                command.angular_velocity = command
-                    .angular_velocity
-                    .clamp(-self.max_angular, self.max_angular);
-            }
-
-
-            if command.dribbler.is_nan() {
-                warn!("An attempt was made to send NaN instead of a valid value in dribbler. It has been adjusted to 0.");
-                command.dribbler = 0.;
-            }
+            let max_angular = if halted { 0. } else { self.max_angular };
+            command.angular_velocity = command
+                .angular_velocity
+                .clamp(-max_angular, max_angular);
 
             if command.dribbler > self.max_dribbler {
                 warn!("An attempt was made to send a dribbler speed higher than the maximum allowed. It has been adjusted to the maximum allowed value. Wich is {}", self.max_dribbler);
                 command.dribbler = self.max_dribbler;
             }
+            let reason = if halted { "halt" } else { "speed limit" };
+            guard_data.report("SpeedGuard", *id, &before, command, reason);
 
-
+            // Nothing moves during a halt
+            if halted {
+                let before = *command;
+                command.dribbler = 0.;
+                command.kick = None;
+                guard_data.report("SpeedGuard", *id, &before, command, "halt");
+            }
         });
     }
 }
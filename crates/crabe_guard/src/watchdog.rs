@@ -0,0 +1,152 @@
+use crate::constant::{WATCHDOG_STOP_TIME, WATCHDOG_TIMEOUT};
+use crate::pipeline::Guard;
+use chrono::{DateTime, Utc};
+use crabe_framework::data::output::CommandMap;
+use crabe_framework::data::tool::{GuardData, ToolCommands};
+use crabe_framework::data::world::World;
+use log::{info, warn};
+
+/// The `WatchdogGuard` struct stops the robots when their data is too old, like when the vision stops
+/// sending packets or a robot isn't seen anymore by the cameras, instead of letting the strategies
+/// drive them blindly until the filter removes them.
+/// Once the data of a robot is older than the timeout, its commands are ramped down to zero during the stop time
+/// and it can't kick. The robots stopped and the loss of the vision are reported to the tools.
+///
+/// # Example
+/// ```
+/// use crabe_framework::config::CommonConfig;
+/// use crabe_framework::data::output::{Command, CommandMap};
+/// use crabe_framework::data::tool::{GuardData, ToolCommands};
+/// use crabe_framework::data::world::{AllyInfo, Robot, World};
+/// use crabe_guard::pipeline::Guard;
+/// use crabe_guard::watchdog::WatchdogGuard;
+///
+/// let mut world = World::with_config(&CommonConfig { yellow: false, real: false, gc: false });
+/// // The robot wasn't seen for a long time
+/// world.allies_bot.insert(0, Robot::<AllyInfo> { id: 0, ..Default::default() });
+///
+/// let mut commands = CommandMap::new();
+/// commands.insert(0, Command { forward_velocity: 1., ..Default::default() });
+/// let mut guard_data = GuardData::default();
+/// WatchdogGuard::new(0.2, 0.5).guard(&world, &mut commands, &mut guard_data, &mut ToolCommands::default());
+/// assert_eq!(commands[&0].forward_velocity, 0.);
+/// assert!(guard_data.vision_lost);
+/// assert_eq!(guard_data.stale_robots, vec![0]);
+/// ```
+pub struct WatchdogGuard {
+    /// Age of the data after which a robot is stopped (s).
+    timeout: f64,
+    /// Time to ramp the commands of a stopped robot down to zero (s).
+    stop_time: f64,
+    /// Whether the vision was lost at the previous step, to log the changes only.
+    vision_lost: bool,
+    /// The robots stopped at the previous step, to log the changes only.
+    stale_robots: Vec<u8>,
+}
+
+impl WatchdogGuard {
+    /// Creates a new `WatchdogGuard` with the age after which the robots are stopped
+    /// and the time to stop them, in seconds.
+    pub fn new(timeout: f64, stop_time: f64) -> Self {
+        Self {
+            timeout,
+            stop_time,
+            vision_lost: false,
+            stale_robots: vec![],
+        }
+    }
+
+    /// Returns the age of a data in seconds.
+    fn age(now: DateTime<Utc>, timestamp: DateTime<Utc>) -> f64 {
+        (now - timestamp).num_milliseconds() as f64 / 1000.
+    }
+
+    /// Returns the ratio of its command a robot can execute, from 1 with fresh data down to 0.
+    fn ratio(&self, age: f64) -> f64 {
+        if self.stop_time <= 0. {
+            return if age > self.timeout { 0. } else { 1. };
+        }
+        (1. - (age - self.timeout) / self.stop_time).clamp(0., 1.)
+    }
+
+    /// Logs the robots which were stopped or which came back.
+    fn log_changes(&mut self, vision_lost: bool, stale_robots: &[u8]) {
+        if vision_lost != self.vision_lost {
+            if vision_lost {
+                warn!(
+                    "no vision data for {} s, the robots are stopped",
+                    self.timeout
+                );
+            } else {
+                info!("the vision is back");
+            }
+            self.vision_lost = vision_lost;
+        }
+        for id in stale_robots
+            .iter()
+            .filter(|id| !self.stale_robots.contains(id))
+        {
+            warn!("the data of robot {} is too old, it is stopped", id);
+        }
+        for id in self
+            .stale_robots
+            .iter()
+            .filter(|id| !stale_robots.contains(id))
+        {
+            info!("the robot {} is seen again", id);
+        }
+        self.stale_robots = stale_robots.to_vec();
+    }
+}
+
+impl Default for WatchdogGuard {
+    fn default() -> Self {
+        Self::new(WATCHDOG_TIMEOUT, WATCHDOG_STOP_TIME)
+    }
+}
+
+impl Guard for WatchdogGuard {
+    fn guard(
+        &mut self,
+        world: &World,
+        commands: &mut CommandMap,
+        guard_data: &mut GuardData,
+        _tool_commands: &mut ToolCommands,
+    ) {
+        let now = Utc::now();
+        let last_vision = world
+            .allies_bot
+            .values()
+            .map(|robot| robot.timestamp)
+            .chain(world.enemies_bot.values().map(|robot| robot.timestamp))
+            .chain(world.ball.iter().map(|ball| ball.timestamp))
+            .max();
+        let vision_lost = last_vision.map_or(true, |last| Self::age(now, last) > self.timeout);
+
+        let mut stale_robots = vec![];
+        for (id, command) in commands.iter_mut() {
+            // a robot removed from the world isn't seen for a long time already
+            let ratio = world
+                .allies_bot
+                .get(id)
+                .map_or(0., |robot| self.ratio(Self::age(now, robot.timestamp)));
+            if ratio >= 1. {
+                continue;
+            }
+            stale_robots.push(*id);
+            let before = *command;
+            let ratio = ratio as f32;
+            command.forward_velocity *= ratio;
+            command.left_velocity *= ratio;
+            command.angular_velocity *= ratio;
+            command.dribbler *= ratio;
+            command.kick = None;
+            guard_data.report("WatchdogGuard", *id, &before, command, "stale vision data");
+        }
+        stale_robots.sort();
+
+        self.log_changes(vision_lost, &stale_robots);
+        guard_data.vision_lost = vision_lost;
+        guard_data.stale_robots = stale_robots;
+    }
+}
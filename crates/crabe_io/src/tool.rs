@@ -1,5 +1,7 @@
 mod config;
+mod console;
 mod server;
 
 pub use config::ToolConfig;
+pub use console::Console;
 pub use server::ToolServer;
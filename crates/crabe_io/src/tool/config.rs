@@ -4,4 +4,7 @@ use clap::Args;
 pub struct ToolConfig {
     #[arg(long, default_value_t = 10400)]
     pub tool_port: u16,
+    /// Reads the commands typed in the terminal, to stop or disable the robots from the keyboard.
+    #[arg(long)]
+    pub console: bool,
 }
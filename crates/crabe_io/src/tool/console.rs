@@ -0,0 +1,87 @@
+use crabe_framework::data::tool::GuardRequest;
+use flume::{unbounded, Receiver};
+use log::{error, info, warn};
+use std::io::BufRead;
+use std::thread;
+
+const HELP: &str = "console commands: `stop` engages the emergency stop, `go` releases it, \
+`disable <id>...` and `enable <id>...` disable and enable robots";
+
+/// The `Console` struct reads the commands typed in the terminal, to stop the robots from the keyboard
+/// when no tool is connected.
+///
+/// The standard input is read by a thread, which can't be stopped while it waits for a line:
+/// it is left running when the program stops.
+pub struct Console {
+    rx: Receiver<GuardRequest>,
+}
+
+impl Console {
+    /// Spawns the thread reading the standard input.
+    pub fn spawn() -> Self {
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            info!("{}", HELP);
+            for line in std::io::stdin().lock().lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        error!("failed to read the console: {}", e);
+                        break;
+                    }
+                };
+                match parse_command(&line) {
+                    Ok(requests) => {
+                        if requests
+                            .into_iter()
+                            .any(|request| tx.send(request).is_err())
+                        {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("{}, {}", e, HELP),
+                }
+            }
+        });
+        Self { rx }
+    }
+
+    /// Returns the requests typed since the previous call.
+    pub fn receive(&self) -> Vec<GuardRequest> {
+        self.rx.try_iter().collect()
+    }
+}
+
+/// Parses a line typed in the console into guard requests.
+///
+/// # Arguments
+///
+/// * `line`: The line, like `stop` or `disable 1 4`.
+///
+/// # Returns
+///
+/// The requests, or a message explaining why the line isn't a valid command.
+fn parse_command(line: &str) -> Result<Vec<GuardRequest>, String> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return Ok(vec![]);
+    };
+    let ids = words
+        .map(|word| {
+            word.parse::<u8>()
+                .map_err(|_| format!("invalid robot id `{}`", word))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    match (command, ids.is_empty()) {
+        ("stop", true) => Ok(vec![GuardRequest::EmergencyStop { engaged: true }]),
+        ("go", true) => Ok(vec![GuardRequest::EmergencyStop { engaged: false }]),
+        ("enable" | "disable", false) => Ok(ids
+            .into_iter()
+            .map(|id| GuardRequest::SetRobotEnabled {
+                id,
+                enabled: command == "enable",
+            })
+            .collect()),
+        _ => Err(format!("unknown command `{}`", line.trim())),
+    }
+}
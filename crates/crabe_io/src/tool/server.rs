@@ -1,9 +1,10 @@
 use crate::communication::WebSocketTransceiver;
 use crate::tool::config::ToolConfig;
+use crate::tool::console::Console;
 use crabe_framework::component::{Component, ToolComponent};
 use crabe_framework::config::CommonConfig;
 use crabe_framework::data::output::CommandMap;
-use crabe_framework::data::tool::{ToolCommands, ToolData};
+use crabe_framework::data::tool::{GuardRequest, StrategyRequest, ToolCommands, ToolData};
 use crabe_framework::data::world::World;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -21,10 +22,13 @@ struct ToolMessage {
 #[serde(rename_all = "camelCase", tag = "requestType", content = "payload")]
 enum ToolRequest {
     Commands(#[serde_as(as = "Vec<(_, _)>")] CommandMap),
+    SpawnStrategy(StrategyRequest),
+    Guard(GuardRequest),
 }
 
 pub struct ToolServer {
     websocket: WebSocketTransceiver<ToolRequest, ToolMessage>,
+    console: Option<Console>,
 }
 
 impl ToolServer {
@@ -33,6 +37,7 @@ impl ToolServer {
             websocket: WebSocketTransceiver::spawn(
                 SocketAddrV4::new(Ipv4Addr::LOCALHOST, tool_config.tool_port).into(),
             ),
+            console: tool_config.console.then(Console::spawn),
         }
     }
 }
@@ -55,14 +60,24 @@ impl ToolComponent for ToolServer {
             world: world_data.clone(),
         };
         self.websocket.send(msg);
-        if let Some(request) = self.websocket.receive() {
-            println!("request");
+        let mut tool_commands = ToolCommands::default();
+        // all the pending requests are taken, so that an emergency stop doesn't wait behind the commands
+        while let Some(request) = self.websocket.receive() {
             match request {
-                ToolRequest::Commands(tool_commands) => {
-                    commands.extend(tool_commands);
+                ToolRequest::Commands(robot_commands) => {
+                    commands.extend(robot_commands);
+                }
+                ToolRequest::SpawnStrategy(strategy) => {
+                    tool_commands.strategies.push(strategy);
+                }
+                ToolRequest::Guard(request) => {
+                    tool_commands.guard_requests.push(request);
                 }
             }
         }
-        ToolCommands {}
+        if let Some(console) = &self.console {
+            tool_commands.guard_requests.extend(console.receive());
+        }
+        tool_commands
     }
 }